@@ -5,23 +5,77 @@
 
 #[ink::contract]
 mod polkadot_bridge_complete {
+    use ink::prelude::string::String;
     use ink::prelude::vec::Vec;
+    use ink::scale::Encode;
     use ink::storage::Mapping;
 
     /// Bridge commitment record
     #[derive(Debug, Clone, PartialEq, Eq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
     pub struct BridgeCommitment {
         commitment_hash: [u8; 32],
-        source_chain: u32,  // 0 = Stellar
+        source_chain: u32,  // Chain the funds left: 0 = Stellar, 1 = Polkadot (burns)
         amount: u128,
         timestamp: u64,
         status: CommitmentStatus,
     }
 
+    /// Settings for a chain mints can originate from
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ChainConfig {
+        enabled: bool,
+        /// Minimum mint from this chain; 0 defers to `effective_min_mint`
+        min_amount: u128,
+        name: Vec<u8>,
+    }
+
+    /// Arguments of a `verify_and_mint` call, bundled for composite messages
+    #[derive(Debug, Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct MintParams {
+        proof: ZKProof,
+        commitment_hash: [u8; 32],
+        nullifier_hash: [u8; 32],
+        recipient_hash: [u8; 32],
+        amount: u128,
+        source_chain: u32,
+        pow_nonce: u64,
+    }
+
+    /// Outcome of a successful mint, from `verify_and_mint` or any of its
+    /// variants
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct MintReceipt {
+        /// Amount credited to the recipient, after the fee
+        minted: u128,
+        /// Relayer fee taken from the amount
+        fee: u128,
+        /// Recipient's balance after the mint, or the hashed balance for a
+        /// mint to a recipient hash; batched mints aren't credited until
+        /// `settle_batch`
+        new_balance: u128,
+    }
+
+    /// Where a mint is credited
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum MintTarget {
+        /// The account registered for the proof's recipient hash
+        Registered,
+        /// The recipient hash itself, claimed later with `claim_by_preimage`
+        Hash,
+        /// An account named by the relayer (legacy, not bound by the proof)
+        Account(AccountId),
+    }
+
     /// Commitment status
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
     pub enum CommitmentStatus {
         Pending = 0,
         Minted = 1,
@@ -33,7 +87,277 @@ mod polkadot_bridge_complete {
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     pub struct ZKProof {
         proof: Vec<u8>,
-        public_inputs: Vec<[u8; 32]>, // [commitment, nullifier, recipient_hash]
+        public_inputs: Vec<[u8; 32]>, // Ordered per the configured `PublicInput` schema
+    }
+
+    /// Public inputs a proof can be required to bind, as `public_input_schema`
+    /// bits. Bound inputs appear in `ZKProof::public_inputs` in this order,
+    /// followed by the instance's domain separator.
+    pub struct PublicInput;
+
+    impl PublicInput {
+        pub const COMMITMENT: u8 = 1 << 0;
+        pub const NULLIFIER: u8 = 1 << 1;
+        pub const RECIPIENT: u8 = 1 << 2;
+        pub const AMOUNT: u8 = 1 << 3;
+        pub const CHAIN_ID: u8 = 1 << 4;
+        pub const DOMAIN_TAG: u8 = 1 << 5;
+        /// Prover-chosen expiry (ms, big-endian u64 in the low 8 bytes); the
+        /// highest bit, so when bound it is always the last input
+        pub const EXPIRY: u8 = 1 << 6;
+        /// Always bound, whatever else the schema adds
+        pub const BASE: u8 = Self::COMMITMENT | Self::NULLIFIER | Self::RECIPIENT;
+        pub const ALL: u8 =
+            Self::BASE | Self::AMOUNT | Self::CHAIN_ID | Self::DOMAIN_TAG | Self::EXPIRY;
+    }
+
+    /// Proof system a submitted proof was produced with, carried as the
+    /// first byte of the proof so several systems can be accepted at once
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ProofType {
+        Groth16 = 1,
+        Plonk = 2,
+    }
+
+    impl ProofType {
+        /// Read the proof system from a prefixed proof
+        pub fn of(proof: &[u8]) -> Option<ProofType> {
+            match proof.first() {
+                Some(1) => Some(ProofType::Groth16),
+                Some(2) => Some(ProofType::Plonk),
+                _ => None,
+            }
+        }
+    }
+
+    /// Per-operation pause bits stored in `pause_flags`
+    pub struct PauseFlags;
+
+    impl PauseFlags {
+        pub const MINT: u8 = 1 << 0;
+        pub const BURN: u8 = 1 << 1;
+        pub const WITHDRAW: u8 = 1 << 2;
+        pub const TRANSFER: u8 = 1 << 3;
+        pub const ALL: u8 = Self::MINT | Self::BURN | Self::WITHDRAW | Self::TRANSFER;
+    }
+
+    /// Access roles
+    ///
+    /// The owner implicitly holds every role. Any other account must be
+    /// granted a role explicitly; relayers get theirs by registering with
+    /// a deposit, fee managers are appointed by the owner.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Role {
+        Owner,
+        Relayer,
+        FeeManager,
+    }
+
+    /// Admin operation that must wait out the timelock before execution
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum QueuedOperation {
+        UpdateConfig {
+            min_mint_amount: Option<u128>,
+            relayer_fee_bps: Option<u32>,
+            mint_limit_per_window: Option<u128>,
+            window_seconds: Option<u64>,
+        },
+        TransferOwnership(AccountId),
+        SetTimelockDelay(u64),
+    }
+
+    /// A queued operation and the earliest time it can execute
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct QueuedOp {
+        operation: QueuedOperation,
+        ready_at: u64,
+    }
+
+    /// A relayer's blinded commitment to a mint it will reveal later
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PendingMint {
+        relayer: AccountId,
+        ready_at: u64,
+        deadline: u64,
+    }
+
+    /// A Stellar lock reported by a relayer, with the recipient it commits to
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct LockAnnouncement {
+        recipient_hash: [u8; 32],
+        announced_at: u64,
+        alternate_recipient: Option<[u8; 32]>,
+    }
+
+    /// A mint credited to a recipient hash, kept until it is claimed
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct HashedMint {
+        nullifier_hash: [u8; 32],
+        recipient_hash: [u8; 32],
+        /// Locked amount, as bound into the proof
+        amount: u128,
+        source_chain: u32,
+        /// Amount credited to the hash, after the fee
+        minted: u128,
+    }
+
+    /// A delegated admin's daily budget for balance adjustments
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AdminLimits {
+        daily_limit: u128,
+        spent_today: u128,
+        day: u64,
+    }
+
+    /// Nullifier tree depth used by the default constructor (2^32 leaves)
+    const DEFAULT_NULLIFIER_TREE_DEPTH: u8 = 32;
+    /// Deepest supported nullifier tree, so capacity fits in a u64
+    const MAX_NULLIFIER_TREE_DEPTH: u8 = 63;
+
+    /// Token metadata used when none is given at deployment: wrapped XLM,
+    /// which keeps Stellar's 7 decimals at the default settlement rate
+    const DEFAULT_TOKEN_NAME: &str = "Wrapped XLM";
+    const DEFAULT_TOKEN_SYMBOL: &str = "wXLM";
+    const DEFAULT_TOKEN_DECIMALS: u8 = 7;
+
+    /// Source chain id of Stellar, registered at deployment
+    const STELLAR_CHAIN_ID: u32 = 0;
+
+    /// This chain's id in the bridge's chain numbering, bound into the
+    /// domain separator
+    const POLKADOT_CHAIN_ID: u32 = 1;
+
+    /// Settlement rate meaning one destination unit per source unit
+    pub const SETTLEMENT_RATE_ONE: u128 = 1_000_000_000_000;
+
+    /// Length of a global volume window in block-timestamp units (ms)
+    const DAY_MS: u64 = 86_400_000;
+
+    /// How long a mint commitment stays revealable once ready (1 hour)
+    const MINT_COMMIT_WINDOW_MS: u64 = 3_600_000;
+
+    /// Observer notification codes, passed as `event_code` to `notify`
+    pub const NOTIFY_MINT: u8 = 1;
+    pub const NOTIFY_BURN: u8 = 2;
+    pub const NOTIFY_PAUSE: u8 = 3;
+
+    /// Window over which nullifier growth is measured for the adaptive minimum (1 hour)
+    const NULLIFIER_RATE_WINDOW_MS: u64 = 3_600_000;
+
+    /// Number of verification results kept in the proof cache
+    const PROOF_CACHE_SIZE: u32 = 16;
+
+    /// Most mints accepted by one `verify_and_mint_batch` call
+    const MAX_MINT_BATCH: usize = 32;
+
+    /// Most recipients credited by one `settle_batch` call
+    const MAX_SETTLE_BATCH: u32 = 32;
+
+    /// Most commitments returned by one `list_commitments` page
+    const MAX_COMMITMENT_PAGE: u32 = 100;
+
+    /// How long an unminted lock announcement counts as pending (1 day)
+    const ANNOUNCEMENT_TTL_MS: u64 = 86_400_000;
+
+    /// BN254 scalar field modulus r as little-endian u64 limbs:
+    /// 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
+    const BN254_MODULUS: [u64; 4] = [
+        0x43e1_f593_f000_0001,
+        0x2833_e848_79b9_7091,
+        0xb850_45b6_8181_585d,
+        0x3064_4e72_e131_a029,
+    ];
+
+    /// BN254 scalar field element, stored as little-endian u64 limbs (always < r)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Fr([u64; 4]);
+
+    impl Fr {
+        /// Reduce an arbitrary 256-bit value into the field (at most 5 subtractions)
+        fn reduce(mut limbs: [u64; 4]) -> Self {
+            while !limbs_lt(&limbs, &BN254_MODULUS) {
+                let mut borrow = false;
+                for (limb, m) in limbs.iter_mut().zip(BN254_MODULUS.iter()) {
+                    let (diff, b1) = limb.overflowing_sub(*m);
+                    let (diff, b2) = diff.overflowing_sub(borrow as u64);
+                    *limb = diff;
+                    borrow = b1 || b2;
+                }
+            }
+            Fr(limbs)
+        }
+
+        /// Whether this is the zero element
+        pub fn is_zero(&self) -> bool {
+            self.0 == [0u64; 4]
+        }
+
+        /// Canonical big-endian encoding (the bridge's wire convention)
+        pub fn to_bytes_be(&self) -> [u8; 32] {
+            let mut out = [0u8; 32];
+            for (i, limb) in self.0.iter().enumerate() {
+                out[24 - i * 8..32 - i * 8].copy_from_slice(&limb.to_be_bytes());
+            }
+            out
+        }
+    }
+
+    /// `a < b` for little-endian limbs
+    fn limbs_lt(a: &[u64; 4], b: &[u64; 4]) -> bool {
+        for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+            if x != y {
+                return x < y;
+            }
+        }
+        false
+    }
+
+    /// Interpret 32 bytes as a big-endian integer and reduce it into the field.
+    ///
+    /// This is the bridge's convention for every `[u8; 32]` public input
+    /// (commitment, nullifier, recipient_hash): it matches Noir/Barretenberg
+    /// and the Solidity verifier, which serialize field elements big-endian.
+    pub fn bytes_to_field_be(b: &[u8; 32]) -> Fr {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&b[24 - i * 8..32 - i * 8]);
+            *limb = u64::from_be_bytes(chunk);
+        }
+        Fr::reduce(limbs)
+    }
+
+    /// Whether 32 big-endian bytes are already a canonical field element (< r)
+    pub fn is_canonical_field(b: &[u8; 32]) -> bool {
+        bytes_to_field_be(b).to_bytes_be() == *b
+    }
+
+    /// Interpret 32 bytes as a little-endian integer and reduce it into the field.
+    ///
+    /// Provided for provers that serialize little-endian (e.g. arkworks); such
+    /// inputs must be converted with `Fr::to_bytes_be` before submission.
+    pub fn bytes_to_field_le(b: &[u8; 32]) -> Fr {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut chunk = [0u8; 8];
+            chunk.copy_from_slice(&b[i * 8..i * 8 + 8]);
+            *limb = u64::from_le_bytes(chunk);
+        }
+        Fr::reduce(limbs)
     }
 
     /// Contract storage
@@ -55,11 +379,190 @@ mod polkadot_bridge_complete {
         min_mint_amount: u128,
         /// Relayer fee percentage (basis points, e.g., 30 = 0.3%)
         relayer_fee_bps: u32,
-        /// Paused state for emergency
-        paused: bool,
+        /// Paused operations for emergency, as `PauseFlags` bits
+        pause_flags: u8,
+        /// Optional PSP22 token contract backing the wrapped asset
+        token_contract: Option<AccountId>,
+        /// Map: recipient_hash -> AccountId (registered by the recipient)
+        registered_recipients: Mapping<[u8; 32], AccountId>,
+        /// Delay (ms) between queuing and executing admin operations; 0 = no timelock
+        timelock_delay: u64,
+        /// Map: op_id -> queued admin operation
+        queued_ops: Mapping<u32, QueuedOp>,
+        /// Next queued operation id
+        next_op_id: u32,
+        /// Depth of the nullifier tree; capacity is 2^depth nullifiers
+        nullifier_tree_depth: u8,
+        /// Nullifiers inserted so far
+        nullifier_count: u64,
+        /// Native balance the contract account always keeps (existential deposit floor)
+        min_contract_retention: u128,
+        /// Native currency the owner has set aside for `withdraw`; no other
+        /// native balance is redeemable
+        native_reserve: u128,
+        /// Map: hash(proof || public inputs) -> (verified, block number); only
+        /// successful verifications are cached
+        proof_cache: Mapping<[u8; 32], (bool, BlockNumber)>,
+        /// Ring buffer of cached keys, used to evict the oldest entry
+        proof_cache_slots: Mapping<u32, [u8; 32]>,
+        /// Next ring buffer slot to overwrite
+        proof_cache_cursor: u32,
+        /// Number of fresh proof verifications recorded by successful calls
+        verification_count: u64,
+        /// Deposit required to self-register as a relayer
+        relayer_deposit: u128,
+        /// Map: relayer -> whitelisted by the owner
+        relayers: Mapping<AccountId, bool>,
+        /// Map: relayer -> refundable registration deposit
+        relayer_deposits: Mapping<AccountId, u128>,
+        /// Sum of all outstanding relayer deposits, never withdrawable
+        total_relayer_deposits: u128,
+        /// Verification key per proof system
+        verification_keys: Mapping<ProofType, Vec<u8>>,
+        /// Proofs verified per proof system
+        verifications_by_type: Mapping<ProofType, u64>,
+        /// Map: nullifier_hash -> block timestamp of a mint that started but has not finalized
+        in_flight: Mapping<[u8; 32], u64>,
+        /// Map: contract -> approved as a cross-contract callee (PSP22 token, callback receivers)
+        approved_callees: Mapping<AccountId, bool>,
+        /// Permanently shut down: no new mints; burns and withdrawals stay open for recovery
+        shut_down: bool,
+        /// Public inputs a proof must bind, as `PublicInput` bits
+        public_input_schema: u8,
+        /// Domain tag bound when the schema includes `PublicInput::DOMAIN_TAG`
+        domain_tag: [u8; 32],
+        /// Map: day index -> volume minted that day
+        global_daily_volume: Mapping<u64, u128>,
+        /// Cap on volume minted per day across all recipients (0 = unlimited)
+        global_daily_cap: u128,
+        /// When set, mints must go through `commit_mint` / `reveal_mint`
+        commit_reveal_required: bool,
+        /// Minimum wait between `commit_mint` and `reveal_mint`
+        mint_reveal_delay: u64,
+        /// Map: blinded mint hash -> pending commitment
+        pending_mints: Mapping<[u8; 32], PendingMint>,
+        /// Optional monitoring contract notified of significant operations
+        observer: Option<AccountId>,
+        /// Nullifiers per window before the effective minimum starts rising (0 = fixed minimum)
+        nullifier_rate_threshold: u32,
+        /// Start of the current nullifier rate window
+        rate_window_start: u64,
+        /// Nullifiers inserted in the current rate window
+        window_nullifiers: u32,
+        /// Settlement batching window; 0 credits every mint immediately
+        batch_window: u64,
+        /// When the oldest unsettled batched mint arrived
+        batch_opened_at: u64,
+        /// Map: recipient -> batched mint amount awaiting `settle_batch`
+        pending_credits: Mapping<AccountId, u128>,
+        /// Queue of recipients with a pending batched credit, by position
+        pending_recipients: Mapping<u32, AccountId>,
+        /// Position of the next recipient `settle_batch` credits
+        pending_head: u32,
+        /// Position the next queued recipient is stored at
+        pending_tail: u32,
+        /// Reject public inputs that are not canonical field elements
+        strict_inputs: bool,
+        /// Maximum size of the relayer set (0 = unbounded)
+        max_relayers: u32,
+        /// Number of currently registered relayers
+        relayer_count: u32,
+        /// Announced Stellar locks, keyed by commitment hash
+        lock_announcements: Mapping<[u8; 32], LockAnnouncement>,
+        /// Time (ms) after an announcement during which only the committed recipient can be minted to
+        recipient_grace_period: u64,
+        /// Number of commitments recorded by mints and burns, which is also
+        /// the next index in `commitment_keys`
+        commitment_count: u64,
+        /// Announced locks not yet minted, the congestion signal for dynamic fees
+        pending_lock_count: u32,
+        /// Dynamic fee at zero congestion (bps)
+        fee_floor_bps: u32,
+        /// Dynamic fee at or above the congestion target (bps)
+        fee_ceiling_bps: u32,
+        /// Pending lock count at which the fee reaches its ceiling (0 = static fee)
+        congestion_target: u32,
+        /// Balances minted to a recipient hash, awaiting `claim_by_preimage`
+        hashed_balances: Mapping<[u8; 32], u128>,
+        /// Map: commitment hash -> unclaimed mint to a recipient hash
+        hashed_mints: Mapping<[u8; 32], HashedMint>,
+        /// Leading zero bits required of `pow_hash(proof, nullifier, nonce)` (0 = no proof of work)
+        pow_difficulty: u8,
+        /// Accounts allowed to set per-commitment fee overrides
+        fee_managers: Mapping<AccountId, bool>,
+        /// Negotiated relayer fee (bps) for specific commitments
+        commitment_fee_override: Mapping<[u8; 32], u32>,
+        /// When each relayer registered
+        relayer_registered_at: Mapping<AccountId, u64>,
+        /// Time (ms) a new relayer must wait before relaying
+        relayer_activation_delay: u64,
+        /// Ignore relayer-named recipients and always resolve from the registry
+        strict_recipient_binding: bool,
+        /// Delegated admins and their daily budgets; the owner is unlimited
+        admins: Mapping<AccountId, AdminLimits>,
+        /// Account relayer fees are meant to be withdrawn to; defaults to the owner
+        fee_recipient: AccountId,
+        /// Relayer fees taken from mints and not yet withdrawn
+        collected_fees: u128,
+        /// When false, anyone may relay mints (permissionless testnet mode)
+        require_whitelisted_relayer: bool,
+        /// Nominated owner who must call `accept_ownership` to take over
+        pending_owner: Option<AccountId>,
+        /// Cap on volume minted per rolling window (0 = unlimited)
+        mint_limit_per_window: u128,
+        /// Length of the mint rate-limit window, in seconds
+        window_seconds: u64,
+        /// Start of the current mint rate-limit window
+        window_start: u64,
+        /// Volume minted in the current mint rate-limit window
+        minted_in_window: u128,
+        /// Map: account -> blacklisted; balances of blacklisted accounts are frozen
+        blacklisted: Mapping<AccountId, bool>,
+        /// Map: (owner, spender) -> amount the spender may move with `transfer_from`
+        allowances: Mapping<(AccountId, AccountId), u128>,
+        /// PSP22 token name
+        name: String,
+        /// PSP22 token symbol
+        symbol: String,
+        /// PSP22 token decimals
+        decimals: u8,
+        /// Set while a guarded message runs, to reject re-entrant calls
+        locked: bool,
+        /// Map: insertion index -> commitment hash, for enumerating `commitments`
+        commitment_keys: Mapping<u32, [u8; 32]>,
+        /// Map: burn commitment -> Stellar account it pays out to
+        burn_destinations: Mapping<[u8; 32], [u8; 32]>,
+        /// Map: source chain id -> configuration; mints from unregistered chains are rejected
+        chain_configs: Mapping<u32, ChainConfig>,
+        /// Blake2(contract account || `POLKADOT_CHAIN_ID`), bound into every Groth16 proof
+        domain_separator: [u8; 32],
+        /// Map: relayer -> self-registered with a deposit; kept apart from
+        /// the owner's whitelist in `relayers`
+        bonded_relayers: Mapping<AccountId, bool>,
+        /// Whether accounts may self-register as relayers (off by default)
+        self_registration_open: bool,
     }
 
+    /// Layout version reported in bits 48..56 of `status_word`
+    pub const STATUS_WORD_VERSION: u8 = 1;
+    /// Net supply is reported in `status_word` in units of 2^48
+    pub const STATUS_SUPPLY_SHIFT: u32 = 48;
+
+    /// Version of the event definitions below, reported in `BridgeInitialized`.
+    /// Bump it whenever an event's fields or topics change.
+    pub const EVENT_SCHEMA_VERSION: u16 = 2;
+
     /// Events
+    #[ink(event)]
+    pub struct BridgeInitialized {
+        #[ink(topic)]
+        owner: AccountId,
+        min_mint_amount: u128,
+        relayer_fee_bps: u32,
+        token_contract: Option<AccountId>,
+        schema_version: u16,
+    }
+
     #[ink(event)]
     pub struct FundsMinted {
         #[ink(topic)]
@@ -76,6 +579,194 @@ mod polkadot_bridge_complete {
         sender: AccountId,
         amount: u128,
         destination_commitment: [u8; 32],
+        /// Stellar ed25519 public key the reverse relayer pays out to,
+        /// indexed so relayers can route without decoding the commitment;
+        /// zero when the recipient is only bound in the commitment
+        #[ink(topic)]
+        destination_address: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct RecipientRegistered {
+        #[ink(topic)]
+        recipient_hash: [u8; 32],
+        #[ink(topic)]
+        recipient: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OperationQueued {
+        #[ink(topic)]
+        op_id: u32,
+        operation: QueuedOperation,
+        ready_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct QueuedOpExecuted {
+        #[ink(topic)]
+        op_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct QueuedOpCancelled {
+        #[ink(topic)]
+        op_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct FundsWithdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct BalanceAdjusted {
+        #[ink(topic)]
+        account: AccountId,
+        old: u128,
+        new: u128,
+        reason: String,
+    }
+
+    #[ink(event)]
+    pub struct RelayerRegistered {
+        #[ink(topic)]
+        relayer: AccountId,
+        deposit: u128,
+    }
+
+    #[ink(event)]
+    pub struct RelayerDeregistered {
+        #[ink(topic)]
+        relayer: AccountId,
+        refunded: u128,
+    }
+
+    /// A batch item failed; `reason` is the error's `reason_code`
+    #[ink(event)]
+    pub struct MintFailed {
+        reason: u8,
+        #[ink(topic)]
+        nullifier_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct FundsMintedToHash {
+        #[ink(topic)]
+        commitment_hash: [u8; 32],
+        #[ink(topic)]
+        recipient_hash: [u8; 32],
+        amount: u128,
+        nullifier_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct HashedBalanceClaimed {
+        #[ink(topic)]
+        recipient_hash: [u8; 32],
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: u128,
+    }
+
+    #[ink(event)]
+    pub struct MintSwapped {
+        #[ink(topic)]
+        recipient: AccountId,
+        #[ink(topic)]
+        dex: AccountId,
+        amount_in: u128,
+        amount_out: u128,
+    }
+
+    /// Every factor of a mint's conversion, for reconciling both legs:
+    /// `source_amount * rate / SETTLEMENT_RATE_ONE == dest_amount + fee`.
+    /// Amounts are not converted between chains, so `rate` is always
+    /// `SETTLEMENT_RATE_ONE`.
+    #[ink(event)]
+    pub struct SettlementComputed {
+        #[ink(topic)]
+        commitment_hash: [u8; 32],
+        source_amount: u128,
+        dest_amount: u128,
+        rate: u128,
+        fee: u128,
+    }
+
+    /// A verification key was set; relayers watch `key_hash` for circuit rotations
+    #[ink(event)]
+    pub struct VerificationKeyUpdated {
+        #[ink(topic)]
+        proof_type: ProofType,
+        key_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    /// PSP22 transfer between accounts
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: u128,
+    }
+
+    /// PSP22 allowance change
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: u128,
+    }
+
+    /// A batch item replayed an already-used nullifier
+    #[ink(event)]
+    pub struct DoubleSpendAttempt {
+        #[ink(topic)]
+        nullifier_hash: [u8; 32],
+        recipient_hash: [u8; 32],
+        #[ink(topic)]
+        caller: AccountId,
+    }
+
+    /// Circulating wrapped supply after a mint, burn or adjustment
+    #[ink(event)]
+    pub struct SupplyChanged {
+        supply: u128,
+    }
+
+    /// Operations newly paused, as `PauseFlags` bits
+    #[ink(event)]
+    pub struct Paused {
+        flags: u8,
+    }
+
+    /// Operations newly resumed, as `PauseFlags` bits
+    #[ink(event)]
+    pub struct Unpaused {
+        flags: u8,
     }
 
     #[ink(event)]
@@ -88,7 +779,7 @@ mod polkadot_bridge_complete {
     }
 
     /// Errors
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
     pub enum BridgeError {
         Unauthorized,
@@ -100,317 +791,6526 @@ mod polkadot_bridge_complete {
         AmountTooLow,
         InsufficientBalance,
         ArithmeticOverflow,
+        InvalidRecipient,
+        RecipientNotRegistered,
+        RecipientAlreadyRegistered,
+        TimelockRequired,
+        QueuedOpNotFound,
+        QueuedOpNotReady,
+        NullifierTreeFull,
+        RetentionFloorReached,
+        TransferFailed,
+        InsufficientDeposit,
+        AlreadyRegistered,
+        NotRelayer,
+        UnsupportedProofType,
+        CalleeNotApproved,
+        BridgeShutDown,
+        IncompletePublicInputs,
+        GlobalDailyCapExceeded,
+        CommitRevealRequired,
+        MintCommitNotFound,
+        MintCommitNotReady,
+        MintCommitExpired,
+        BatchWindowOpen,
+        NonCanonicalInput,
+        RelayerLimitReached,
+        RecipientMismatch,
+        InvalidFeeBounds,
+        NothingToClaim,
+        InsufficientPow,
+        SwapFailed,
+        SlippageExceeded,
+        /// Unused; keeps the reason codes of later variants stable
+        InvalidSettlementRate,
+        RelayerNotYetActive,
+        AdminLimitExceeded,
+        ProofExpired,
+        InvalidVerificationKey,
+        BatchTooLarge,
+        RateLimitExceeded,
+        AccountBlacklisted,
+        InsufficientAllowance,
+        Reentrancy,
+        UnsupportedSourceChain,
+        AnnouncementNotExpired,
+        SelfRegistrationClosed,
+        /// A recognized proof system this build has no verifier for (Plonk)
+        ProofSystemUnavailable,
+        InsufficientReserve,
     }
 
-    impl PolkadotBridgeComplete {
-        /// Constructor
-        #[ink(constructor)]
-        pub fn new(min_mint_amount: u128, relayer_fee_bps: u32) -> Self {
-            Self {
-                owner: Self::env().caller(),
-                total_minted: 0,
-                total_burned: 0,
-                commitments: Mapping::new(),
-                nullifiers: Mapping::new(),
-                balances: Mapping::new(),
-                min_mint_amount,
-                relayer_fee_bps,
-                paused: false,
-            }
+    impl BridgeError {
+        /// Stable numeric code used in `MintFailed` events. Codes are the
+        /// variant's declaration index, so new variants are only ever appended.
+        pub fn reason_code(&self) -> u8 {
+            *self as u8
         }
+    }
 
-        /// Verify ZK proof and mint wrapped tokens
-        #[ink(message)]
-        pub fn verify_and_mint(
-            &mut self,
-            proof: Vec<u8>,
-            commitment_hash: [u8; 32],
-            nullifier_hash: [u8; 32],
-            recipient: AccountId,
-            amount: u128,
-            source_chain: u32,
-        ) -> Result<(), BridgeError> {
-            // Check if paused
-            if self.paused {
-                return Err(BridgeError::ContractPaused);
-            }
+    /// PSP22 errors, as defined by the standard
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PSP22Error {
+        /// Any other failure, carrying the bridge error's name
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
+    }
 
-            // Check minimum amount
-            if amount < self.min_mint_amount {
-                return Err(BridgeError::AmountTooLow);
+    impl From<BridgeError> for PSP22Error {
+        fn from(error: BridgeError) -> Self {
+            match error {
+                BridgeError::InsufficientBalance => PSP22Error::InsufficientBalance,
+                BridgeError::InsufficientAllowance => PSP22Error::InsufficientAllowance,
+                other => PSP22Error::Custom(ink::prelude::format!("{:?}", other)),
             }
+        }
+    }
 
-            // Check if nullifier already used
-            if self.nullifiers.get(&nullifier_hash).unwrap_or(false) {
-                return Err(BridgeError::NullifierUsed);
-            }
+    /// The PSP22 fungible token standard; messages are dispatched under
+    /// `PSP22::` selectors so DEXes and wallets can call the wrapped token
+    /// like any other
+    ///
+    /// ABI change: these replace the bridge's own `transfer`, `approve`,
+    /// `allowance` and `transfer_from` messages. Callers must use the
+    /// `PSP22::` selectors, pass the trailing `data` argument to `transfer`
+    /// and `transfer_from`, and decode `PSP22Error`. A `BridgeError` maps
+    /// through `From<BridgeError>`; `InsufficientAllowance` and
+    /// `InsufficientBalance` keep their own variants.
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        /// Total token supply
+        #[ink(message)]
+        fn total_supply(&self) -> u128;
 
-            // Verify ZK proof
-            let recipient_hash = Self::hash_recipient(&recipient);
-            let is_valid = self.verify_zk_proof(
-                &proof,
-                &commitment_hash,
-                &nullifier_hash,
-                &recipient_hash,
-            );
+        /// Balance of `owner`
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128;
 
-            if !is_valid {
-                self.env().emit_event(ProofVerified {
-                    commitment_hash,
-                    nullifier_hash,
-                    verified: false,
-                });
-                return Err(BridgeError::InvalidProof);
-            }
+        /// How much `spender` may still move on behalf of `owner`
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128;
 
-            // Mark nullifier as used
-            self.nullifiers.insert(nullifier_hash, &true);
+        /// Move `value` from the caller to `to`
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error>;
 
-            // Calculate relayer fee
-            let fee = self.calculate_fee(amount);
-            let mint_amount = amount.checked_sub(fee)
-                .ok_or(BridgeError::ArithmeticOverflow)?;
+        /// Move `value` from `from` to `to` against the caller's allowance
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error>;
 
-            // Mint tokens to recipient
-            let current_balance = self.balances.get(&recipient).unwrap_or(0);
-            let new_balance = current_balance
-                .checked_add(mint_amount)
-                .ok_or(BridgeError::ArithmeticOverflow)?;
-            self.balances.insert(recipient, &new_balance);
+        /// Allow `spender` to move up to `value` of the caller's tokens,
+        /// replacing any previous allowance
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error>;
 
-            // Update total minted
-            self.total_minted = self.total_minted
-                .checked_add(mint_amount)
-                .ok_or(BridgeError::ArithmeticOverflow)?;
+        /// Raise `spender`'s allowance by `delta_value`
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error>;
 
-            // Store commitment
-            let commitment = BridgeCommitment {
-                commitment_hash,
-                source_chain,
-                amount: mint_amount,
-                timestamp: self.env().block_timestamp(),
-                status: CommitmentStatus::Minted,
+        /// Lower `spender`'s allowance by `delta_value`
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error>;
+    }
+
+    /// Optional PSP22 metadata extension
+    #[ink::trait_definition]
+    pub trait PSP22Metadata {
+        /// Token name
+        #[ink(message)]
+        fn token_name(&self) -> Option<String>;
+
+        /// Token symbol
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String>;
+
+        /// Token decimals
+        #[ink(message)]
+        fn token_decimals(&self) -> u8;
+    }
+
+    impl PolkadotBridgeComplete {
+        /// Constructor
+        #[ink(constructor)]
+        pub fn new(min_mint_amount: u128, relayer_fee_bps: u32) -> Self {
+            Self::new_with_config(
+                min_mint_amount,
+                relayer_fee_bps,
+                None,
+                DEFAULT_NULLIFIER_TREE_DEPTH,
+                true,
+            )
+        }
+
+        /// Constructor with the wrapped token's PSP22 metadata
+        #[ink(constructor)]
+        pub fn new_with_metadata(
+            min_mint_amount: u128,
+            relayer_fee_bps: u32,
+            name: String,
+            symbol: String,
+            decimals: u8,
+        ) -> Self {
+            let mut instance = Self::new(min_mint_amount, relayer_fee_bps);
+            instance.name = name;
+            instance.symbol = symbol;
+            instance.decimals = decimals;
+            instance
+        }
+
+        /// Constructor with an optional PSP22 token contract, an explicit
+        /// nullifier tree depth (clamped to `MAX_NULLIFIER_TREE_DEPTH`) and
+        /// whether mints may only be relayed by whitelisted relayers
+        #[ink(constructor)]
+        pub fn new_with_config(
+            min_mint_amount: u128,
+            relayer_fee_bps: u32,
+            token_contract: Option<AccountId>,
+            nullifier_tree_depth: u8,
+            require_whitelisted_relayer: bool,
+        ) -> Self {
+            let owner = Self::env().caller();
+            let mut instance = Self {
+                owner,
+                total_minted: 0,
+                total_burned: 0,
+                commitments: Mapping::new(),
+                nullifiers: Mapping::new(),
+                balances: Mapping::new(),
+                min_mint_amount,
+                relayer_fee_bps,
+                pause_flags: 0,
+                token_contract,
+                registered_recipients: Mapping::new(),
+                timelock_delay: 0,
+                queued_ops: Mapping::new(),
+                next_op_id: 0,
+                nullifier_tree_depth: nullifier_tree_depth.min(MAX_NULLIFIER_TREE_DEPTH),
+                nullifier_count: 0,
+                min_contract_retention: 0,
+                native_reserve: 0,
+                proof_cache: Mapping::new(),
+                proof_cache_slots: Mapping::new(),
+                proof_cache_cursor: 0,
+                verification_count: 0,
+                relayer_deposit: 0,
+                relayers: Mapping::new(),
+                relayer_deposits: Mapping::new(),
+                total_relayer_deposits: 0,
+                verification_keys: Mapping::default(),
+                verifications_by_type: Mapping::default(),
+                in_flight: Mapping::default(),
+                approved_callees: Mapping::default(),
+                shut_down: false,
+                public_input_schema: PublicInput::BASE,
+                domain_tag: [0u8; 32],
+                global_daily_volume: Mapping::default(),
+                global_daily_cap: 0,
+                commit_reveal_required: false,
+                mint_reveal_delay: 0,
+                pending_mints: Mapping::default(),
+                observer: None,
+                nullifier_rate_threshold: 0,
+                rate_window_start: 0,
+                window_nullifiers: 0,
+                batch_window: 0,
+                batch_opened_at: 0,
+                pending_credits: Mapping::default(),
+                pending_recipients: Mapping::default(),
+                pending_head: 0,
+                pending_tail: 0,
+                strict_inputs: false,
+                max_relayers: 0,
+                relayer_count: 0,
+                lock_announcements: Mapping::new(),
+                recipient_grace_period: 0,
+                commitment_count: 0,
+                pending_lock_count: 0,
+                fee_floor_bps: 0,
+                fee_ceiling_bps: 0,
+                congestion_target: 0,
+                hashed_balances: Mapping::new(),
+                hashed_mints: Mapping::new(),
+                pow_difficulty: 0,
+                fee_managers: Mapping::new(),
+                commitment_fee_override: Mapping::new(),
+                relayer_registered_at: Mapping::new(),
+                relayer_activation_delay: 0,
+                strict_recipient_binding: true,
+                admins: Mapping::new(),
+                fee_recipient: owner,
+                collected_fees: 0,
+                require_whitelisted_relayer,
+                pending_owner: None,
+                mint_limit_per_window: 0,
+                window_seconds: 0,
+                window_start: 0,
+                minted_in_window: 0,
+                blacklisted: Mapping::new(),
+                allowances: Mapping::new(),
+                name: String::from(DEFAULT_TOKEN_NAME),
+                symbol: String::from(DEFAULT_TOKEN_SYMBOL),
+                decimals: DEFAULT_TOKEN_DECIMALS,
+                locked: false,
+                commitment_keys: Mapping::new(),
+                burn_destinations: Mapping::new(),
+                chain_configs: Mapping::new(),
+                domain_separator: Self::compute_domain_separator(&Self::env().account_id()),
+                bonded_relayers: Mapping::new(),
+                self_registration_open: false,
             };
-            self.commitments.insert(commitment_hash, &commitment);
 
-            // Emit events
-            self.env().emit_event(ProofVerified {
-                commitment_hash,
-                nullifier_hash,
-                verified: true,
-            });
+            instance.chain_configs.insert(
+                STELLAR_CHAIN_ID,
+                &ChainConfig {
+                    enabled: true,
+                    min_amount: 0,
+                    name: b"Stellar".to_vec(),
+                },
+            );
 
-            self.env().emit_event(FundsMinted {
-                commitment_hash,
-                recipient,
-                amount: mint_amount,
-                nullifier_hash,
+            Self::env().emit_event(BridgeInitialized {
+                owner,
+                min_mint_amount,
+                relayer_fee_bps,
+                token_contract,
+                schema_version: EVENT_SCHEMA_VERSION,
             });
 
-            Ok(())
+            instance
         }
 
-        /// Burn wrapped tokens to bridge back to Stellar
+        /// Verify ZK proof and mint wrapped tokens
+        ///
+        /// Callable by registered relayers and the owner.
+        ///
+        /// The recipient is resolved from the proof's `recipient_hash` through
+        /// the recipient registry, so the relayer has no say over where the
+        /// minted tokens go.
+        ///
+        /// A failed mint reverts, and its `BridgeError` is the whole report.
+        /// Failed items of a best-effort `verify_and_mint_batch` don't revert;
+        /// they emit `MintFailed` with the error's reason code, and
+        /// `DoubleSpendAttempt` when the nullifier was already used.
+        ///
+        /// When a proof-of-work difficulty is set, `pow_nonce` must make
+        /// `pow_hash(proof.proof, nullifier_hash, pow_nonce)` start with that
+        /// many zero bits; this is checked before any proof verification.
+        ///
+        /// `proof.public_inputs` must hold every input the configured schema
+        /// requires, in `PublicInput` order, or the mint fails with
+        /// `IncompletePublicInputs`; inputs that do not match the call
+        /// arguments fail with `InvalidProof`. The inputs are what the proof
+        /// is verified against. When the schema binds `PublicInput::EXPIRY`,
+        /// the prover's expiry is read from the proof and the mint fails with
+        /// `ProofExpired` once `block_timestamp` has passed it.
         #[ink(message)]
-        pub fn burn_and_bridge(
+        #[allow(clippy::too_many_arguments)]
+        pub fn verify_and_mint(
             &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
             amount: u128,
-            destination_commitment: [u8; 32],
-        ) -> Result<(), BridgeError> {
-            let caller = self.env().caller();
+            source_chain: u32,
+            pow_nonce: u64,
+        ) -> Result<MintReceipt, BridgeError> {
+            let result = if !self.check_pow(&proof.proof, &nullifier_hash, pow_nonce) {
+                Err(BridgeError::InsufficientPow)
+            } else if self.commit_reveal_required {
+                Err(BridgeError::CommitRevealRequired)
+            } else {
+                self.with_guard(|this| {
+                    this.try_verify_and_mint(
+                        proof,
+                        commitment_hash,
+                        nullifier_hash,
+                        recipient_hash,
+                        amount,
+                        source_chain,
+                        MintTarget::Registered,
+                    )
+                })
+            };
 
-            // Check if paused
-            if self.paused {
-                return Err(BridgeError::ContractPaused);
-            }
+            self.close_mint(result, nullifier_hash)
+        }
 
-            // Check balance
-            let current_balance = self.balances.get(&caller).unwrap_or(0);
-            if current_balance < amount {
-                return Err(BridgeError::InsufficientBalance);
+        /// Legacy mint to a relayer-named `recipient`, kept for migration
+        ///
+        /// With `strict_recipient_binding` on (the default) `recipient` is
+        /// ignored and the mint resolves through the registry exactly like
+        /// `verify_and_mint`. With it off, `recipient` is credited as given.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn verify_and_mint_legacy(
+            &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            recipient: AccountId,
+            amount: u128,
+            source_chain: u32,
+            pow_nonce: u64,
+        ) -> Result<MintReceipt, BridgeError> {
+            let target = if self.strict_recipient_binding {
+                MintTarget::Registered
+            } else {
+                MintTarget::Account(recipient)
+            };
+            let result = if !self.check_pow(&proof.proof, &nullifier_hash, pow_nonce) {
+                Err(BridgeError::InsufficientPow)
+            } else if self.commit_reveal_required {
+                Err(BridgeError::CommitRevealRequired)
+            } else {
+                self.with_guard(|this| {
+                    this.try_verify_and_mint(
+                        proof,
+                        commitment_hash,
+                        nullifier_hash,
+                        recipient_hash,
+                        amount,
+                        source_chain,
+                        target,
+                    )
+                })
+            };
+
+            self.close_mint(result, nullifier_hash)
+        }
+
+        /// Verify ZK proof and mint to a balance keyed by `recipient_hash`
+        ///
+        /// The recipient's account never appears on-chain until they claim
+        /// the balance with `claim_by_preimage`. The hash need not be
+        /// registered. `pow_nonce` is checked as in `verify_and_mint`.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn verify_and_mint_to_hash(
+            &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+            pow_nonce: u64,
+        ) -> Result<MintReceipt, BridgeError> {
+            let result = if !self.check_pow(&proof.proof, &nullifier_hash, pow_nonce) {
+                Err(BridgeError::InsufficientPow)
+            } else if self.commit_reveal_required {
+                Err(BridgeError::CommitRevealRequired)
+            } else {
+                self.with_guard(|this| {
+                    this.try_verify_and_mint(
+                        proof,
+                        commitment_hash,
+                        nullifier_hash,
+                        recipient_hash,
+                        amount,
+                        source_chain,
+                        MintTarget::Hash,
+                    )
+                })
+            };
+
+            self.close_mint(result, nullifier_hash)
+        }
+
+        /// Claim the mint of `commitment_hash` made to `recipient`'s hash
+        /// into its balance
+        ///
+        /// Must be called by `recipient` itself, with a proof bound to the
+        /// mint's commitment, nullifier and recipient hash exactly as the
+        /// minting proof was, so a leaked account id alone does not release
+        /// the funds. Returns the amount claimed.
+        #[ink(message)]
+        pub fn claim_by_preimage(
+            &mut self,
+            recipient: AccountId,
+            commitment_hash: [u8; 32],
+            proof: ZKProof,
+        ) -> Result<u128, BridgeError> {
+            if self.env().caller() != recipient {
+                return Err(BridgeError::Unauthorized);
             }
+            self.ensure_not_blacklisted(recipient)?;
 
-            // Burn tokens
-            let new_balance = current_balance - amount;
-            self.balances.insert(caller, &new_balance);
+            let recipient_hash = Self::hash_recipient(&recipient);
+            let mint = self
+                .hashed_mints
+                .get(commitment_hash)
+                .filter(|mint| mint.recipient_hash == recipient_hash)
+                .ok_or(BridgeError::NothingToClaim)?;
 
-            // Update total burned
-            self.total_burned = self.total_burned
+            // The expiry was enforced when minting; only the binding matters now
+            let (expected, _) = self.bound_inputs(
+                &proof.public_inputs,
+                commitment_hash,
+                mint.nullifier_hash,
+                recipient_hash,
+                mint.amount,
+                mint.source_chain,
+            );
+            Self::check_public_inputs(&proof.public_inputs, &expected)?;
+            let verified = self.verify_zk_proof_cached(&proof.proof, &proof.public_inputs)?;
+
+            let amount = mint.minted;
+            let hashed_balance = self
+                .hashed_balances
+                .get(recipient_hash)
+                .unwrap_or(0)
+                .checked_sub(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            let balance = self
+                .balances
+                .get(recipient)
+                .unwrap_or(0)
                 .checked_add(amount)
                 .ok_or(BridgeError::ArithmeticOverflow)?;
+            self.hashed_mints.remove(commitment_hash);
+            if hashed_balance == 0 {
+                self.hashed_balances.remove(recipient_hash);
+            } else {
+                self.hashed_balances.insert(recipient_hash, &hashed_balance);
+            }
+            self.balances.insert(recipient, &balance);
+            self.record_verification(verified, &proof.proof);
 
-            // Emit burn event (relayers will process on Stellar)
-            self.env().emit_event(FundsBurned {
-                sender: caller,
+            // Hashed balances aren't PSP22 balances, so this is the mint
+            self.emit_transfer(None, Some(recipient), amount);
+            self.env().emit_event(HashedBalanceClaimed {
+                recipient_hash,
+                recipient,
                 amount,
-                destination_commitment,
             });
 
-            Ok(())
+            Ok(amount)
         }
 
-        /// Internal ZK proof verification
-        fn verify_zk_proof(
-            &self,
-            proof: &[u8],
-            commitment: &[u8; 32],
-            nullifier: &[u8; 32],
-            recipient_hash: &[u8; 32],
-        ) -> bool {
-            // Simplified verification for testnet
-            // In production, this would:
-            // 1. Deserialize the Groth16/Plonk proof
-            // 2. Verify against verification key
-            // 3. Check public inputs match commitment, nullifier, recipient_hash
+        /// Get the unclaimed balance minted to a recipient hash
+        #[ink(message)]
+        pub fn get_hashed_balance(&self, recipient_hash: [u8; 32]) -> u128 {
+            self.hashed_balances.get(recipient_hash).unwrap_or(0)
+        }
 
-            // Basic validation
-            if proof.len() < 32 {
-                return false;
-            }
+        /// Credit batched mints once the batching window has closed
+        ///
+        /// Each recipient gets one balance credit and one `FundsMinted` event
+        /// for the window. Aggregated events carry zero commitment and nullifier
+        /// hashes; the per-mint values are in the commitment records. At most
+        /// `MAX_SETTLE_BATCH` recipients are credited per call, oldest first;
+        /// call again until it returns 0. Returns the number of recipients
+        /// settled.
+        #[ink(message)]
+        pub fn settle_batch(&mut self) -> Result<u32, BridgeError> {
+            self.ensure_role(Role::Relayer)?;
 
-            // Check all inputs are non-zero
-            let zero_hash = [0u8; 32];
-            if commitment == &zero_hash || nullifier == &zero_hash || recipient_hash == &zero_hash {
-                return false;
+            if self.pending_head == self.pending_tail {
+                return Ok(0);
+            }
+            let closes_at = self.batch_opened_at.saturating_add(self.batch_window);
+            if self.env().block_timestamp() < closes_at {
+                return Err(BridgeError::BatchWindowOpen);
             }
 
-            // TODO: Add actual ZK proof verification
-            // This would use a Groth16/Plonk verifier implementation
-            // For testnet, we accept valid-looking proofs
+            let end = self
+                .pending_tail
+                .min(self.pending_head.saturating_add(MAX_SETTLE_BATCH));
+            let settled = end - self.pending_head;
+            while self.pending_head < end {
+                let position = self.pending_head;
+                self.pending_head += 1;
+                let Some(recipient) = self.pending_recipients.take(position) else {
+                    continue;
+                };
+                let amount = self.pending_credits.take(recipient).unwrap_or(0);
+                self.credit_mint(recipient, amount)?;
+                self.env().emit_event(FundsMinted {
+                    commitment_hash: [0u8; 32],
+                    recipient,
+                    amount,
+                    nullifier_hash: [0u8; 32],
+                });
+            }
 
-            true
+            Ok(settled)
         }
 
-        /// Hash recipient account for ZK proof
-        fn hash_recipient(recipient: &AccountId) -> [u8; 32] {
-            use ink::env::hash::{Blake2x256, HashOutput};
-            let mut output = <Blake2x256 as HashOutput>::Type::default();
-            ink::env::hash_bytes::<Blake2x256>(recipient.as_ref(), &mut output);
-            output
-        }
+        /// Commit to a mint before revealing it, so the proof never sits in
+        /// the mempool where another relayer could copy it and win the race
+        ///
+        /// `blinded_hash` is `mint_commit_hash(relayer, proof, nullifier_hash, salt)`.
+        #[ink(message)]
+        pub fn commit_mint(&mut self, blinded_hash: [u8; 32]) -> Result<(), BridgeError> {
+            let ready_at = self
+                .env()
+                .block_timestamp()
+                .checked_add(self.mint_reveal_delay)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            let deadline = ready_at
+                .checked_add(MINT_COMMIT_WINDOW_MS)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
 
-        /// Calculate relayer fee
-        fn calculate_fee(&self, amount: u128) -> u128 {
-            (amount * self.relayer_fee_bps as u128) / 10000
+            self.pending_mints.insert(
+                blinded_hash,
+                &PendingMint {
+                    relayer: self.env().caller(),
+                    ready_at,
+                    deadline,
+                },
+            );
+            Ok(())
         }
 
-        /// Get balance
+        /// Reveal and execute a mint committed to earlier by the same relayer
+        ///
+        /// `pow_nonce` is checked as in `verify_and_mint`.
         #[ink(message)]
-        pub fn balance_of(&self, account: AccountId) -> u128 {
-            self.balances.get(&account).unwrap_or(0)
-        }
+        #[allow(clippy::too_many_arguments)]
+        pub fn reveal_mint(
+            &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+            pow_nonce: u64,
+            salt: [u8; 32],
+        ) -> Result<MintReceipt, BridgeError> {
+            let caller = self.env().caller();
+            let blinded_hash =
+                self.mint_commit_hash(caller, proof.proof.clone(), nullifier_hash, salt);
 
-        /// Check if nullifier is used
-        #[ink(message)]
-        pub fn is_nullifier_used(&self, nullifier_hash: [u8; 32]) -> bool {
-            self.nullifiers.get(&nullifier_hash).unwrap_or(false)
-        }
+            let result = if !self.check_pow(&proof.proof, &nullifier_hash, pow_nonce) {
+                Err(BridgeError::InsufficientPow)
+            } else {
+                self.with_guard(|this| match this.pending_mints.get(blinded_hash) {
+                    None => Err(BridgeError::MintCommitNotFound),
+                    Some(pending) if pending.relayer != caller => {
+                        Err(BridgeError::MintCommitNotFound)
+                    }
+                    Some(pending) if this.env().block_timestamp() < pending.ready_at => {
+                        Err(BridgeError::MintCommitNotReady)
+                    }
+                    Some(pending) if this.env().block_timestamp() > pending.deadline => {
+                        Err(BridgeError::MintCommitExpired)
+                    }
+                    Some(_) => this.try_verify_and_mint(
+                        proof,
+                        commitment_hash,
+                        nullifier_hash,
+                        recipient_hash,
+                        amount,
+                        source_chain,
+                        MintTarget::Registered,
+                    ),
+                })
+            };
+            if result.is_ok() {
+                self.pending_mints.remove(blinded_hash);
+            }
 
-        /// Get commitment details
-        #[ink(message)]
-        pub fn get_commitment(&self, commitment_hash: [u8; 32]) -> Option<BridgeCommitment> {
-            self.commitments.get(&commitment_hash)
+            self.close_mint(result, nullifier_hash)
         }
 
-        /// Get total minted
+        /// Blinded hash a relayer commits to: blake2(relayer || proof || nullifier_hash || salt)
         #[ink(message)]
-        pub fn get_total_minted(&self) -> u128 {
-            self.total_minted
+        pub fn mint_commit_hash(
+            &self,
+            relayer: AccountId,
+            proof: Vec<u8>,
+            nullifier_hash: [u8; 32],
+            salt: [u8; 32],
+        ) -> [u8; 32] {
+            let mut preimage = Vec::with_capacity(32 + proof.len() + 64);
+            preimage.extend_from_slice(relayer.as_ref());
+            preimage.extend_from_slice(&proof);
+            preimage.extend_from_slice(&nullifier_hash);
+            preimage.extend_from_slice(&salt);
+            Self::hash_proof(&preimage)
         }
 
-        /// Get total burned
+        /// Proof-of-work hash:
+        /// blake2(blake2(proof) || nullifier_hash || nonce as little-endian)
+        ///
+        /// The proof is part of the preimage, so work done for one proof can't
+        /// be reused to submit another under the same nullifier.
         #[ink(message)]
-        pub fn get_total_burned(&self) -> u128 {
-            self.total_burned
+        pub fn pow_hash(&self, proof: Vec<u8>, nullifier_hash: [u8; 32], nonce: u64) -> [u8; 32] {
+            Self::pow_digest(&proof, &nullifier_hash, nonce)
         }
 
-        /// Get contract owner
-        #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
-            self.owner
+        fn pow_digest(proof: &[u8], nullifier_hash: &[u8; 32], nonce: u64) -> [u8; 32] {
+            let mut preimage = [0u8; 72];
+            preimage[..32].copy_from_slice(&Self::hash_proof(proof));
+            preimage[32..64].copy_from_slice(nullifier_hash);
+            preimage[64..].copy_from_slice(&nonce.to_le_bytes());
+            Self::hash_proof(&preimage)
         }
 
-        /// Transfer tokens between accounts
-        #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, amount: u128) -> Result<(), BridgeError> {
-            let caller = self.env().caller();
-            let from_balance = self.balances.get(&caller).unwrap_or(0);
+        /// Whether `nonce` meets the configured proof-of-work difficulty
+        fn check_pow(&self, proof: &[u8], nullifier_hash: &[u8; 32], nonce: u64) -> bool {
+            if self.pow_difficulty == 0 {
+                return true;
+            }
 
-            if from_balance < amount {
-                return Err(BridgeError::InsufficientBalance);
+            let hash = Self::pow_digest(proof, nullifier_hash, nonce);
+            let mut zeros = 0u32;
+            for byte in hash {
+                zeros += byte.leading_zeros();
+                if byte != 0 {
+                    break;
+                }
             }
+            zeros >= u32::from(self.pow_difficulty)
+        }
 
-            let to_balance = self.balances.get(&to).unwrap_or(0);
+        /// Clear a failed mint's in-flight marker and pass the result through
+        fn close_mint<T>(
+            &mut self,
+            result: Result<T, BridgeError>,
+            nullifier_hash: [u8; 32],
+        ) -> Result<T, BridgeError> {
+            if result.is_err() {
+                // A failed mint never finalizes, so it must not be reported as
+                // stuck, whether the message then reverts or a batch goes on
+                self.in_flight.remove(nullifier_hash);
+            }
 
-            self.balances.insert(caller, &(from_balance - amount));
-            self.balances.insert(to, &(to_balance
-                .checked_add(amount)
-                .ok_or(BridgeError::ArithmeticOverflow)?));
+            result
+        }
 
-            Ok(())
+        /// Emit `MintFailed`, and `DoubleSpendAttempt` for a replayed
+        /// nullifier, for a batch item that failed
+        ///
+        /// Only a best-effort batch returns `Ok` around a failed mint, so only
+        /// there do these events outlive the call. Anywhere else the error
+        /// reverts the message, events included, and is itself the report.
+        fn report_mint_failure(
+            &self,
+            error: BridgeError,
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+        ) {
+            if error == BridgeError::NullifierUsed {
+                self.env().emit_event(DoubleSpendAttempt {
+                    nullifier_hash,
+                    recipient_hash,
+                    caller: self.env().caller(),
+                });
+            }
+            self.env().emit_event(MintFailed {
+                reason: error.reason_code(),
+                nullifier_hash,
+            });
         }
 
-        /// Admin: Update configuration
+        /// Mint, then swap the minted amount on `dex` for the recipient
+        ///
+        /// Only the recipient may call this: the DEX, route and `min_out`
+        /// decide what their funds become, so no relayer can pick them. The
+        /// relayer checks of `verify_and_mint` still apply to the caller.
+        ///
+        /// The minted balance is handed to the DEX, which is called with
+        /// `swap(recipient, amount_in, swap_calldata)` and must deliver at
+        /// least `min_out` of `out_token` (a PSP22) to the recipient. The
+        /// output is measured as the recipient's `out_token` balance change,
+        /// not taken from the DEX's word. Any failure returns an error, which
+        /// reverts the whole message on-chain, nullifier included. `dex` and
+        /// `out_token` must be approved callees and cannot re-enter the
+        /// bridge. Returns the swap output.
         #[ink(message)]
-        pub fn update_config(
+        pub fn mint_and_swap(
             &mut self,
-            min_mint_amount: Option<u128>,
-            relayer_fee_bps: Option<u32>,
-        ) -> Result<(), BridgeError> {
-            if self.env().caller() != self.owner {
+            params: MintParams,
+            dex: AccountId,
+            out_token: AccountId,
+            swap_calldata: Vec<u8>,
+            min_out: u128,
+        ) -> Result<u128, BridgeError> {
+            let nullifier_hash = params.nullifier_hash;
+
+            // Checks: batched mints aren't credited yet, so there'd be nothing to swap
+            self.ensure_callee_approved(dex)?;
+            self.ensure_callee_approved(out_token)?;
+            if self.batch_window > 0 {
+                return Err(BridgeError::BatchWindowOpen);
+            }
+            let recipient = self
+                .registered_recipients
+                .get(params.recipient_hash)
+                .ok_or(BridgeError::RecipientNotRegistered)?;
+            if self.env().caller() != recipient {
                 return Err(BridgeError::Unauthorized);
             }
 
-            if let Some(min_amount) = min_mint_amount {
-                self.min_mint_amount = min_amount;
-            }
+            // Effects: the mint, then the minted amount moves to the DEX
+            let before = self.balances.get(recipient).unwrap_or(0);
+            let minted = self
+                .verify_and_mint(
+                    params.proof,
+                    params.commitment_hash,
+                    nullifier_hash,
+                    params.recipient_hash,
+                    params.amount,
+                    params.source_chain,
+                    params.pow_nonce,
+                )?
+                .minted;
 
-            if let Some(fee) = relayer_fee_bps {
-                self.relayer_fee_bps = fee;
-            }
+            self.balances.insert(recipient, &before);
+            let dex_balance = self
+                .balances
+                .get(dex)
+                .unwrap_or(0)
+                .checked_add(minted)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            self.balances.insert(dex, &dex_balance);
+            self.emit_transfer(Some(recipient), Some(dex), minted);
 
-            Ok(())
+            // Interaction last, under the lock, with slippage protection
+            let swap = self
+                .with_guard(|_| {
+                    let balance_of = |account| {
+                        Self::psp22_balance_of(out_token, account)
+                            .map_err(|_| BridgeError::SwapFailed)
+                    };
+                    let out_before = balance_of(recipient)?;
+                    Self::invoke_dex(dex, recipient, minted, swap_calldata)
+                        .map_err(|_| BridgeError::SwapFailed)?;
+                    Ok(balance_of(recipient)?.saturating_sub(out_before))
+                })
+                .and_then(|out| {
+                    if out < min_out {
+                        Err(BridgeError::SlippageExceeded)
+                    } else {
+                        Ok(out)
+                    }
+                });
+            let amount_out = match swap {
+                Ok(out) => out,
+                Err(error) => return self.close_mint(Err(error), nullifier_hash),
+            };
+
+            self.env().emit_event(MintSwapped {
+                recipient,
+                dex,
+                amount_in: minted,
+                amount_out,
+            });
+
+            Ok(amount_out)
         }
 
-        /// Admin: Pause contract
+        /// Verify and mint up to `MAX_MINT_BATCH` items in one call
+        ///
+        /// Items are processed in order, each exactly as `verify_and_mint`
+        /// would. With `abort_on_error` the first failing item's error is
+        /// returned, which reverts the whole batch on-chain. Otherwise one bad
+        /// proof doesn't stop the rest: a failed item leaves no storage writes
+        /// behind even though the call succeeds, and one result is returned
+        /// per item, index for index. Each failed item emits `MintFailed`,
+        /// and a replayed nullifier `DoubleSpendAttempt` as well.
         #[ink(message)]
-        pub fn set_paused(&mut self, paused: bool) -> Result<(), BridgeError> {
-            if self.env().caller() != self.owner {
-                return Err(BridgeError::Unauthorized);
+        pub fn verify_and_mint_batch(
+            &mut self,
+            items: Vec<MintParams>,
+            abort_on_error: bool,
+        ) -> Result<Vec<Result<MintReceipt, BridgeError>>, BridgeError> {
+            if items.len() > MAX_MINT_BATCH {
+                return Err(BridgeError::BatchTooLarge);
+            }
+
+            let mut results = Vec::with_capacity(items.len());
+            for item in items {
+                let (nullifier_hash, recipient_hash) = (item.nullifier_hash, item.recipient_hash);
+                let result = self.verify_and_mint(
+                    item.proof,
+                    item.commitment_hash,
+                    item.nullifier_hash,
+                    item.recipient_hash,
+                    item.amount,
+                    item.source_chain,
+                    item.pow_nonce,
+                );
+                if let Err(error) = result {
+                    if abort_on_error {
+                        return Err(error);
+                    }
+                    self.report_mint_failure(error, nullifier_hash, recipient_hash);
+                }
+                results.push(result);
+            }
+            Ok(results)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn try_verify_and_mint(
+            &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+            target: MintTarget,
+        ) -> Result<MintReceipt, BridgeError> {
+            // Only relayers relay (the owner holds every role), unless permissionless
+            if self.require_whitelisted_relayer {
+                self.ensure_role(Role::Relayer)?;
+                self.ensure_relayer_active(self.env().caller())?;
+            }
+
+            // Check if paused
+            self.ensure_not_paused(PauseFlags::MINT)?;
+
+            // Nothing is minted once the bridge is winding down
+            if self.shut_down {
+                return Err(BridgeError::BridgeShutDown);
+            }
+
+            // The backing PSP22 token must be an approved callee
+            if let Some(token) = self.token_contract {
+                self.ensure_callee_approved(token)?;
+            }
+
+            // Only enabled source chains, each with its own minimum amount
+            // (by default the global one, raised while nullifiers grow quickly)
+            let chain = self
+                .chain_configs
+                .get(source_chain)
+                .filter(|chain| chain.enabled)
+                .ok_or(BridgeError::UnsupportedSourceChain)?;
+            let min_amount = if chain.min_amount > 0 {
+                chain.min_amount
+            } else {
+                self.effective_min_mint()
+            };
+            if amount < min_amount {
+                return Err(BridgeError::AmountTooLow);
+            }
+
+            // Check if nullifier already used
+            if self.nullifiers.get(&nullifier_hash).unwrap_or(false) {
+                return Err(BridgeError::NullifierUsed);
+            }
+
+            // Mints and burns share the commitment keyspace; never overwrite
+            if self.commitments.contains(commitment_hash) {
+                return Err(BridgeError::CommitmentAlreadyProcessed);
+            }
+
+            // Resolve the recipient the proof is bound to, unless minting to
+            // the hash itself or to a legacy relayer-named account
+            let recipient = match target {
+                MintTarget::Registered => Some(
+                    self.registered_recipients
+                        .get(&recipient_hash)
+                        .ok_or(BridgeError::RecipientNotRegistered)?,
+                ),
+                MintTarget::Hash => None,
+                MintTarget::Account(account) => Some(account),
+            };
+            if let Some(recipient) = recipient {
+                self.ensure_not_blacklisted(recipient)?;
+            }
+
+            // Announced locks can only be redirected after the grace period
+            self.check_recipient_priority(&commitment_hash, &recipient_hash)?;
+
+            // In strict mode, inputs must already be reduced field elements
+            if self.strict_inputs
+                && ![commitment_hash, nullifier_hash, recipient_hash]
+                    .iter()
+                    .all(is_canonical_field)
+            {
+                return Err(BridgeError::NonCanonicalInput);
+            }
+
+            // Proofs carry their proof system as a one-byte prefix
+            Self::ensure_verifiable(&proof.proof)?;
+
+            // The proof's public inputs must be exactly what the schema binds
+            self.check_bound_inputs(
+                &proof.public_inputs,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            )?;
+
+            // Verify ZK proof (reusing a result from earlier in this block)
+            let verified = match self.verify_zk_proof_cached(&proof.proof, &proof.public_inputs) {
+                Ok(verified) => verified,
+                Err(error) => {
+                    self.env().emit_event(ProofVerified {
+                        commitment_hash,
+                        nullifier_hash,
+                        verified: false,
+                    });
+                    return Err(error);
+                }
+            };
+
+            // Calculate relayer fee
+            let fee = self.calculate_fee(&commitment_hash, amount)?;
+            let mint_amount = amount.checked_sub(fee)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            let collected_fees = self
+                .collected_fees
+                .checked_add(fee)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+
+            // Enforce the global daily volume cap
+            let day = self.current_day();
+            let day_volume = self
+                .global_daily_volume
+                .get(day)
+                .unwrap_or(0)
+                .checked_add(mint_amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            if self.global_daily_cap > 0 && day_volume > self.global_daily_cap {
+                return Err(BridgeError::GlobalDailyCapExceeded);
+            }
+
+            // Enforce the rolling-window mint rate limit
+            let (window_start, minted_in_window) = self.current_mint_window();
+            let minted_in_window = minted_in_window
+                .checked_add(mint_amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            if self.mint_limit_per_window > 0 && minted_in_window > self.mint_limit_per_window {
+                return Err(BridgeError::RateLimitExceeded);
+            }
+
+            // Nothing may fail once the nullifier is marked: a mint that errors
+            // inside a batch isn't reverted, so it must not leave writes behind
+            let batched = self.batch_window > 0 && recipient.is_some();
+            self.ensure_mint_fits(recipient, batched, recipient_hash, mint_amount)?;
+
+            // Mark nullifier as used
+            self.insert_nullifier(nullifier_hash)?;
+            self.in_flight
+                .insert(nullifier_hash, &self.env().block_timestamp());
+            self.global_daily_volume.insert(day, &day_volume);
+            self.window_start = window_start;
+            self.minted_in_window = minted_in_window;
+            self.collected_fees = collected_fees;
+
+            match recipient {
+                // Credited and announced in aggregate by `settle_batch`
+                Some(recipient) if batched => self.queue_batch_credit(recipient, mint_amount)?,
+                Some(recipient) => self.credit_mint(recipient, mint_amount)?,
+                None => {
+                    self.credit_hash(recipient_hash, mint_amount)?;
+                    self.hashed_mints.insert(
+                        commitment_hash,
+                        &HashedMint {
+                            nullifier_hash,
+                            recipient_hash,
+                            amount,
+                            source_chain,
+                            minted: mint_amount,
+                        },
+                    );
+                }
+            }
+
+            // Store commitment
+            let commitment = BridgeCommitment {
+                commitment_hash,
+                source_chain,
+                amount: mint_amount,
+                timestamp: self.env().block_timestamp(),
+                status: CommitmentStatus::Minted,
+            };
+            self.record_commitment(&commitment)?;
+            self.record_verification(verified, &proof.proof);
+
+            // The announced lock is no longer pending
+            if self.lock_announcements.contains(commitment_hash) {
+                self.lock_announcements.remove(commitment_hash);
+                self.pending_lock_count = self.pending_lock_count.saturating_sub(1);
+            }
+
+            // Emit events
+            self.env().emit_event(ProofVerified {
+                commitment_hash,
+                nullifier_hash,
+                verified: true,
+            });
+            self.env().emit_event(SettlementComputed {
+                commitment_hash,
+                source_amount: amount,
+                dest_amount: mint_amount,
+                rate: SETTLEMENT_RATE_ONE,
+                fee,
+            });
+
+            match recipient {
+                Some(recipient) => {
+                    if !batched {
+                        self.env().emit_event(FundsMinted {
+                            commitment_hash,
+                            recipient,
+                            amount: mint_amount,
+                            nullifier_hash,
+                        });
+                    }
+                    self.notify_observer(
+                        NOTIFY_MINT,
+                        (commitment_hash, recipient, mint_amount).encode(),
+                    );
+                }
+                None => self.env().emit_event(FundsMintedToHash {
+                    commitment_hash,
+                    recipient_hash,
+                    amount: mint_amount,
+                    nullifier_hash,
+                }),
+            }
+
+            // Finalized: a lingering in-flight entry would mean a mint stopped halfway
+            self.in_flight.remove(nullifier_hash);
+
+            let new_balance = match recipient {
+                Some(recipient) => self.balances.get(recipient).unwrap_or(0),
+                None => self.hashed_balances.get(recipient_hash).unwrap_or(0),
+            };
+            Ok(MintReceipt {
+                minted: mint_amount,
+                fee,
+                new_balance,
+            })
+        }
+
+        /// Register the caller as the destination for mints bound to `recipient_hash`
+        ///
+        /// The hash must be the caller's own `recipient_hash_of`, either as is
+        /// or reduced into the field for strict-inputs mode, so nobody can
+        /// claim a hash that belongs to another account. A hash can only be
+        /// claimed once; the registering account can release it again with
+        /// `unregister_recipient`.
+        #[ink(message)]
+        pub fn register_recipient(&mut self, recipient_hash: [u8; 32]) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+
+            let own_hash = Self::hash_recipient(&caller);
+            if recipient_hash != own_hash
+                && recipient_hash != bytes_to_field_be(&own_hash).to_bytes_be()
+            {
+                return Err(BridgeError::InvalidRecipient);
+            }
+
+            if let Some(existing) = self.registered_recipients.get(&recipient_hash) {
+                if existing != caller {
+                    return Err(BridgeError::RecipientAlreadyRegistered);
+                }
             }
 
-            self.paused = paused;
+            self.registered_recipients.insert(recipient_hash, &caller);
+
+            self.env().emit_event(RecipientRegistered {
+                recipient_hash,
+                recipient: caller,
+            });
+
             Ok(())
         }
 
-        /// Admin: Transfer ownership
+        /// Release a recipient hash previously registered by the caller
         #[ink(message)]
-        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), BridgeError> {
-            if self.env().caller() != self.owner {
+        pub fn unregister_recipient(&mut self, recipient_hash: [u8; 32]) -> Result<(), BridgeError> {
+            match self.registered_recipients.get(&recipient_hash) {
+                Some(existing) if existing == self.env().caller() => {
+                    self.registered_recipients.remove(&recipient_hash);
+                    Ok(())
+                }
+                Some(_) => Err(BridgeError::Unauthorized),
+                None => Err(BridgeError::RecipientNotRegistered),
+            }
+        }
+
+        /// Convert a prover's 32-byte public input into the canonical big-endian,
+        /// reduced encoding this contract verifies against
+        #[ink(message)]
+        pub fn to_canonical_input(&self, input: [u8; 32], little_endian: bool) -> [u8; 32] {
+            let field = if little_endian {
+                bytes_to_field_le(&input)
+            } else {
+                bytes_to_field_be(&input)
+            };
+            field.to_bytes_be()
+        }
+
+        /// Get the default recipient hash for an account (blake2 of the AccountId)
+        #[ink(message)]
+        pub fn recipient_hash_of(&self, account: AccountId) -> [u8; 32] {
+            Self::hash_recipient(&account)
+        }
+
+        /// Get the account registered for a recipient hash
+        #[ink(message)]
+        pub fn get_registered_recipient(&self, recipient_hash: [u8; 32]) -> Option<AccountId> {
+            self.registered_recipients.get(&recipient_hash)
+        }
+
+        /// Report a Stellar lock, with the proof that will mint it
+        ///
+        /// The proof is checked as `verify_and_mint` checks it, so the
+        /// recorded recipient hash is the one the lock's proof binds rather
+        /// than the relayer's say-so. For `recipient_grace_period` after the
+        /// announcement only that recipient can be minted to; afterwards an
+        /// alternate designated by the recipient is accepted as well, for
+        /// recovery.
+        #[ink(message)]
+        pub fn announce_lock(
+            &mut self,
+            proof: ZKProof,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Relayer)?;
+
+            if self.lock_announcements.contains(commitment_hash)
+                || self.commitments.contains(commitment_hash)
+            {
+                return Err(BridgeError::CommitmentAlreadyProcessed);
+            }
+            if self.nullifiers.get(&nullifier_hash).unwrap_or(false) {
+                return Err(BridgeError::NullifierUsed);
+            }
+
+            Self::ensure_verifiable(&proof.proof)?;
+            self.check_bound_inputs(
+                &proof.public_inputs,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            )?;
+            let verified = self.verify_zk_proof_cached(&proof.proof, &proof.public_inputs)?;
+
+            self.lock_announcements.insert(
+                commitment_hash,
+                &LockAnnouncement {
+                    recipient_hash,
+                    announced_at: self.env().block_timestamp(),
+                    alternate_recipient: None,
+                },
+            );
+            self.pending_lock_count = self.pending_lock_count.saturating_add(1);
+            self.record_verification(verified, &proof.proof);
+            Ok(())
+        }
+
+        /// Let the account registered for an announced lock's recipient hash
+        /// name an alternate recipient, usable once the grace period is over
+        #[ink(message)]
+        pub fn designate_alternate_recipient(
+            &mut self,
+            commitment_hash: [u8; 32],
+            alternate_hash: [u8; 32],
+        ) -> Result<(), BridgeError> {
+            let mut announcement = self
+                .lock_announcements
+                .get(commitment_hash)
+                .ok_or(BridgeError::CommitmentNotFound)?;
+
+            if self.registered_recipients.get(announcement.recipient_hash)
+                != Some(self.env().caller())
+            {
                 return Err(BridgeError::Unauthorized);
             }
 
-            self.owner = new_owner;
+            announcement.alternate_recipient = Some(alternate_hash);
+            self.lock_announcements.insert(commitment_hash, &announcement);
             Ok(())
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+        /// Drop a lock announcement that was never minted
+        ///
+        /// Anyone may call this once `ANNOUNCEMENT_TTL_MS` (or the recipient
+        /// grace period, if longer) has passed since the announcement, so
+        /// abandoned locks stop counting towards congestion.
+        #[ink(message)]
+        pub fn expire_announcement(
+            &mut self,
+            commitment_hash: [u8; 32],
+        ) -> Result<(), BridgeError> {
+            let announcement = self
+                .lock_announcements
+                .get(commitment_hash)
+                .ok_or(BridgeError::CommitmentNotFound)?;
 
-        #[ink::test]
-        fn test_new() {
-            let contract = PolkadotBridgeComplete::new(1000, 30);
-            assert_eq!(contract.get_total_minted(), 0);
-            assert_eq!(contract.get_total_burned(), 0);
+            let expires_at = announcement
+                .announced_at
+                .saturating_add(ANNOUNCEMENT_TTL_MS.max(self.recipient_grace_period));
+            if self.env().block_timestamp() < expires_at {
+                return Err(BridgeError::AnnouncementNotExpired);
+            }
+
+            self.lock_announcements.remove(commitment_hash);
+            self.pending_lock_count = self.pending_lock_count.saturating_sub(1);
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_balance() {
-            let contract = PolkadotBridgeComplete::new(1000, 30);
-            let account = AccountId::from([0x01; 32]);
-            assert_eq!(contract.balance_of(account), 0);
+        /// Get the announcement recorded for a commitment, if any
+        #[ink(message)]
+        pub fn get_lock_announcement(&self, commitment_hash: [u8; 32]) -> Option<LockAnnouncement> {
+            self.lock_announcements.get(commitment_hash)
+        }
+
+        /// Reject mints to anyone but the committed recipient during the grace
+        /// period, and to anyone but it or its alternate afterwards
+        fn check_recipient_priority(
+            &self,
+            commitment_hash: &[u8; 32],
+            recipient_hash: &[u8; 32],
+        ) -> Result<(), BridgeError> {
+            let Some(announcement) = self.lock_announcements.get(commitment_hash) else {
+                return Ok(());
+            };
+
+            if announcement.recipient_hash == *recipient_hash {
+                return Ok(());
+            }
+
+            let grace_over = self.env().block_timestamp()
+                >= announcement
+                    .announced_at
+                    .saturating_add(self.recipient_grace_period);
+            if grace_over && announcement.alternate_recipient == Some(*recipient_hash) {
+                return Ok(());
+            }
+
+            Err(BridgeError::RecipientMismatch)
+        }
+
+        /// Record a nullifier, refusing once the tree is at capacity
+        fn insert_nullifier(&mut self, nullifier_hash: [u8; 32]) -> Result<(), BridgeError> {
+            if self.nullifier_count >= self.nullifier_tree_capacity() {
+                return Err(BridgeError::NullifierTreeFull);
+            }
+
+            self.nullifiers.insert(nullifier_hash, &true);
+            self.nullifier_count += 1;
+
+            let now = self.env().block_timestamp();
+            if now >= self.rate_window_start.saturating_add(NULLIFIER_RATE_WINDOW_MS) {
+                self.rate_window_start = now;
+                self.window_nullifiers = 0;
+            }
+            self.window_nullifiers = self.window_nullifiers.saturating_add(1);
+            Ok(())
+        }
+
+        /// Nullifiers inserted in the rate window that is still running
+        fn recent_nullifiers(&self) -> u32 {
+            let window_end = self.rate_window_start.saturating_add(NULLIFIER_RATE_WINDOW_MS);
+            if self.env().block_timestamp() >= window_end {
+                return 0;
+            }
+            self.window_nullifiers
+        }
+
+        /// Get the maximum number of nullifiers the tree can hold
+        #[ink(message)]
+        pub fn nullifier_tree_capacity(&self) -> u64 {
+            1u64 << self.nullifier_tree_depth
+        }
+
+        /// Get the number of nullifiers inserted so far
+        #[ink(message)]
+        pub fn nullifier_tree_used(&self) -> u64 {
+            self.nullifier_count
+        }
+
+        /// Burn wrapped tokens to bridge back to Stellar
+        ///
+        /// `destination_address` is the raw 32-byte ed25519 public key of the
+        /// Stellar account to pay out to. Pass zero to keep the recipient
+        /// bound only in `destination_commitment`, as before; nothing is
+        /// stored for `get_burn_destination` then. A zero `amount` is rejected
+        /// with `AmountTooLow`.
+        #[ink(message)]
+        pub fn burn_and_bridge(
+            &mut self,
+            amount: u128,
+            destination_commitment: [u8; 32],
+            destination_address: [u8; 32],
+        ) -> Result<(), BridgeError> {
+            self.with_guard(|this| {
+                this.try_burn_and_bridge(amount, destination_commitment, destination_address)
+            })
+        }
+
+        fn try_burn_and_bridge(
+            &mut self,
+            amount: u128,
+            destination_commitment: [u8; 32],
+            destination_address: [u8; 32],
+        ) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+
+            // Check if paused
+            self.ensure_not_paused(PauseFlags::BURN)?;
+            self.ensure_not_blacklisted(caller)?;
+
+            // A zero burn would claim a commitment with nothing to unlock
+            if amount == 0 {
+                return Err(BridgeError::AmountTooLow);
+            }
+
+            // Each burn gets its own commitment, shared with mints' keyspace
+            if self.commitments.contains(destination_commitment) {
+                return Err(BridgeError::CommitmentAlreadyProcessed);
+            }
+
+            // Check balance
+            let current_balance = self.balances.get(&caller).unwrap_or(0);
+            if current_balance < amount {
+                return Err(BridgeError::InsufficientBalance);
+            }
+
+            // Burn tokens
+            let new_balance = current_balance - amount;
+            self.balances.insert(caller, &new_balance);
+
+            // Update total burned
+            self.total_burned = self.total_burned
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+
+            // Record the burn for relayers to reconcile against the Stellar unlock
+            let commitment = BridgeCommitment {
+                commitment_hash: destination_commitment,
+                source_chain: POLKADOT_CHAIN_ID,
+                amount,
+                timestamp: self.env().block_timestamp(),
+                status: CommitmentStatus::Burned,
+            };
+            self.record_commitment(&commitment)?;
+            // A zero key is never a valid Stellar account, so it means none
+            if destination_address != [0u8; 32] {
+                self.burn_destinations
+                    .insert(destination_commitment, &destination_address);
+            }
+
+            self.emit_supply_changed();
+            self.emit_transfer(Some(caller), None, amount);
+
+            // Emit burn event (relayers will process on Stellar)
+            self.env().emit_event(FundsBurned {
+                sender: caller,
+                amount,
+                destination_commitment,
+                destination_address,
+            });
+
+            self.notify_observer(NOTIFY_BURN, (caller, amount, destination_commitment).encode());
+
+            Ok(())
+        }
+
+        /// Redeem wrapped balance for the contract's native currency
+        ///
+        /// Wrapped tokens are backed by locks on the source chain, not by
+        /// native currency, so redemption is bounded by what the owner has
+        /// set aside for it:
+        /// - only the native reserve funded through `fund_native_reserve` is
+        ///   paid out, failing with `InsufficientReserve` beyond it; relayer
+        ///   deposits and any other native balance are never redeemable
+        /// - the native balance net of relayer deposits can never drop below
+        ///   `min_contract_retention`, so the account is never reaped
+        /// - the caller's wrapped balance is burned 1:1 for what is paid out,
+        ///   and counted in `total_burned`
+        #[ink(message)]
+        pub fn withdraw(&mut self, to: AccountId, amount: u128) -> Result<(), BridgeError> {
+            self.with_guard(|this| this.try_withdraw(to, amount))
+        }
+
+        fn try_withdraw(&mut self, to: AccountId, amount: u128) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+
+            self.ensure_not_paused(PauseFlags::WITHDRAW)?;
+            self.ensure_not_blacklisted(caller)?;
+
+            let current_balance = self.balances.get(&caller).unwrap_or(0);
+            if current_balance < amount {
+                return Err(BridgeError::InsufficientBalance);
+            }
+            if amount > self.native_reserve {
+                return Err(BridgeError::InsufficientReserve);
+            }
+
+            let remaining_native = self
+                .env()
+                .balance()
+                .saturating_sub(self.total_relayer_deposits)
+                .checked_sub(amount)
+                .ok_or(BridgeError::InsufficientBalance)?;
+            if remaining_native < self.min_contract_retention {
+                return Err(BridgeError::RetentionFloorReached);
+            }
+
+            let total_burned = self.total_burned;
+            self.balances.insert(caller, &(current_balance - amount));
+            self.total_burned = total_burned
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            self.native_reserve -= amount;
+
+            // A recipient that refuses the transfer must not cost the caller
+            // their balance: restore it explicitly so the caller can retry to
+            // another address, whether or not the error reverts the call
+            if Self::transfer_native(to, amount).is_err() {
+                self.balances.insert(caller, &current_balance);
+                self.total_burned = total_burned;
+                self.native_reserve += amount;
+                return Err(BridgeError::TransferFailed);
+            }
+
+            self.emit_supply_changed();
+            self.emit_transfer(Some(caller), None, amount);
+            self.env().emit_event(FundsWithdrawn {
+                account: caller,
+                to,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Verify a proof, unless the same proof and public inputs already
+        /// verified earlier in this block
+        ///
+        /// Writes nothing, so a call that goes on to fail leaves no trace. A
+        /// fresh success returns the cache key to pass to
+        /// `record_verification` once every other check has passed; a cache
+        /// hit returns `None`, as there is nothing new to record.
+        fn verify_zk_proof_cached(
+            &self,
+            proof: &[u8],
+            public_inputs: &[[u8; 32]],
+        ) -> Result<Option<[u8; 32]>, BridgeError> {
+            let mut preimage = Vec::with_capacity(proof.len() + 32 * public_inputs.len());
+            preimage.extend_from_slice(proof);
+            for input in public_inputs {
+                preimage.extend_from_slice(input);
+            }
+            let key = Self::hash_proof(&preimage);
+
+            if let Some((true, cached_at)) = self.proof_cache.get(&key) {
+                if cached_at == self.env().block_number() {
+                    return Ok(None);
+                }
+            }
+
+            if !self.verify_zk_proof(proof, public_inputs) {
+                return Err(BridgeError::InvalidProof);
+            }
+            Ok(Some(key))
+        }
+
+        /// Count a fresh verification of `proof` and cache it under `key` for
+        /// the rest of the block
+        ///
+        /// The cache holds at most `PROOF_CACHE_SIZE` entries, evicting the
+        /// oldest. Only successes are recorded, and only by calls that
+        /// succeed, so a proof verified by a failing batch item is neither
+        /// counted nor cached.
+        fn record_verification(&mut self, key: Option<[u8; 32]>, proof: &[u8]) {
+            let Some(key) = key else {
+                return;
+            };
+            self.verification_count = self.verification_count.saturating_add(1);
+            if let Some(proof_type) = ProofType::of(proof) {
+                let count = self.verifications_by_type.get(proof_type).unwrap_or(0);
+                self.verifications_by_type
+                    .insert(proof_type, &count.saturating_add(1));
+            }
+
+            let slot = self.proof_cache_cursor;
+            if let Some(evicted) = self.proof_cache_slots.get(slot) {
+                self.proof_cache.remove(&evicted);
+            }
+            self.proof_cache.insert(key, &(true, self.env().block_number()));
+            self.proof_cache_slots.insert(slot, &key);
+            self.proof_cache_cursor = (slot + 1) % PROOF_CACHE_SIZE;
+        }
+
+        /// Hash arbitrary bytes with Blake2x256
+        fn hash_proof(data: &[u8]) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(data, &mut output);
+            output
+        }
+
+        /// Poseidon commitment to `(sender_hash, amount, nonce)` as the bridge
+        /// circuit computes it. For a Stellar lock, `sender_hash` is
+        /// sha256(sender XDR), matching the Stellar contract's
+        /// `compute_poseidon_commitment`.
+        #[ink(message)]
+        pub fn compute_poseidon_commitment(
+            &self,
+            sender_hash: [u8; 32],
+            amount: u128,
+            nonce: u64,
+        ) -> [u8; 32] {
+            bridge_poseidon::commitment(&sender_hash, amount, nonce)
+        }
+
+        /// Get the number of fresh proof verifications recorded by successful
+        /// calls; cache hits and calls that fail are not counted
+        #[ink(message)]
+        pub fn get_verification_count(&self) -> u64 {
+            self.verification_count
+        }
+
+        // === Relayers ===
+
+        /// Self-register as a relayer by posting the required deposit; only
+        /// while the owner has opened self-registration
+        #[ink(message, payable)]
+        pub fn register_relayer(&mut self) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+            let deposit = self.env().transferred_value();
+
+            if !self.self_registration_open {
+                return Err(BridgeError::SelfRegistrationClosed);
+            }
+            if self.is_registered_relayer(caller) {
+                return Err(BridgeError::AlreadyRegistered);
+            }
+            if self.max_relayers > 0 && self.relayer_count >= self.max_relayers {
+                return Err(BridgeError::RelayerLimitReached);
+            }
+            if deposit < self.relayer_deposit {
+                return Err(BridgeError::InsufficientDeposit);
+            }
+
+            self.bonded_relayers.insert(caller, &true);
+            self.relayer_deposits.insert(caller, &deposit);
+            self.total_relayer_deposits = self.total_relayer_deposits.saturating_add(deposit);
+            self.relayer_registered_at
+                .insert(caller, &self.env().block_timestamp());
+            self.relayer_count += 1;
+
+            self.env().emit_event(RelayerRegistered {
+                relayer: caller,
+                deposit,
+            });
+
+            Ok(())
+        }
+
+        /// Leave the relayer set and get back the (possibly slashed) deposit
+        #[ink(message)]
+        pub fn deregister_relayer(&mut self) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+
+            self.remove_relayer_entry(caller)
+        }
+
+        /// Admin: Whitelist a relayer without a deposit; it still waits out
+        /// the activation delay like a self-registered one
+        #[ink(message)]
+        pub fn add_relayer(&mut self, relayer: AccountId) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if self.is_registered_relayer(relayer) {
+                return Err(BridgeError::AlreadyRegistered);
+            }
+            if self.max_relayers > 0 && self.relayer_count >= self.max_relayers {
+                return Err(BridgeError::RelayerLimitReached);
+            }
+
+            self.relayers.insert(relayer, &true);
+            self.relayer_registered_at
+                .insert(relayer, &self.env().block_timestamp());
+            self.relayer_count += 1;
+
+            self.env().emit_event(RelayerRegistered {
+                relayer,
+                deposit: 0,
+            });
+
+            Ok(())
+        }
+
+        /// Admin: Remove a relayer, refunding whatever deposit it has left
+        #[ink(message)]
+        pub fn remove_relayer(&mut self, relayer: AccountId) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.remove_relayer_entry(relayer)
+        }
+
+        /// Drop `relayer` from the set and refund its deposit
+        fn remove_relayer_entry(&mut self, relayer: AccountId) -> Result<(), BridgeError> {
+            if !self.is_registered_relayer(relayer) {
+                return Err(BridgeError::NotRelayer);
+            }
+
+            let refund = self.relayer_deposits.get(relayer).unwrap_or(0);
+            self.relayers.remove(relayer);
+            self.bonded_relayers.remove(relayer);
+            self.relayer_deposits.remove(relayer);
+            self.total_relayer_deposits = self.total_relayer_deposits.saturating_sub(refund);
+            self.relayer_registered_at.remove(relayer);
+            self.relayer_count -= 1;
+
+            if refund > 0 {
+                self.env()
+                    .transfer(relayer, refund)
+                    .map_err(|_| BridgeError::TransferFailed)?;
+            }
+
+            self.env().emit_event(RelayerDeregistered {
+                relayer,
+                refunded: refund,
+            });
+
+            Ok(())
+        }
+
+        /// Check if an account may relay: whitelisted by the owner, or
+        /// self-registered while self-registration is open
+        #[ink(message)]
+        pub fn is_relayer(&self, account: AccountId) -> bool {
+            self.relayers.get(account).unwrap_or(false)
+                || (self.self_registration_open
+                    && self.bonded_relayers.get(account).unwrap_or(false))
+        }
+
+        /// Whether `account` holds a relayer seat of either kind
+        fn is_registered_relayer(&self, account: AccountId) -> bool {
+            self.relayers.get(account).unwrap_or(false)
+                || self.bonded_relayers.get(account).unwrap_or(false)
+        }
+
+        /// Admin: Open or close relayer self-registration. Closing it also
+        /// stops self-registered relayers from relaying; they can still
+        /// deregister to get their deposit back.
+        #[ink(message)]
+        pub fn set_self_registration(&mut self, open: bool) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.self_registration_open = open;
+            Ok(())
+        }
+
+        /// Check whether relayers may self-register
+        #[ink(message)]
+        pub fn is_self_registration_open(&self) -> bool {
+            self.self_registration_open
+        }
+
+        /// Get a relayer's refundable deposit
+        #[ink(message)]
+        pub fn get_relayer_deposit(&self, relayer: AccountId) -> u128 {
+            self.relayer_deposits.get(&relayer).unwrap_or(0)
+        }
+
+        /// Reject relayers still inside the activation delay; the owner is exempt
+        fn ensure_relayer_active(&self, relayer: AccountId) -> Result<(), BridgeError> {
+            if relayer == self.owner {
+                return Ok(());
+            }
+
+            let registered_at = self.relayer_registered_at.get(relayer).unwrap_or(0);
+            if self.env().block_timestamp()
+                < registered_at.saturating_add(self.relayer_activation_delay)
+            {
+                return Err(BridgeError::RelayerNotYetActive);
+            }
+            Ok(())
+        }
+
+        /// Get the number of registered relayers
+        #[ink(message)]
+        pub fn get_relayer_count(&self) -> u32 {
+            self.relayer_count
+        }
+
+        /// Get the maximum relayer set size (0 = unbounded)
+        #[ink(message)]
+        pub fn get_max_relayers(&self) -> u32 {
+            self.max_relayers
+        }
+
+        /// Get the deposit required to register as a relayer
+        #[ink(message)]
+        pub fn get_required_relayer_deposit(&self) -> u128 {
+            self.relayer_deposit
+        }
+
+        /// Mint tokens to recipient and count them in `total_minted`
+        fn credit_mint(&mut self, recipient: AccountId, amount: u128) -> Result<(), BridgeError> {
+            let current_balance = self.balances.get(&recipient).unwrap_or(0);
+            let new_balance = current_balance
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            let total_minted = self.total_minted
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+
+            self.balances.insert(recipient, &new_balance);
+            self.total_minted = total_minted;
+            self.emit_supply_changed();
+            self.emit_transfer(None, Some(recipient), amount);
+            Ok(())
+        }
+
+        /// Check that every counter a mint of `amount` credits can take it, so
+        /// the writes `try_verify_and_mint` then makes cannot fail halfway
+        fn ensure_mint_fits(
+            &self,
+            recipient: Option<AccountId>,
+            batched: bool,
+            recipient_hash: [u8; 32],
+            amount: u128,
+        ) -> Result<(), BridgeError> {
+            if self.nullifier_count >= self.nullifier_tree_capacity() {
+                return Err(BridgeError::NullifierTreeFull);
+            }
+            u32::try_from(self.commitment_count).map_err(|_| BridgeError::ArithmeticOverflow)?;
+
+            let credited = match recipient {
+                Some(recipient) if batched => {
+                    let pending = self.pending_credits.get(recipient).unwrap_or(0);
+                    if pending == 0 {
+                        self.pending_tail
+                            .checked_add(1)
+                            .ok_or(BridgeError::ArithmeticOverflow)?;
+                    }
+                    pending
+                }
+                Some(recipient) => self.balances.get(recipient).unwrap_or(0),
+                None => self.hashed_balances.get(recipient_hash).unwrap_or(0),
+            };
+            credited
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            // Batched credits are only counted in `total_minted` when settled
+            if !batched {
+                self.total_minted
+                    .checked_add(amount)
+                    .ok_or(BridgeError::ArithmeticOverflow)?;
+            }
+            Ok(())
+        }
+
+        /// Mint tokens to a recipient hash and count them in `total_minted`
+        fn credit_hash(&mut self, recipient_hash: [u8; 32], amount: u128) -> Result<(), BridgeError> {
+            let new_balance = self
+                .hashed_balances
+                .get(recipient_hash)
+                .unwrap_or(0)
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            let total_minted = self.total_minted
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+
+            self.hashed_balances.insert(recipient_hash, &new_balance);
+            self.total_minted = total_minted;
+            self.emit_supply_changed();
+            Ok(())
+        }
+
+        fn emit_supply_changed(&self) {
+            self.env().emit_event(SupplyChanged {
+                supply: self.total_supply(),
+            });
+        }
+
+        fn queue_batch_credit(
+            &mut self,
+            recipient: AccountId,
+            amount: u128,
+        ) -> Result<(), BridgeError> {
+            let pending = self.pending_credits.get(recipient).unwrap_or(0);
+            let new_pending = pending
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+
+            if self.pending_head == self.pending_tail {
+                self.batch_opened_at = self.env().block_timestamp();
+            }
+            if pending == 0 {
+                self.pending_recipients.insert(self.pending_tail, &recipient);
+                self.pending_tail = self
+                    .pending_tail
+                    .checked_add(1)
+                    .ok_or(BridgeError::ArithmeticOverflow)?;
+            }
+            self.pending_credits.insert(recipient, &new_pending);
+            Ok(())
+        }
+
+        /// Index of the current day for the global volume cap
+        fn current_day(&self) -> u64 {
+            self.env().block_timestamp() / DAY_MS
+        }
+
+        /// Public inputs the configured schema requires, in `PublicInput` order
+        fn expected_public_inputs(
+            &self,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+        ) -> Vec<[u8; 32]> {
+            let mut amount_input = [0u8; 32];
+            amount_input[16..].copy_from_slice(&amount.to_be_bytes());
+            let mut chain_input = [0u8; 32];
+            chain_input[28..].copy_from_slice(&source_chain.to_be_bytes());
+
+            let schema = self.public_input_schema;
+            [
+                (PublicInput::COMMITMENT, commitment_hash),
+                (PublicInput::NULLIFIER, nullifier_hash),
+                (PublicInput::RECIPIENT, recipient_hash),
+                (PublicInput::AMOUNT, amount_input),
+                (PublicInput::CHAIN_ID, chain_input),
+                (PublicInput::DOMAIN_TAG, self.domain_tag),
+            ]
+            .into_iter()
+            .filter(|(bit, _)| schema & bit != 0)
+            .map(|(_, input)| input)
+            .collect()
+        }
+
+        /// Index of the expiry in `ZKProof::public_inputs`, if the schema
+        /// binds one. Like every public input it is part of what the proof is
+        /// verified over, so a relayer cannot change it.
+        #[ink(message)]
+        pub fn proof_expiry_slot(&self) -> Option<u32> {
+            let schema = self.public_input_schema;
+            if schema & PublicInput::EXPIRY == 0 {
+                return None;
+            }
+            Some((schema & (PublicInput::EXPIRY - 1)).count_ones())
+        }
+
+        fn check_proof_expiry(&self, expiry_input: &[u8; 32]) -> Result<(), BridgeError> {
+            if expiry_input[..24] != [0u8; 24] {
+                return Err(BridgeError::InvalidProof);
+            }
+            let mut expiry = [0u8; 8];
+            expiry.copy_from_slice(&expiry_input[24..]);
+            if self.env().block_timestamp() > u64::from_be_bytes(expiry) {
+                return Err(BridgeError::ProofExpired);
+            }
+            Ok(())
+        }
+
+        /// Check a proof's public inputs against the schema and the call
+        /// arguments, and its bound expiry (if any) against the clock
+        fn check_bound_inputs(
+            &self,
+            public_inputs: &[[u8; 32]],
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+        ) -> Result<(), BridgeError> {
+            let (expected, expiry) = self.bound_inputs(
+                public_inputs,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            );
+
+            Self::check_public_inputs(public_inputs, &expected)?;
+            match expiry {
+                Some(expiry_input) => self.check_proof_expiry(&expiry_input),
+                None => Ok(()),
+            }
+        }
+
+        /// The public inputs a proof must carry for these call arguments,
+        /// ending in the domain separator, and the expiry input it binds, if
+        /// the schema has one
+        fn bound_inputs(
+            &self,
+            public_inputs: &[[u8; 32]],
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+        ) -> (Vec<[u8; 32]>, Option<[u8; 32]>) {
+            let mut expected = self.expected_public_inputs(
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            );
+            // The expiry isn't a call argument: whatever the proof binds is
+            // checked against the clock instead
+            let expiry = self.proof_expiry_slot().map(|slot| {
+                public_inputs
+                    .get(slot as usize)
+                    .copied()
+                    .unwrap_or_default()
+            });
+            if let Some(expiry_input) = expiry {
+                expected.push(expiry_input);
+            }
+            expected.push(self.domain_separator);
+            (expected, expiry)
+        }
+
+        /// A missing input, or a zero where a non-zero value is expected, is
+        /// `IncompletePublicInputs`; any other difference is `InvalidProof`.
+        /// Zero is only accepted where the expected value is zero itself,
+        /// such as the chain id of chain 0.
+        fn check_public_inputs(
+            submitted: &[[u8; 32]],
+            expected: &[[u8; 32]],
+        ) -> Result<(), BridgeError> {
+            if submitted.len() != expected.len()
+                || submitted
+                    .iter()
+                    .zip(expected)
+                    .any(|(input, want)| *input == [0u8; 32] && *want != [0u8; 32])
+            {
+                return Err(BridgeError::IncompletePublicInputs);
+            }
+            if submitted != expected {
+                return Err(BridgeError::InvalidProof);
+            }
+            Ok(())
+        }
+
+        /// The verification step on its own, with no caching or state changes,
+        /// so off-chain benchmarks can measure its cost directly
+        pub(crate) fn verify_zk_proof(&self, proof: &[u8], public_inputs: &[[u8; 32]]) -> bool {
+            // Every proof system binds the domain separator as its last
+            // input, so a proof made for another instance never verifies
+            if public_inputs.last() != Some(&self.domain_separator) {
+                return false;
+            }
+            // Dispatch on the proof system prefix
+            let body = match proof.split_first() {
+                Some((_, body)) => body,
+                None => return false,
+            };
+            match ProofType::of(proof) {
+                Some(ProofType::Groth16) => self.verify_groth16(body, public_inputs),
+                // No Plonk verifier is built in, see `ensure_verifiable`
+                Some(ProofType::Plonk) | None => false,
+            }
+        }
+
+        /// Reject a proof whose system prefix this build cannot verify
+        ///
+        /// Unknown prefixes fail with `UnsupportedProofType`. Plonk proofs
+        /// are recognized, so a Plonk key can be staged ahead of a migration,
+        /// but fail with `ProofSystemUnavailable` until a Plonk verifier is
+        /// built in.
+        fn ensure_verifiable(proof: &[u8]) -> Result<(), BridgeError> {
+            match ProofType::of(proof) {
+                Some(ProofType::Groth16) => Ok(()),
+                Some(ProofType::Plonk) => Err(BridgeError::ProofSystemUnavailable),
+                None => Err(BridgeError::UnsupportedProofType),
+            }
+        }
+
+        /// Verify a Groth16 proof body against the Groth16 verification key
+        ///
+        /// A BN254 pairing check of the compressed proof `(A, B, C)` against
+        /// the stored, compressed verifying key, over the public inputs
+        /// (ending in the domain separator) reduced into the field. Without a
+        /// stored key nothing verifies.
+        #[cfg(feature = "groth16")]
+        fn verify_groth16(&self, proof: &[u8], public_inputs: &[[u8; 32]]) -> bool {
+            use ark_bn254::{Bn254, Fr as ArkFr};
+            use ark_ff::PrimeField;
+            use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+            use ark_serialize::CanonicalDeserialize;
+
+            let Some(vk_bytes) = self.verification_keys.get(ProofType::Groth16) else {
+                return false;
+            };
+            let Ok(vk) = VerifyingKey::<Bn254>::deserialize_compressed(&vk_bytes[..]) else {
+                return false;
+            };
+            let Ok(proof) = Proof::<Bn254>::deserialize_compressed(proof) else {
+                return false;
+            };
+
+            let inputs: Vec<ArkFr> = public_inputs
+                .iter()
+                .map(|input| ArkFr::from_be_bytes_mod_order(&input[..]))
+                .collect();
+            Groth16::<Bn254>::verify_proof(&prepare_verifying_key(&vk), &proof, &inputs)
+                .unwrap_or(false)
+        }
+
+        /// Verify a Groth16 proof body against the Groth16 verification key
+        ///
+        /// Chains without pairing support build without the `groth16`
+        /// feature and only get the shape check; the domain separator is
+        /// still checked by `verify_zk_proof`.
+        #[cfg(not(feature = "groth16"))]
+        fn verify_groth16(&self, proof: &[u8], public_inputs: &[[u8; 32]]) -> bool {
+            Self::check_proof_shape(proof, public_inputs)
+        }
+
+        fn check_proof_shape(proof: &[u8], public_inputs: &[[u8; 32]]) -> bool {
+            // Simplified verification for testnet
+            // In production, this would:
+            // 1. Deserialize the proof
+            // 2. Verify against verification key
+            // 3. Check public inputs match commitment, nullifier, recipient_hash
+
+            // Basic validation
+            if proof.len() < 32 {
+                return false;
+            }
+
+            // Public inputs are big-endian field elements; the commitment,
+            // nullifier and recipient hash always lead and must be non-zero
+            if public_inputs.len() < 3
+                || public_inputs[..3]
+                    .iter()
+                    .any(|input| bytes_to_field_be(input).is_zero())
+            {
+                return false;
+            }
+
+            // TODO: Add actual ZK proof verification
+            // This would use a Groth16/Plonk verifier implementation
+            // For testnet, we accept valid-looking proofs
+
+            true
+        }
+
+        /// Hash recipient account for ZK proof
+        fn hash_recipient(recipient: &AccountId) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(recipient.as_ref(), &mut output);
+            output
+        }
+
+        /// Calculate relayer fee, honouring a commitment's fee override
+        fn calculate_fee(
+            &self,
+            commitment_hash: &[u8; 32],
+            amount: u128,
+        ) -> Result<u128, BridgeError> {
+            let fee_bps = self
+                .commitment_fee_override
+                .get(commitment_hash)
+                .unwrap_or_else(|| self.current_dynamic_fee_bps());
+            amount
+                .checked_mul(fee_bps as u128)
+                .map(|x| x / 10000)
+                .ok_or(BridgeError::ArithmeticOverflow)
+        }
+
+        /// Fee manager: Set (or clear, with `None`) the fee charged on one
+        /// commitment's mint
+        #[ink(message)]
+        pub fn set_commitment_fee_override(
+            &mut self,
+            commitment_hash: [u8; 32],
+            fee_bps: Option<u32>,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::FeeManager)?;
+
+            match fee_bps {
+                Some(fee_bps) => {
+                    if fee_bps > 10_000 {
+                        return Err(BridgeError::InvalidFeeBounds);
+                    }
+                    self.commitment_fee_override.insert(commitment_hash, &fee_bps);
+                }
+                None => self.commitment_fee_override.remove(commitment_hash),
+            }
+            Ok(())
+        }
+
+        /// Get a commitment's fee override, if any
+        #[ink(message)]
+        pub fn get_commitment_fee_override(&self, commitment_hash: [u8; 32]) -> Option<u32> {
+            self.commitment_fee_override.get(commitment_hash)
+        }
+
+        /// Relayer fee (bps) at the current congestion
+        ///
+        /// With a congestion target set, the fee moves linearly from the floor
+        /// at no pending locks to the ceiling at `congestion_target` pending
+        /// locks or more; otherwise it is the static `relayer_fee_bps`.
+        /// Pending locks are proof-verified announcements that were neither
+        /// minted nor expired.
+        #[ink(message)]
+        pub fn current_dynamic_fee_bps(&self) -> u32 {
+            if self.congestion_target == 0 {
+                return self.relayer_fee_bps;
+            }
+
+            let pending = self.pending_lock_count.min(self.congestion_target);
+            let span = u64::from(self.fee_ceiling_bps - self.fee_floor_bps);
+            let increase = span * u64::from(pending) / u64::from(self.congestion_target);
+            self.fee_floor_bps + increase as u32
+        }
+
+        /// Get the number of announced locks not yet minted
+        #[ink(message)]
+        pub fn get_pending_lock_count(&self) -> u32 {
+            self.pending_lock_count
+        }
+
+        /// Check if nullifier is used
+        #[ink(message)]
+        pub fn is_nullifier_used(&self, nullifier_hash: [u8; 32]) -> bool {
+            self.nullifiers.get(&nullifier_hash).unwrap_or(false)
+        }
+
+        /// Get commitment details
+        #[ink(message)]
+        pub fn get_commitment(&self, commitment_hash: [u8; 32]) -> Option<BridgeCommitment> {
+            self.commitments.get(&commitment_hash)
+        }
+
+        /// Get the record of a `burn_and_bridge` by its destination commitment
+        #[ink(message)]
+        pub fn get_burn_commitment(&self, commitment_hash: [u8; 32]) -> Option<BridgeCommitment> {
+            self.commitments
+                .get(commitment_hash)
+                .filter(|commitment| commitment.status == CommitmentStatus::Burned)
+        }
+
+        /// Get the Stellar account a burn pays out to
+        #[ink(message)]
+        pub fn get_burn_destination(&self, commitment_hash: [u8; 32]) -> Option<[u8; 32]> {
+            self.burn_destinations.get(commitment_hash)
+        }
+
+        /// Get total minted
+        #[ink(message)]
+        pub fn get_total_minted(&self) -> u128 {
+            self.total_minted
+        }
+
+        /// Get relayer fees collected and not yet withdrawn
+        #[ink(message)]
+        pub fn get_collected_fees(&self) -> u128 {
+            self.collected_fees
+        }
+
+        /// Get the account relayer fees are meant to be withdrawn to
+        #[ink(message)]
+        pub fn get_fee_recipient(&self) -> AccountId {
+            self.fee_recipient
+        }
+
+        /// Check if an account is blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: AccountId) -> bool {
+            self.blacklisted.get(account).unwrap_or(false)
+        }
+
+        /// Get total burned
+        #[ink(message)]
+        pub fn get_total_burned(&self) -> u128 {
+            self.total_burned
+        }
+
+        /// Get the number of commitments recorded by mints and burns
+        #[ink(message)]
+        pub fn get_commitment_count(&self) -> u64 {
+            self.commitment_count
+        }
+
+        /// `get_commitment_count` as the index bound of `list_commitments`
+        #[ink(message)]
+        pub fn commitment_count(&self) -> u32 {
+            u32::try_from(self.commitment_count).unwrap_or(u32::MAX)
+        }
+
+        /// List commitments in the order they were recorded, starting at
+        /// index `start`; at most `MAX_COMMITMENT_PAGE` are returned
+        #[ink(message)]
+        pub fn list_commitments(&self, start: u32, limit: u32) -> Vec<BridgeCommitment> {
+            let end = start
+                .saturating_add(limit.min(MAX_COMMITMENT_PAGE))
+                .min(self.commitment_count());
+            (start..end)
+                .filter_map(|index| self.commitment_keys.get(index))
+                .filter_map(|commitment_hash| self.commitments.get(commitment_hash))
+                .collect()
+        }
+
+        /// Store a new commitment and append it to the enumeration index;
+        /// existing commitments are never overwritten or indexed twice
+        fn record_commitment(&mut self, commitment: &BridgeCommitment) -> Result<(), BridgeError> {
+            let commitment_hash = commitment.commitment_hash;
+            if self.commitments.contains(commitment_hash) {
+                return Err(BridgeError::CommitmentAlreadyProcessed);
+            }
+            let index =
+                u32::try_from(self.commitment_count).map_err(|_| BridgeError::ArithmeticOverflow)?;
+
+            self.commitments.insert(commitment_hash, commitment);
+            self.commitment_keys.insert(index, &commitment_hash);
+            self.commitment_count += 1;
+            Ok(())
+        }
+
+        /// Operational state packed into one word for light clients
+        ///
+        /// Bit layout, most significant first:
+        /// - `63..56` pause flags (`PauseFlags`)
+        /// - `55..48` `STATUS_WORD_VERSION`
+        /// - `47..32` net supply (minted - burned) >> `STATUS_SUPPLY_SHIFT`,
+        ///   saturating at `u16::MAX`
+        /// - `31..0` commitment count, saturating at `u32::MAX`
+        #[ink(message)]
+        pub fn status_word(&self) -> u64 {
+            let supply_high =
+                u16::try_from(self.total_supply() >> STATUS_SUPPLY_SHIFT).unwrap_or(u16::MAX);
+            let commitments = self.commitment_count();
+
+            (u64::from(self.pause_flags) << 56)
+                | (u64::from(STATUS_WORD_VERSION) << 48)
+                | (u64::from(supply_high) << 32)
+                | u64::from(commitments)
+        }
+
+        /// Get contract owner
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Get the native balance floor kept by the contract
+        #[ink(message)]
+        pub fn get_min_contract_retention(&self) -> u128 {
+            self.min_contract_retention
+        }
+
+        /// Get the native currency still set aside for `withdraw`
+        #[ink(message)]
+        pub fn get_native_reserve(&self) -> u128 {
+            self.native_reserve
+        }
+
+        /// Get the bridge's own account id
+        #[ink(message)]
+        pub fn contract_address(&self) -> AccountId {
+            self.env().account_id()
+        }
+
+        /// Verify a proof without minting or touching the proof cache
+        ///
+        /// Meant to be dry-run by relayers: the node's reported gas for this
+        /// call is the weight of the verification step `verify_and_mint` runs.
+        #[ink(message)]
+        pub fn check_proof(&self, proof: ZKProof) -> bool {
+            self.verify_zk_proof(&proof.proof, &proof.public_inputs)
+        }
+
+        /// Get the start time of a mint that reserved `nullifier_hash` but never
+        /// finalized; any entry here indicates a bug
+        ///
+        /// A mint that fails reverts along with its marker, so only a mint
+        /// that returned `Ok` without finalizing can leave one behind.
+        #[ink(message)]
+        pub fn get_in_flight(&self, nullifier_hash: [u8; 32]) -> Option<u64> {
+            self.in_flight.get(nullifier_hash)
+        }
+
+        /// Whether `account` holds `role`; the owner holds every role
+        #[ink(message)]
+        pub fn has_role(&self, account: AccountId, role: Role) -> bool {
+            if account == self.owner {
+                return true;
+            }
+            match role {
+                Role::Owner => false,
+                Role::Relayer => self.is_relayer(account),
+                Role::FeeManager => self.fee_managers.get(account).unwrap_or(false),
+            }
+        }
+
+        /// Get the monitoring observer, if one is configured
+        #[ink(message)]
+        pub fn get_observer(&self) -> Option<AccountId> {
+            self.observer
+        }
+
+        /// Whether the bridge has been permanently shut down
+        #[ink(message)]
+        pub fn is_shut_down(&self) -> bool {
+            self.shut_down
+        }
+
+        /// Minimum mint amount right now
+        ///
+        /// Every `nullifier_rate_threshold` nullifiers inserted in the current
+        /// window add another `min_mint_amount` to the floor, making bursts of
+        /// dust mints that bloat the nullifier set progressively expensive.
+        #[ink(message)]
+        pub fn effective_min_mint(&self) -> u128 {
+            if self.nullifier_rate_threshold == 0 {
+                return self.min_mint_amount;
+            }
+            let steps = u128::from(self.recent_nullifiers() / self.nullifier_rate_threshold);
+            self.min_mint_amount.saturating_mul(steps.saturating_add(1))
+        }
+
+        /// Get the batched mint amount waiting to be settled for `account`
+        #[ink(message)]
+        pub fn get_pending_credit(&self, account: AccountId) -> u128 {
+            self.pending_credits.get(account).unwrap_or(0)
+        }
+
+        /// Get the global daily mint cap (0 = unlimited)
+        #[ink(message)]
+        pub fn get_global_daily_cap(&self) -> u128 {
+            self.global_daily_cap
+        }
+
+        /// Get the volume minted so far today
+        #[ink(message)]
+        pub fn get_global_daily_volume(&self) -> u128 {
+            self.global_daily_volume.get(self.current_day()).unwrap_or(0)
+        }
+
+        /// Start and minted volume of the mint window `block_timestamp` falls
+        /// in, opening a fresh window once the current one has run out
+        fn current_mint_window(&self) -> (u64, u128) {
+            let now = self.env().block_timestamp();
+            let window_end = self
+                .window_start
+                .saturating_add(self.window_seconds.saturating_mul(1000));
+            if now >= window_end {
+                (now, 0)
+            } else {
+                (self.window_start, self.minted_in_window)
+            }
+        }
+
+        /// Get how much more can be minted in the current window
+        #[ink(message)]
+        pub fn get_remaining_mint_quota(&self) -> u128 {
+            if self.mint_limit_per_window == 0 {
+                return u128::MAX;
+            }
+            let (_, minted_in_window) = self.current_mint_window();
+            self.mint_limit_per_window.saturating_sub(minted_in_window)
+        }
+
+        /// Get the public-input schema as `PublicInput` bits
+        #[ink(message)]
+        pub fn get_public_input_schema(&self) -> u8 {
+            self.public_input_schema
+        }
+
+        /// Get the domain separator proofs for this instance must bind
+        #[ink(message)]
+        pub fn get_domain_separator(&self) -> [u8; 32] {
+            self.domain_separator
+        }
+
+        fn compute_domain_separator(contract: &AccountId) -> [u8; 32] {
+            let mut preimage = Vec::with_capacity(36);
+            preimage.extend_from_slice(contract.as_ref());
+            preimage.extend_from_slice(&POLKADOT_CHAIN_ID.to_be_bytes());
+            Self::hash_proof(&preimage)
+        }
+
+        /// Check whether the bridge may call into `callee`
+        #[ink(message)]
+        pub fn is_approved_callee(&self, callee: AccountId) -> bool {
+            self.approved_callees.get(callee).unwrap_or(false)
+        }
+
+        /// Get the verification key configured for a proof system
+        #[ink(message)]
+        pub fn get_verification_key(&self, proof_type: ProofType) -> Option<Vec<u8>> {
+            self.verification_keys.get(proof_type)
+        }
+
+        /// Get the number of proofs verified with a given proof system
+        #[ink(message)]
+        pub fn get_verification_count_by_type(&self, proof_type: ProofType) -> u64 {
+            self.verifications_by_type.get(proof_type).unwrap_or(0)
+        }
+
+        /// Get the PSP22 token contract, if one is configured
+        #[ink(message)]
+        pub fn get_token_contract(&self) -> Option<AccountId> {
+            self.token_contract
+        }
+
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, amount: u128) {
+            self.allowances.insert((owner, spender), &amount);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                amount,
+            });
+        }
+
+        /// PSP22 `Transfer`; `from` is `None` for mints and `to` for burns
+        fn emit_transfer(&self, from: Option<AccountId>, to: Option<AccountId>, value: u128) {
+            self.env().emit_event(Transfer { from, to, value });
+        }
+
+        /// Move `amount` from `from` to `to` against `spender`'s allowance,
+        /// which is lowered by the amount moved
+        fn transfer_allowance(
+            &mut self,
+            spender: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: u128,
+        ) -> Result<(), BridgeError> {
+            let allowance = self.allowances.get((from, spender)).unwrap_or(0);
+            if allowance < amount {
+                return Err(BridgeError::InsufficientAllowance);
+            }
+            self.ensure_not_blacklisted(spender)?;
+
+            self.transfer_balance(from, to, amount)?;
+            self.set_allowance(from, spender, allowance - amount);
+            Ok(())
+        }
+
+        fn transfer_balance(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: u128,
+        ) -> Result<(), BridgeError> {
+            self.ensure_not_paused(PauseFlags::TRANSFER)?;
+            self.ensure_not_blacklisted(from)?;
+            self.ensure_not_blacklisted(to)?;
+
+            if to == self.env().account_id() {
+                return Err(BridgeError::InvalidRecipient);
+            }
+            let from_balance = self.balances.get(&from).unwrap_or(0);
+
+            if from_balance < amount {
+                return Err(BridgeError::InsufficientBalance);
+            }
+
+            let to_balance = self.balances.get(&to).unwrap_or(0);
+
+            self.balances.insert(from, &(from_balance - amount));
+            self.balances.insert(to, &(to_balance
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?));
+
+            self.emit_transfer(Some(from), Some(to), amount);
+            Ok(())
+        }
+
+        /// Admin: Update configuration
+        ///
+        /// `mint_limit_per_window` caps the volume minted per window of
+        /// `window_seconds` (0 = unlimited).
+        #[ink(message)]
+        pub fn update_config(
+            &mut self,
+            min_mint_amount: Option<u128>,
+            relayer_fee_bps: Option<u32>,
+            mint_limit_per_window: Option<u128>,
+            window_seconds: Option<u64>,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if self.timelock_delay > 0 {
+                return Err(BridgeError::TimelockRequired);
+            }
+
+            self.apply_config(
+                min_mint_amount,
+                relayer_fee_bps,
+                mint_limit_per_window,
+                window_seconds,
+            );
+            Ok(())
+        }
+
+        fn apply_config(
+            &mut self,
+            min_mint_amount: Option<u128>,
+            relayer_fee_bps: Option<u32>,
+            mint_limit_per_window: Option<u128>,
+            window_seconds: Option<u64>,
+        ) {
+            if let Some(min_amount) = min_mint_amount {
+                self.min_mint_amount = min_amount;
+            }
+
+            if let Some(fee) = relayer_fee_bps {
+                self.relayer_fee_bps = fee;
+            }
+
+            if let Some(limit) = mint_limit_per_window {
+                self.mint_limit_per_window = limit;
+            }
+
+            if let Some(seconds) = window_seconds {
+                self.window_seconds = seconds;
+            }
+        }
+
+        /// Admin: Set the native balance floor withdrawals cannot cross
+        #[ink(message)]
+        pub fn set_min_contract_retention(&mut self, retention: u128) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.min_contract_retention = retention;
+            Ok(())
+        }
+
+        /// Admin: Add the transferred value to the native reserve `withdraw`
+        /// pays out from
+        #[ink(message, payable)]
+        pub fn fund_native_reserve(&mut self) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.native_reserve = self
+                .native_reserve
+                .checked_add(self.env().transferred_value())
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            Ok(())
+        }
+
+        /// Admin: Break-glass correction of a single account's balance
+        ///
+        /// The difference is booked as a mint (increase) or burn (decrease) so
+        /// that `total_minted - total_burned` still equals circulating balances.
+        ///
+        /// Delegated admins may call this too; the size of each adjustment is
+        /// charged against their daily budget.
+        #[ink(message)]
+        pub fn admin_adjust_balance(
+            &mut self,
+            account: AccountId,
+            new_balance: u128,
+            reason: String,
+        ) -> Result<(), BridgeError> {
+            let old_balance = self.balances.get(&account).unwrap_or(0);
+            self.charge_admin(new_balance.abs_diff(old_balance))?;
+
+            if new_balance > old_balance {
+                self.total_minted = self.total_minted
+                    .checked_add(new_balance - old_balance)
+                    .ok_or(BridgeError::ArithmeticOverflow)?;
+            } else {
+                self.total_burned = self.total_burned
+                    .checked_add(old_balance - new_balance)
+                    .ok_or(BridgeError::ArithmeticOverflow)?;
+            }
+            self.balances.insert(account, &new_balance);
+            self.emit_supply_changed();
+            if new_balance > old_balance {
+                self.emit_transfer(None, Some(account), new_balance - old_balance);
+            } else if new_balance < old_balance {
+                self.emit_transfer(Some(account), None, old_balance - new_balance);
+            }
+
+            self.env().emit_event(BalanceAdjusted {
+                account,
+                old: old_balance,
+                new: new_balance,
+                reason,
+            });
+
+            Ok(())
+        }
+
+        /// Admin: Appoint a delegated admin with a daily adjustment budget, or
+        /// remove one with `None`
+        #[ink(message)]
+        pub fn set_admin(
+            &mut self,
+            account: AccountId,
+            daily_limit: Option<u128>,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            match daily_limit {
+                Some(daily_limit) => {
+                    let limits = AdminLimits {
+                        daily_limit,
+                        spent_today: 0,
+                        day: self.current_day(),
+                    };
+                    self.admins.insert(account, &limits);
+                }
+                None => self.admins.remove(account),
+            }
+            Ok(())
+        }
+
+        /// Get a delegated admin's budget and today's spending
+        #[ink(message)]
+        pub fn get_admin_limits(&self, account: AccountId) -> Option<AdminLimits> {
+            self.admins.get(account)
+        }
+
+        /// Let the owner through, and charge `amount` to a delegated admin's
+        /// budget for the day
+        fn charge_admin(&mut self, amount: u128) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+            if caller == self.owner {
+                return Ok(());
+            }
+
+            let mut limits = self.admins.get(caller).ok_or(BridgeError::Unauthorized)?;
+            let day = self.current_day();
+            if limits.day != day {
+                limits.day = day;
+                limits.spent_today = 0;
+            }
+
+            let spent = limits
+                .spent_today
+                .checked_add(amount)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            if spent > limits.daily_limit {
+                return Err(BridgeError::AdminLimitExceeded);
+            }
+
+            limits.spent_today = spent;
+            self.admins.insert(caller, &limits);
+            Ok(())
+        }
+
+        /// Admin: Set the deposit required for relayer self-registration
+        #[ink(message)]
+        pub fn set_relayer_deposit(&mut self, deposit: u128) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.relayer_deposit = deposit;
+            Ok(())
+        }
+
+        /// Admin: Set how long (ms) a newly registered relayer waits before
+        /// it may relay
+        #[ink(message)]
+        pub fn set_relayer_activation_delay(&mut self, delay: u64) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.relayer_activation_delay = delay;
+            Ok(())
+        }
+
+        /// Admin: Cap the relayer set size (0 = unbounded). Relayers already
+        /// registered above a lowered cap keep their seats.
+        #[ink(message)]
+        pub fn set_max_relayers(&mut self, max_relayers: u32) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.max_relayers = max_relayers;
+            Ok(())
+        }
+
+        /// Admin: Slash part of a relayer's deposit; the slashed amount stays
+        /// with the contract
+        #[ink(message)]
+        pub fn slash_relayer(&mut self, relayer: AccountId, amount: u128) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let deposit = self.relayer_deposits.get(&relayer).unwrap_or(0);
+            let slashed = amount.min(deposit);
+            self.relayer_deposits.insert(relayer, &(deposit - slashed));
+            self.total_relayer_deposits = self.total_relayer_deposits.saturating_sub(slashed);
+            Ok(())
+        }
+
+        /// Admin: Set the verification key for a proof system
+        #[ink(message)]
+        pub fn set_verification_key(
+            &mut self,
+            proof_type: ProofType,
+            verification_key: Vec<u8>,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if verification_key.is_empty() {
+                return Err(BridgeError::InvalidVerificationKey);
+            }
+
+            self.verification_keys.insert(proof_type, &verification_key);
+            self.env().emit_event(VerificationKeyUpdated {
+                proof_type,
+                key_hash: Self::hash_proof(&verification_key),
+            });
+            Ok(())
+        }
+
+        /// Admin: Require commit-reveal for mints, with a minimum reveal delay
+        #[ink(message)]
+        pub fn set_commit_reveal(&mut self, required: bool, delay: u64) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.commit_reveal_required = required;
+            self.mint_reveal_delay = delay;
+            Ok(())
+        }
+
+        /// Admin: Set or clear the monitoring observer (must be an approved callee)
+        #[ink(message)]
+        pub fn set_observer(&mut self, observer: Option<AccountId>) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if let Some(observer) = observer {
+                self.ensure_callee_approved(observer)?;
+            }
+
+            self.observer = observer;
+            Ok(())
+        }
+
+        /// Admin: Set the nullifier rate that raises the effective minimum (0 = off)
+        #[ink(message)]
+        pub fn set_nullifier_rate_threshold(&mut self, threshold: u32) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.nullifier_rate_threshold = threshold;
+            Ok(())
+        }
+
+        /// Admin: Set the settlement batching window (0 = credit mints immediately)
+        #[ink(message)]
+        pub fn set_batch_window(&mut self, window: u64) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.batch_window = window;
+            Ok(())
+        }
+
+        /// Admin: Set how long (ms) an announced lock is reserved for its
+        /// committed recipient
+        #[ink(message)]
+        pub fn set_recipient_grace_period(&mut self, grace_period: u64) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.recipient_grace_period = grace_period;
+            Ok(())
+        }
+
+        /// Admin: Scale the relayer fee with congestion between `floor_bps`
+        /// and `ceiling_bps`; a `congestion_target` of 0 restores the static fee
+        #[ink(message)]
+        pub fn set_congestion_fee(
+            &mut self,
+            floor_bps: u32,
+            ceiling_bps: u32,
+            congestion_target: u32,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if floor_bps > ceiling_bps || ceiling_bps > 10_000 {
+                return Err(BridgeError::InvalidFeeBounds);
+            }
+
+            self.fee_floor_bps = floor_bps;
+            self.fee_ceiling_bps = ceiling_bps;
+            self.congestion_target = congestion_target;
+            Ok(())
+        }
+
+        /// Admin: Require a proof-of-work nonce with `difficulty` leading zero
+        /// bits on every mint (0 disables)
+        #[ink(message)]
+        pub fn set_pow_difficulty(&mut self, difficulty: u8) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.pow_difficulty = difficulty;
+            Ok(())
+        }
+
+        /// Admin: Pay `amount` of the collected fees out to `to`'s balance,
+        /// normally the account named by `get_fee_recipient`
+        ///
+        /// Withdrawn fees become wrapped tokens and count in `total_minted`.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, to: AccountId, amount: u128) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if amount > self.collected_fees {
+                return Err(BridgeError::InsufficientBalance);
+            }
+
+            self.credit_mint(to, amount)?;
+            self.collected_fees -= amount;
+            Ok(())
+        }
+
+        /// Admin: Set the account relayer fees are paid out to
+        #[ink(message)]
+        pub fn set_fee_recipient(&mut self, fee_recipient: AccountId) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.fee_recipient = fee_recipient;
+            Ok(())
+        }
+
+        /// Admin: Blacklist or clear an account. A blacklisted account keeps
+        /// its balance but can't receive, send, burn or withdraw it.
+        #[ink(message)]
+        pub fn set_blacklisted(
+            &mut self,
+            account: AccountId,
+            blacklisted: bool,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            if blacklisted {
+                self.blacklisted.insert(account, &true);
+            } else {
+                self.blacklisted.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Admin: Register a source chain, or replace its configuration
+        #[ink(message)]
+        pub fn register_chain(
+            &mut self,
+            chain_id: u32,
+            config: ChainConfig,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.chain_configs.insert(chain_id, &config);
+            Ok(())
+        }
+
+        /// Admin: Enable or disable mints from a registered source chain
+        #[ink(message)]
+        pub fn set_chain_enabled(
+            &mut self,
+            chain_id: u32,
+            enabled: bool,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let mut config = self
+                .chain_configs
+                .get(chain_id)
+                .ok_or(BridgeError::UnsupportedSourceChain)?;
+            config.enabled = enabled;
+            self.chain_configs.insert(chain_id, &config);
+            Ok(())
+        }
+
+        /// Get a source chain's configuration
+        #[ink(message)]
+        pub fn get_chain_config(&self, chain_id: u32) -> Option<ChainConfig> {
+            self.chain_configs.get(chain_id)
+        }
+
+        /// Admin: Grant or revoke the FeeManager role
+        #[ink(message)]
+        pub fn set_fee_manager(&mut self, account: AccountId, enabled: bool) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            if enabled {
+                self.fee_managers.insert(account, &true);
+            } else {
+                self.fee_managers.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Admin: Toggle whether `verify_and_mint_legacy` honours the
+        /// relayer-named recipient (off) or resolves from the registry (on)
+        #[ink(message)]
+        pub fn set_strict_recipient_binding(&mut self, strict: bool) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.strict_recipient_binding = strict;
+            Ok(())
+        }
+
+        /// Admin: Reject non-canonical public inputs instead of reducing them
+        #[ink(message)]
+        pub fn set_strict_inputs(&mut self, strict: bool) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.strict_inputs = strict;
+            Ok(())
+        }
+
+        /// Admin: Set the global daily mint cap (0 = unlimited)
+        #[ink(message)]
+        pub fn set_global_daily_cap(&mut self, cap: u128) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.global_daily_cap = cap;
+            Ok(())
+        }
+
+        /// Admin: Set which public inputs proofs must bind
+        ///
+        /// The commitment, nullifier and recipient are always part of the schema.
+        #[ink(message)]
+        pub fn set_public_input_schema(
+            &mut self,
+            schema: u8,
+            domain_tag: [u8; 32],
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let schema = (schema & PublicInput::ALL) | PublicInput::BASE;
+            if schema & PublicInput::DOMAIN_TAG != 0 && domain_tag == [0u8; 32] {
+                return Err(BridgeError::IncompletePublicInputs);
+            }
+
+            self.public_input_schema = schema;
+            self.domain_tag = domain_tag;
+            Ok(())
+        }
+
+        /// Admin: Permanently shut the bridge down
+        ///
+        /// Only minting stops: `burn_and_bridge`, `withdraw` and `transfer` are
+        /// exempt so holders can always recover their funds during wind-down.
+        #[ink(message)]
+        pub fn shutdown(&mut self) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.shut_down = true;
+            Ok(())
+        }
+
+        /// Admin: Approve or revoke a contract the bridge may call into
+        #[ink(message)]
+        pub fn set_approved_callee(
+            &mut self,
+            callee: AccountId,
+            approved: bool,
+        ) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            if approved {
+                self.approved_callees.insert(callee, &true);
+            } else {
+                self.approved_callees.remove(callee);
+            }
+            Ok(())
+        }
+
+        /// Admin: Clear a stuck in-flight entry after investigating it
+        #[ink(message)]
+        pub fn clear_in_flight(&mut self, nullifier_hash: [u8; 32]) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.in_flight.remove(nullifier_hash);
+            Ok(())
+        }
+
+        /// Admin: Pause contract (every operation)
+        #[ink(message)]
+        pub fn set_paused(&mut self, paused: bool) -> Result<(), BridgeError> {
+            let flags = if paused { PauseFlags::ALL } else { 0 };
+            self.set_pause_flags(flags)
+        }
+
+        /// Admin: Pause or resume minting only, leaving burns to Stellar open
+        #[ink(message)]
+        pub fn set_mint_paused(&mut self, paused: bool) -> Result<(), BridgeError> {
+            self.set_pause_flag(PauseFlags::MINT, paused)
+        }
+
+        /// Admin: Pause or resume burning only
+        #[ink(message)]
+        pub fn set_burn_paused(&mut self, paused: bool) -> Result<(), BridgeError> {
+            self.set_pause_flag(PauseFlags::BURN, paused)
+        }
+
+        fn set_pause_flag(&mut self, flag: u8, paused: bool) -> Result<(), BridgeError> {
+            let flags = if paused {
+                self.pause_flags | flag
+            } else {
+                self.pause_flags & !flag
+            };
+            self.set_pause_flags(flags)
+        }
+
+        /// Admin: Pause individual operations (see `PauseFlags`)
+        #[ink(message)]
+        pub fn set_pause_flags(&mut self, flags: u8) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let previous = self.pause_flags;
+            self.pause_flags = flags & PauseFlags::ALL;
+
+            let paused = self.pause_flags & !previous;
+            let unpaused = previous & !self.pause_flags;
+            if paused != 0 {
+                self.env().emit_event(Paused { flags: paused });
+            }
+            if unpaused != 0 {
+                self.env().emit_event(Unpaused { flags: unpaused });
+            }
+            self.with_guard(|this| {
+                this.notify_observer(NOTIFY_PAUSE, this.pause_flags.encode());
+                Ok(())
+            })
+        }
+
+        /// Whether minting is paused
+        #[ink(message)]
+        pub fn is_mint_paused(&self) -> bool {
+            self.pause_flags & PauseFlags::MINT != 0
+        }
+
+        /// Whether burning is paused
+        #[ink(message)]
+        pub fn is_burn_paused(&self) -> bool {
+            self.pause_flags & PauseFlags::BURN != 0
+        }
+
+        /// Get the currently paused operations
+        #[ink(message)]
+        pub fn get_pause_flags(&self) -> u8 {
+            self.pause_flags
+        }
+
+        /// Require the caller to hold `role`
+        fn ensure_role(&self, role: Role) -> Result<(), BridgeError> {
+            if self.has_role(self.env().caller(), role) {
+                return Ok(());
+            }
+            Err(BridgeError::Unauthorized)
+        }
+
+        /// Best-effort `notify(event_code, data)` call to the observer
+        ///
+        /// Failures are swallowed: monitoring must never block a bridge operation.
+        fn notify_observer(&self, event_code: u8, data: Vec<u8>) {
+            if let Some(observer) = self.observer {
+                if self.ensure_callee_approved(observer).is_ok() {
+                    let _ = Self::invoke_observer(observer, event_code, data);
+                }
+            }
+        }
+
+        #[cfg(not(test))]
+        fn invoke_observer(observer: AccountId, event_code: u8, data: Vec<u8>) -> Result<(), ()> {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+            /// Weight (ref time) `notify` may use, so an observer cannot
+            /// spend the gas of the mint or burn that notifies it
+            const OBSERVER_GAS_LIMIT: u64 = 5_000_000_000;
+
+            build_call::<Environment>()
+                .call_v1(observer)
+                .gas_limit(OBSERVER_GAS_LIMIT)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("notify")))
+                        .push_arg(event_code)
+                        .push_arg(data),
+                )
+                .returns::<()>()
+                .try_invoke()
+                .map_err(|_| ())?
+                .map_err(|_| ())
+        }
+
+        /// The off-chain test environment cannot invoke contracts, so tests
+        /// stand in a mock observer that records calls or reverts
+        #[cfg(test)]
+        fn invoke_observer(observer: AccountId, event_code: u8, data: Vec<u8>) -> Result<(), ()> {
+            tests::mock_observer_notify(observer, event_code, data)
+        }
+
+        #[cfg(not(test))]
+        fn invoke_dex(
+            dex: AccountId,
+            recipient: AccountId,
+            amount_in: u128,
+            swap_calldata: Vec<u8>,
+        ) -> Result<u128, ()> {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+            /// Weight (ref time) a swap may use, bounded like observer calls
+            const DEX_GAS_LIMIT: u64 = 20_000_000_000;
+
+            build_call::<Environment>()
+                .call_v1(dex)
+                .gas_limit(DEX_GAS_LIMIT)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("swap")))
+                        .push_arg(recipient)
+                        .push_arg(amount_in)
+                        .push_arg(swap_calldata),
+                )
+                .returns::<u128>()
+                .try_invoke()
+                .map_err(|_| ())?
+                .map_err(|_| ())
+        }
+
+        /// Off-chain tests swap against a mock DEX instead
+        #[cfg(test)]
+        fn invoke_dex(
+            dex: AccountId,
+            recipient: AccountId,
+            amount_in: u128,
+            swap_calldata: Vec<u8>,
+        ) -> Result<u128, ()> {
+            tests::mock_dex_swap(dex, recipient, amount_in, swap_calldata)
+        }
+
+        #[cfg(not(test))]
+        fn psp22_balance_of(token: AccountId, owner: AccountId) -> Result<u128, ()> {
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+
+            build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::balance_of")))
+                        .push_arg(owner),
+                )
+                .returns::<u128>()
+                .try_invoke()
+                .map_err(|_| ())?
+                .map_err(|_| ())
+        }
+
+        /// Off-chain tests read the mock DEX's output ledger instead
+        #[cfg(test)]
+        fn psp22_balance_of(token: AccountId, owner: AccountId) -> Result<u128, ()> {
+            tests::mock_psp22_balance_of(token, owner)
+        }
+
+        #[cfg(not(test))]
+        fn transfer_native(to: AccountId, amount: u128) -> Result<(), ()> {
+            ink::env::transfer::<Environment>(to, amount).map_err(|_| ())
+        }
+
+        /// Off-chain tests can't deploy a rejecting recipient, so one is mocked
+        #[cfg(test)]
+        fn transfer_native(to: AccountId, amount: u128) -> Result<(), ()> {
+            tests::mock_transfer(to, amount)
+        }
+
+        /// Run `f` holding the reentrancy lock, failing with `Reentrancy` if
+        /// a guarded message is already running further up the call stack
+        fn with_guard<T>(
+            &mut self,
+            f: impl FnOnce(&mut Self) -> Result<T, BridgeError>,
+        ) -> Result<T, BridgeError> {
+            if self.locked {
+                return Err(BridgeError::Reentrancy);
+            }
+
+            self.locked = true;
+            let result = f(self);
+            self.locked = false;
+            result
+        }
+
+        /// Guard to run before every cross-contract call
+        fn ensure_callee_approved(&self, callee: AccountId) -> Result<(), BridgeError> {
+            if !self.is_approved_callee(callee) {
+                return Err(BridgeError::CalleeNotApproved);
+            }
+            Ok(())
+        }
+
+        fn ensure_not_blacklisted(&self, account: AccountId) -> Result<(), BridgeError> {
+            if self.blacklisted.get(account).unwrap_or(false) {
+                return Err(BridgeError::AccountBlacklisted);
+            }
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self, flag: u8) -> Result<(), BridgeError> {
+            if self.pause_flags & flag != 0 {
+                return Err(BridgeError::ContractPaused);
+            }
+            Ok(())
+        }
+
+        /// Admin: Nominate a new owner, who takes over once they call
+        /// `accept_ownership`. A later nomination replaces an earlier one.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if self.timelock_delay > 0 {
+                return Err(BridgeError::TimelockRequired);
+            }
+
+            self.start_ownership_transfer(new_owner);
+            Ok(())
+        }
+
+        fn start_ownership_transfer(&mut self, new_owner: AccountId) {
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(OwnershipTransferStarted {
+                previous_owner: self.owner,
+                new_owner,
+            });
+        }
+
+        /// Pending owner: Take over ownership
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), BridgeError> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(BridgeError::Unauthorized);
+            }
+
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+            Ok(())
+        }
+
+        /// Admin: Withdraw a pending ownership nomination
+        #[ink(message)]
+        pub fn cancel_ownership_transfer(&mut self) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            self.pending_owner = None;
+            Ok(())
+        }
+
+        /// Get the nominated owner, if a transfer is pending
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        // === Timelock ===
+
+        /// Admin: Set the timelock delay. It can only be raised directly;
+        /// lowering it must itself go through the queue.
+        #[ink(message)]
+        pub fn set_timelock_delay(&mut self, delay: u64) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if delay < self.timelock_delay {
+                return Err(BridgeError::TimelockRequired);
+            }
+
+            self.timelock_delay = delay;
+            Ok(())
+        }
+
+        /// Admin: Queue an operation to run once the timelock delay has passed
+        #[ink(message)]
+        pub fn queue_operation(&mut self, operation: QueuedOperation) -> Result<u32, BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let op_id = self.next_op_id;
+            self.next_op_id = op_id.checked_add(1).ok_or(BridgeError::ArithmeticOverflow)?;
+
+            let ready_at = self
+                .env()
+                .block_timestamp()
+                .checked_add(self.timelock_delay)
+                .ok_or(BridgeError::ArithmeticOverflow)?;
+            self.queued_ops.insert(op_id, &QueuedOp {
+                operation: operation.clone(),
+                ready_at,
+            });
+
+            self.env().emit_event(OperationQueued {
+                op_id,
+                operation,
+                ready_at,
+            });
+
+            Ok(op_id)
+        }
+
+        /// Admin: Execute a queued operation whose delay has elapsed
+        #[ink(message)]
+        pub fn execute_queued(&mut self, op_id: u32) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+
+            let queued = self.queued_ops.get(op_id).ok_or(BridgeError::QueuedOpNotFound)?;
+            if self.env().block_timestamp() < queued.ready_at {
+                return Err(BridgeError::QueuedOpNotReady);
+            }
+            self.queued_ops.remove(op_id);
+
+            match queued.operation {
+                QueuedOperation::UpdateConfig {
+                    min_mint_amount,
+                    relayer_fee_bps,
+                    mint_limit_per_window,
+                    window_seconds,
+                } => self.apply_config(
+                    min_mint_amount,
+                    relayer_fee_bps,
+                    mint_limit_per_window,
+                    window_seconds,
+                ),
+                QueuedOperation::TransferOwnership(new_owner) => {
+                    self.start_ownership_transfer(new_owner)
+                }
+                QueuedOperation::SetTimelockDelay(delay) => self.timelock_delay = delay,
+            }
+
+            self.env().emit_event(QueuedOpExecuted { op_id });
+            Ok(())
+        }
+
+        /// Admin: Cancel a queued operation before it is executed
+        #[ink(message)]
+        pub fn cancel_queued(&mut self, op_id: u32) -> Result<(), BridgeError> {
+            self.ensure_role(Role::Owner)?;
+            if !self.queued_ops.contains(op_id) {
+                return Err(BridgeError::QueuedOpNotFound);
+            }
+
+            self.queued_ops.remove(op_id);
+            self.env().emit_event(QueuedOpCancelled { op_id });
+            Ok(())
+        }
+
+        /// Get a queued operation
+        #[ink(message)]
+        pub fn get_queued(&self, op_id: u32) -> Option<QueuedOp> {
+            self.queued_ops.get(op_id)
+        }
+
+        /// Get the timelock delay
+        #[ink(message)]
+        pub fn get_timelock_delay(&self) -> u64 {
+            self.timelock_delay
+        }
+    }
+
+    impl PSP22 for PolkadotBridgeComplete {
+        /// Circulating wrapped supply: everything minted (fees paid out
+        /// included) less everything burned or withdrawn
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.total_minted.saturating_sub(self.total_burned)
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.balances.get(&owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Sending to the bridge's own account is rejected: tokens parked there
+        /// would leave circulation without being counted in `total_burned`.
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.with_guard(|this| this.transfer_balance(caller, to, value))?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            self.with_guard(|this| this.transfer_allowance(spender, from, to, value))?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender).saturating_add(delta_value);
+            self.set_allowance(owner, spender, allowance);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
+            self.set_allowance(owner, spender, allowance);
+            Ok(())
+        }
+    }
+
+    impl PSP22Metadata for PolkadotBridgeComplete {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            Some(self.name.clone())
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            Some(self.symbol.clone())
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::cell::RefCell;
+
+        /// Mock observer that reverts every notification
+        const REVERTING_OBSERVER: [u8; 32] = [0xee; 32];
+
+        thread_local! {
+            static NOTIFICATIONS: RefCell<Vec<(AccountId, u8, Vec<u8>)>> = RefCell::new(Vec::new());
+        }
+
+        pub(super) fn mock_observer_notify(
+            observer: AccountId,
+            event_code: u8,
+            data: Vec<u8>,
+        ) -> Result<(), ()> {
+            if observer == AccountId::from(REVERTING_OBSERVER) {
+                return Err(());
+            }
+            NOTIFICATIONS.with(|log| log.borrow_mut().push((observer, event_code, data)));
+            Ok(())
+        }
+
+        const REVERTING_DEX: [u8; 32] = [0xdd; 32];
+        /// Mock DEX that reports the full 2:1 output but only delivers half of it
+        const SHORTING_DEX: [u8; 32] = [0xdc; 32];
+
+        thread_local! {
+            static SWAP_OUTPUT: RefCell<Vec<(AccountId, u128)>> = RefCell::new(Vec::new());
+        }
+
+        /// A 2:1 pool: pays out half of the input, in the one output token
+        pub(super) fn mock_dex_swap(
+            dex: AccountId,
+            recipient: AccountId,
+            amount_in: u128,
+            _swap_calldata: Vec<u8>,
+        ) -> Result<u128, ()> {
+            if dex == AccountId::from(REVERTING_DEX) {
+                return Err(());
+            }
+            let reported = amount_in / 2;
+            let delivered = if dex == AccountId::from(SHORTING_DEX) {
+                reported / 2
+            } else {
+                reported
+            };
+            SWAP_OUTPUT.with(|ledger| ledger.borrow_mut().push((recipient, delivered)));
+            Ok(reported)
+        }
+
+        pub(super) fn mock_psp22_balance_of(
+            _token: AccountId,
+            owner: AccountId,
+        ) -> Result<u128, ()> {
+            Ok(SWAP_OUTPUT.with(|ledger| {
+                ledger
+                    .borrow()
+                    .iter()
+                    .filter(|(account, _)| *account == owner)
+                    .map(|(_, amount)| amount)
+                    .sum()
+            }))
+        }
+
+        const REJECTING_RECIPIENT: [u8; 32] = [0xfe; 32];
+
+        pub(super) fn mock_transfer(to: AccountId, amount: u128) -> Result<(), ()> {
+            if to == AccountId::from(REJECTING_RECIPIENT) {
+                return Err(());
+            }
+            ink::env::transfer::<ink::env::DefaultEnvironment>(to, amount).map_err(|_| ())
+        }
+
+        fn notification_codes() -> Vec<u8> {
+            NOTIFICATIONS.with(|log| log.borrow().iter().map(|(_, code, _)| *code).collect())
+        }
+
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        }
+
+        /// Register `recipient` under its account hash, then restore the caller
+        fn register(contract: &mut PolkadotBridgeComplete, recipient: AccountId) -> [u8; 32] {
+            let caller = ink::env::caller::<ink::env::DefaultEnvironment>();
+            let recipient_hash = PolkadotBridgeComplete::hash_recipient(&recipient);
+            set_caller(recipient);
+            contract
+                .register_recipient(recipient_hash)
+                .expect("registration succeeds");
+            set_caller(caller);
+            recipient_hash
+        }
+
+        /// A mock proof carrying exactly the public inputs `contract` expects
+        fn zk(
+            contract: &PolkadotBridgeComplete,
+            proof: Vec<u8>,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+        ) -> ZKProof {
+            let (public_inputs, _) = contract.bound_inputs(
+                &[],
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            );
+            ZKProof {
+                proof,
+                public_inputs,
+            }
+        }
+
+        /// The receipt `verify_and_mint` returns on success
+        fn receipt(minted: u128, fee: u128, new_balance: u128) -> Result<MintReceipt, BridgeError> {
+            Ok(MintReceipt {
+                minted,
+                fee,
+                new_balance,
+            })
+        }
+
+        /// `verify_and_mint` with `proof` wrapped by `zk`
+        #[allow(clippy::too_many_arguments)]
+        fn mint(
+            contract: &mut PolkadotBridgeComplete,
+            proof: Vec<u8>,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+            source_chain: u32,
+            pow_nonce: u64,
+        ) -> Result<MintReceipt, BridgeError> {
+            let proof = zk(
+                contract,
+                proof,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+            );
+            contract.verify_and_mint(
+                proof,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                source_chain,
+                pow_nonce,
+            )
+        }
+
+        /// `announce_lock` for a chain-0 lock, with a proof wrapped by `zk`
+        fn announce(
+            contract: &mut PolkadotBridgeComplete,
+            commitment_hash: [u8; 32],
+            nullifier_hash: [u8; 32],
+            recipient_hash: [u8; 32],
+            amount: u128,
+        ) -> Result<(), BridgeError> {
+            let proof = zk(
+                contract,
+                vec![1u8; 64],
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                0,
+            );
+            contract.announce_lock(
+                proof,
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                0,
+            )
+        }
+
+        /// Mint `amount` to `recipient` through a well-formed mock proof
+        fn mint_to(
+            contract: &mut PolkadotBridgeComplete,
+            recipient: AccountId,
+            amount: u128,
+            seed: u8,
+        ) {
+            let recipient_hash = register(contract, recipient);
+            mint(contract, vec![1u8; 64], [seed; 32], [seed; 32], recipient_hash, amount, 0, 0)
+                .expect("mint succeeds");
+        }
+
+        /// Fund `amount` of the native reserve `withdraw` pays out from, as
+        /// the owner
+        fn fund_reserve(contract: &mut PolkadotBridgeComplete, amount: u128) {
+            let caller = ink::env::caller::<ink::env::DefaultEnvironment>();
+            set_caller(contract.get_owner());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(amount);
+            contract.fund_native_reserve().expect("owner funds the reserve");
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            set_caller(caller);
+        }
+
+        /// Decode every recorded event of type `E`
+        fn emitted<E: ink::env::Event + ink::scale::Decode>() -> Vec<E> {
+            ink::env::test::recorded_events()
+                .filter(|event| {
+                    E::SIGNATURE_TOPIC.map(|topic| topic.to_vec()) == event.topics.first().cloned()
+                })
+                .map(|event| E::decode(&mut &event.data[..]).expect("event decodes"))
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_new() {
+            let contract = PolkadotBridgeComplete::new(1000, 30);
+            assert_eq!(contract.get_total_minted(), 0);
+            assert_eq!(contract.get_total_burned(), 0);
+        }
+
+        #[ink::test]
+        fn test_balance() {
+            let contract = PolkadotBridgeComplete::new(1000, 30);
+            let account = AccountId::from([0x01; 32]);
+            assert_eq!(contract.balance_of(account), 0);
+        }
+
+        #[ink::test]
+        fn test_initialized_event() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token = AccountId::from([0x07; 32]);
+            let contract = PolkadotBridgeComplete::new_with_config(1000, 30, Some(token), 32, true);
+            assert_eq!(contract.get_token_contract(), Some(token));
+
+            let events = emitted::<BridgeInitialized>();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].owner, accounts.alice);
+            assert_eq!(events[0].min_mint_amount, 1000);
+            assert_eq!(events[0].relayer_fee_bps, 30);
+            assert_eq!(events[0].token_contract, Some(token));
+            assert_eq!(events[0].schema_version, EVENT_SCHEMA_VERSION);
+        }
+
+        #[ink::test]
+        fn test_transfer_to_contract_rejected() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.alice, 5000, 1);
+
+            assert_eq!(
+                contract.transfer(bridge, 1000, Vec::new()),
+                Err(PSP22Error::Custom(String::from("InvalidRecipient")))
+            );
+            assert_eq!(contract.balance_of(accounts.alice), 5000);
+            assert_eq!(contract.balance_of(bridge), 0);
+
+            assert_eq!(contract.transfer(accounts.bob, 1000, Vec::new()), Ok(()));
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_mint_resolves_registered_recipient() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+
+            // Unregistered hash cannot be minted to
+            let unknown = [0x42; 32];
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], unknown, 5000, 0, 0),
+                Err(BridgeError::RecipientNotRegistered)
+            );
+            assert!(!contract.is_nullifier_used([1; 32]));
+
+            // Bob registers; the relayer (alice) cannot redirect the mint
+            let bob_hash = register(&mut contract, accounts.bob);
+            assert_eq!(contract.get_registered_recipient(bob_hash), Some(accounts.bob));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 5000, 0, 0),
+                receipt(5000, 0, 5000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 5000);
+            assert_eq!(contract.balance_of(accounts.alice), 0);
+
+            // Someone else cannot take over bob's hash, nor claim one that
+            // isn't theirs before its owner registers it
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.register_recipient(bob_hash),
+                Err(BridgeError::InvalidRecipient)
+            );
+            let django_hash = PolkadotBridgeComplete::hash_recipient(&accounts.django);
+            assert_eq!(
+                contract.register_recipient(django_hash),
+                Err(BridgeError::InvalidRecipient)
+            );
+            assert_eq!(contract.get_registered_recipient(django_hash), None);
+        }
+
+        #[ink::test]
+        fn test_field_encoding_vectors() {
+            // Test vector for provers: bytes 0x00..0x1f read big-endian
+            let mut bytes = [0u8; 32];
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            let be = bytes_to_field_be(&bytes);
+            assert_eq!(
+                be.0,
+                [
+                    0x18191a1b1c1d1e1f,
+                    0x1011121314151617,
+                    0x08090a0b0c0d0e0f,
+                    0x0001020304050607,
+                ]
+            );
+            assert_eq!(be.to_bytes_be(), bytes);
+
+            // The same bytes read little-endian give a different element
+            let le = bytes_to_field_le(&bytes);
+            assert_ne!(be, le);
+            let mut reversed = bytes;
+            reversed.reverse();
+            assert_eq!(bytes_to_field_le(&reversed), be);
+
+            // One is 0x00..01 in BE and 0x01..00 in LE
+            let mut one_be = [0u8; 32];
+            one_be[31] = 1;
+            let mut one_le = [0u8; 32];
+            one_le[0] = 1;
+            assert_eq!(bytes_to_field_be(&one_be), Fr([1, 0, 0, 0]));
+            assert_eq!(bytes_to_field_le(&one_le), Fr([1, 0, 0, 0]));
+
+            // The modulus itself reduces to zero, so it is rejected as a public input
+            let modulus = Fr(BN254_MODULUS).to_bytes_be();
+            assert!(bytes_to_field_be(&modulus).is_zero());
+            let contract = PolkadotBridgeComplete::new(1000, 0);
+            let domain = contract.get_domain_separator();
+            assert!(!contract.verify_zk_proof(&[1u8; 64], &[modulus, [1; 32], [1; 32], domain]));
+            assert!(contract.verify_zk_proof(&[1u8; 64], &[one_be, [1; 32], [1; 32], domain]));
+            assert_eq!(contract.to_canonical_input(one_le, true), one_be);
+        }
+
+        #[ink::test]
+        fn test_cancel_queued_operation() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            contract.set_timelock_delay(100).unwrap();
+
+            // Direct admin changes are blocked while the timelock is active
+            assert_eq!(
+                contract.transfer_ownership(accounts.bob),
+                Err(BridgeError::TimelockRequired)
+            );
+
+            let op_id = contract
+                .queue_operation(QueuedOperation::TransferOwnership(accounts.bob))
+                .unwrap();
+            assert_eq!(contract.execute_queued(op_id), Err(BridgeError::QueuedOpNotReady));
+
+            contract.cancel_queued(op_id).unwrap();
+            assert_eq!(emitted::<QueuedOpCancelled>().len(), 1);
+            assert_eq!(contract.get_queued(op_id), None);
+
+            // Even after the delay, a cancelled op cannot run
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(contract.execute_queued(op_id), Err(BridgeError::QueuedOpNotFound));
+            assert_eq!(contract.get_owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn test_execute_queued_after_delay() {
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            contract.set_timelock_delay(100).unwrap();
+
+            let op_id = contract
+                .queue_operation(QueuedOperation::UpdateConfig {
+                    min_mint_amount: Some(5000),
+                    relayer_fee_bps: None,
+                    mint_limit_per_window: None,
+                    window_seconds: None,
+                })
+                .unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            contract.execute_queued(op_id).unwrap();
+            assert_eq!(contract.get_queued(op_id), None);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], [1; 32], 2000, 0, 0),
+                Err(BridgeError::AmountTooLow)
+            );
+        }
+
+        #[ink::test]
+        fn test_nullifier_tree_capacity() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new_with_config(1000, 0, None, 1, true);
+            assert_eq!(contract.nullifier_tree_capacity(), 2);
+
+            mint_to(&mut contract, accounts.bob, 1000, 1);
+            mint_to(&mut contract, accounts.bob, 1000, 2);
+            assert_eq!(contract.nullifier_tree_used(), 2);
+
+            let bob_hash = PolkadotBridgeComplete::hash_recipient(&accounts.bob);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::NullifierTreeFull)
+            );
+            assert!(!contract.is_nullifier_used([3; 32]));
+            assert_eq!(contract.balance_of(accounts.bob), 2000);
+        }
+
+        #[ink::test]
+        fn test_withdraw_respects_retention_floor() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 10_000_000);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_min_contract_retention(2_000_000).unwrap();
+            fund_reserve(&mut contract, 10_000_000);
+            mint_to(&mut contract, accounts.bob, 20_000_000, 1);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.withdraw(accounts.bob, 5_000_000), Ok(()));
+            assert_eq!(contract.balance_of(accounts.bob), 15_000_000);
+
+            // 5_000_000 native left; withdrawing 4_000_000 would cross the floor
+            assert_eq!(
+                contract.withdraw(accounts.bob, 4_000_000),
+                Err(BridgeError::RetentionFloorReached)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 15_000_000);
+
+            // Down to exactly the floor is allowed
+            assert_eq!(contract.withdraw(accounts.bob, 3_000_000), Ok(()));
+            assert_eq!(contract.get_total_burned(), 8_000_000);
+        }
+
+        #[ink::test]
+        fn test_withdraw_draws_only_on_native_reserve() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 10_000_000);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 20_000_000, 1);
+
+            // Native balance alone doesn't back wrapped tokens
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.withdraw(accounts.bob, 1_000_000),
+                Err(BridgeError::InsufficientReserve)
+            );
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(4_000_000);
+            assert_eq!(contract.fund_native_reserve(), Err(BridgeError::Unauthorized));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+
+            fund_reserve(&mut contract, 4_000_000);
+            assert_eq!(contract.get_native_reserve(), 4_000_000);
+            assert_eq!(
+                contract.withdraw(accounts.bob, 4_000_001),
+                Err(BridgeError::InsufficientReserve)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 20_000_000);
+
+            // Each redemption burns the wrapped amount it pays out
+            assert_eq!(contract.withdraw(accounts.bob, 4_000_000), Ok(()));
+            assert_eq!(contract.get_native_reserve(), 0);
+            assert_eq!(contract.balance_of(accounts.bob), 16_000_000);
+            assert_eq!(contract.total_supply(), 16_000_000);
+            assert_eq!(
+                contract.withdraw(accounts.bob, 1),
+                Err(BridgeError::InsufficientReserve)
+            );
+        }
+
+        #[ink::test]
+        fn test_withdraw_cannot_spend_relayer_deposits() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 3_000_000);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            contract.set_relayer_deposit(2_000_000).unwrap();
+            fund_reserve(&mut contract, 3_000_000);
+            mint_to(&mut contract, accounts.bob, 5_000_000, 1);
+
+            // Charlie's deposit arrives with the call
+            set_caller(accounts.charlie);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2_000_000);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 5_000_000);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+
+            // Only the 3_000_000 set aside can be redeemed, never the deposit
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.withdraw(accounts.bob, 3_000_001),
+                Err(BridgeError::InsufficientReserve)
+            );
+            assert_eq!(contract.withdraw(accounts.bob, 3_000_000), Ok(()));
+
+            set_caller(accounts.charlie);
+            assert_eq!(contract.deregister_relayer(), Ok(()));
+        }
+
+        #[ink::test]
+        fn test_repeat_verification_uses_cached_result() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = PolkadotBridgeComplete::hash_recipient(&accounts.bob);
+            let proof = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            contract
+                .verify_and_mint_to_hash(proof.clone(), [1; 32], [1; 32], bob_hash, 1000, 0, 0)
+                .unwrap();
+            assert_eq!(contract.get_verification_count(), 1);
+
+            // Claiming in the same block reuses the minting proof's result
+            set_caller(accounts.bob);
+            assert_eq!(contract.claim_by_preimage(accounts.bob, [1; 32], proof), Ok(1000));
+            assert_eq!(contract.get_verification_count(), 1);
+
+            // A mint stopped by the rate limit after verifying records nothing,
+            // so once the limit is lifted the proof is verified afresh
+            set_caller(accounts.alice);
+            register(&mut contract, accounts.bob);
+            contract.update_config(None, None, Some(500), Some(3_600)).unwrap();
+            let proof = zk(&contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0);
+            let params = MintParams {
+                proof: proof.clone(),
+                commitment_hash: [2; 32],
+                nullifier_hash: [2; 32],
+                recipient_hash: bob_hash,
+                amount: 1000,
+                source_chain: 0,
+                pow_nonce: 0,
+            };
+            let results = contract.verify_and_mint_batch(vec![params.clone()], false).unwrap();
+            assert_eq!(results[0], Err(BridgeError::RateLimitExceeded));
+            assert_eq!(contract.get_verification_count(), 1);
+            assert_eq!(contract.get_verification_count_by_type(ProofType::Groth16), 1);
+            contract.update_config(None, None, Some(0), None).unwrap();
+            contract.verify_and_mint_batch(vec![params], false).unwrap()[0].unwrap();
+            assert_eq!(contract.get_verification_count(), 2);
+
+            // Cached results expire with the block
+            let proof = zk(&contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 1000, 0);
+            contract
+                .announce_lock(proof.clone(), [3; 32], [3; 32], bob_hash, 1000, 0)
+                .unwrap();
+            assert_eq!(contract.get_verification_count(), 3);
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            contract
+                .verify_and_mint(proof, [3; 32], [3; 32], bob_hash, 1000, 0, 0)
+                .unwrap();
+            assert_eq!(contract.get_verification_count(), 4);
+        }
+
+        #[ink::test]
+        fn test_proof_cache_is_bounded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let proof = |contract: &PolkadotBridgeComplete, seed: u8| {
+                zk(contract, vec![1u8; 64], [seed; 32], [seed; 32], bob_hash, 1000, 0)
+            };
+            for seed in 1..=PROOF_CACHE_SIZE as u8 + 1 {
+                let zk_proof = proof(&contract, seed);
+                contract
+                    .announce_lock(zk_proof, [seed; 32], [seed; 32], bob_hash, 1000, 0)
+                    .unwrap();
+            }
+            assert_eq!(contract.get_verification_count(), PROOF_CACHE_SIZE as u64 + 1);
+
+            // The first entry was evicted, the most recent one is still cached
+            let first = proof(&contract, 1);
+            contract
+                .verify_and_mint(first, [1; 32], [1; 32], bob_hash, 1000, 0, 0)
+                .unwrap();
+            assert_eq!(contract.get_verification_count(), PROOF_CACHE_SIZE as u64 + 2);
+            let newest = PROOF_CACHE_SIZE as u8 + 1;
+            contract
+                .verify_and_mint(
+                    proof(&contract, newest),
+                    [newest; 32],
+                    [newest; 32],
+                    bob_hash,
+                    1000,
+                    0,
+                    0,
+                )
+                .unwrap();
+            assert_eq!(contract.get_verification_count(), PROOF_CACHE_SIZE as u64 + 2);
+        }
+
+        #[ink::test]
+        fn test_burn_with_destination_address() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.alice, 5000, 1);
+
+            // Zero keeps the commitment-only burn
+            assert_eq!(contract.burn_and_bridge(1000, [8; 32], [0; 32]), Ok(()));
+            assert_eq!(contract.balance_of(accounts.alice), 4000);
+            assert_eq!(contract.get_burn_destination([8; 32]), None);
+
+            let stellar_key = [0xAB; 32];
+            assert_eq!(contract.burn_and_bridge(1000, [9; 32], stellar_key), Ok(()));
+            let burns = emitted::<FundsBurned>();
+            assert_eq!(burns.len(), 2);
+            assert_eq!(burns[0].destination_address, [0; 32]);
+            assert_eq!(burns[1].destination_address, stellar_key);
+            assert_eq!(burns[1].amount, 1000);
+        }
+
+        #[ink::test]
+        fn test_admin_adjust_balance_keeps_supply_invariant() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            mint_to(&mut contract, accounts.charlie, 3000, 2);
+
+            let net = |c: &PolkadotBridgeComplete| c.get_total_minted() - c.get_total_burned();
+
+            contract
+                .admin_adjust_balance(accounts.bob, 7000, String::from("missed credit"))
+                .unwrap();
+            assert_eq!(contract.balance_of(accounts.bob), 7000);
+            assert_eq!(net(&contract), 10_000);
+
+            contract
+                .admin_adjust_balance(accounts.charlie, 1000, String::from("double credit"))
+                .unwrap();
+            assert_eq!(contract.balance_of(accounts.charlie), 1000);
+            assert_eq!(net(&contract), 8_000);
+
+            let adjustments = emitted::<BalanceAdjusted>();
+            assert_eq!(adjustments.len(), 2);
+            assert_eq!(adjustments[1].old, 3000);
+            assert_eq!(adjustments[1].new, 1000);
+            assert_eq!(adjustments[1].reason, String::from("double credit"));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.admin_adjust_balance(accounts.bob, 0, String::new()),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_relayer_deposit_and_refund() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            contract.set_relayer_deposit(500).unwrap();
+
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(499);
+            assert_eq!(contract.register_relayer(), Err(BridgeError::InsufficientDeposit));
+            assert!(!contract.is_relayer(accounts.bob));
+
+            // The deposit arrives with the call, on top of the existential deposit
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 1_000_500);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            assert!(contract.is_relayer(accounts.bob));
+            assert_eq!(contract.get_relayer_deposit(accounts.bob), 500);
+
+            // Slashed portion is kept, the rest is refunded on exit
+            set_caller(accounts.alice);
+            contract.slash_relayer(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                .unwrap_or(0);
+            assert_eq!(contract.deregister_relayer(), Ok(()));
+            let after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                .unwrap_or(0);
+            assert_eq!(after - before, 400);
+            assert!(!contract.is_relayer(accounts.bob));
+            assert_eq!(contract.deregister_relayer(), Err(BridgeError::NotRelayer));
+        }
+
+        #[ink::test]
+        fn test_mint_failures_emit_reason_codes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let item = |contract: &PolkadotBridgeComplete, seed: u8, nullifier: u8, amount| {
+                let (commitment, nullifier) = ([seed; 32], [nullifier; 32]);
+                MintParams {
+                    proof: zk(contract, vec![1u8; 64], commitment, nullifier, bob_hash, amount, 0),
+                    commitment_hash: commitment,
+                    nullifier_hash: nullifier,
+                    recipient_hash: bob_hash,
+                    amount,
+                    source_chain: 0,
+                    pow_nonce: 0,
+                }
+            };
+
+            // A single failed mint reverts, so its error is the only report
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 10, 0, 0),
+                Err(BridgeError::AmountTooLow)
+            );
+            assert!(emitted::<MintFailed>().is_empty());
+
+            // Failed items of a best-effort batch don't revert, and are reported
+            let mut short_proof = item(&contract, 2, 2, 1000);
+            short_proof.proof.proof = vec![1u8; 8];
+            let batch = vec![
+                item(&contract, 1, 1, 10),
+                short_proof,
+                item(&contract, 3, 3, 1000),
+                item(&contract, 4, 3, 1000),
+            ];
+            contract.verify_and_mint_batch(batch, false).unwrap();
+            contract.set_paused(true).unwrap();
+            let paused = vec![item(&contract, 5, 5, 1000)];
+            contract.verify_and_mint_batch(paused, false).unwrap();
+
+            let failures = emitted::<MintFailed>();
+            let reasons: Vec<u8> = failures.iter().map(|event| event.reason).collect();
+            assert_eq!(
+                reasons,
+                vec![
+                    BridgeError::AmountTooLow.reason_code(),
+                    BridgeError::InvalidProof.reason_code(),
+                    BridgeError::NullifierUsed.reason_code(),
+                    BridgeError::ContractPaused.reason_code(),
+                ]
+            );
+            assert_eq!(failures[2].nullifier_hash, [3; 32]);
+            assert_eq!(BridgeError::Unauthorized.reason_code(), 0);
+        }
+
+        #[ink::test]
+        fn test_contract_address_matches_callee() {
+            let contract = PolkadotBridgeComplete::new(1000, 30);
+            assert_eq!(
+                contract.contract_address(),
+                ink::env::test::callee::<ink::env::DefaultEnvironment>()
+            );
+        }
+
+        #[ink::test]
+        fn test_proof_prefix_routes_to_verifier() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract
+                .set_verification_key(ProofType::Groth16, vec![0xaa; 8])
+                .unwrap();
+            contract
+                .set_verification_key(ProofType::Plonk, vec![0xbb; 8])
+                .unwrap();
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            let mut groth16 = vec![ProofType::Groth16 as u8];
+            groth16.extend_from_slice(&[7u8; 64]);
+            let mut plonk = vec![ProofType::Plonk as u8];
+            plonk.extend_from_slice(&[7u8; 64]);
+
+            assert_eq!(
+                mint(&mut contract, groth16, [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.get_verification_count_by_type(ProofType::Groth16), 1);
+            assert_eq!(contract.get_verification_count_by_type(ProofType::Plonk), 0);
+
+            // Plonk is recognized, and its key can be staged, but there is no
+            // verifier for it, so it is refused before any verification
+            assert_eq!(
+                mint(&mut contract, plonk.clone(), [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::ProofSystemUnavailable)
+            );
+            let plonk_proof = zk(&contract, plonk, [2; 32], [2; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.announce_lock(plonk_proof.clone(), [2; 32], [2; 32], bob_hash, 1000, 0),
+                Err(BridgeError::ProofSystemUnavailable)
+            );
+            assert!(!contract.check_proof(plonk_proof));
+            assert_eq!(contract.get_verification_count_by_type(ProofType::Groth16), 1);
+            assert_eq!(contract.get_verification_count_by_type(ProofType::Plonk), 0);
+            assert_eq!(contract.get_verification_key(ProofType::Plonk), Some(vec![0xbb; 8]));
+
+            let mut unknown = vec![0x7f];
+            unknown.extend_from_slice(&[7u8; 64]);
+            assert_eq!(
+                mint(&mut contract, unknown, [3; 32], [3; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::UnsupportedProofType)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_pause_flags_are_per_operation() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+
+            // Minting paused, burns still flow
+            contract.set_pause_flags(PauseFlags::MINT).unwrap();
+            assert_eq!(contract.get_pause_flags(), PauseFlags::MINT);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::ContractPaused)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(contract.burn_and_bridge(1000, [9; 32], [8; 32]), Ok(()));
+
+            // Burning paused, mints flow again
+            set_caller(accounts.alice);
+            contract.set_pause_flags(PauseFlags::BURN).unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 5000)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.burn_and_bridge(1000, [9; 32], [8; 32]),
+                Err(BridgeError::ContractPaused)
+            );
+            assert_eq!(contract.transfer(accounts.charlie, 100, Vec::new()), Ok(()));
+            assert_eq!(contract.balance_of(accounts.bob), 4900);
+        }
+
+        #[ink::test]
+        fn bench_verify_zk_proof() {
+            let contract = PolkadotBridgeComplete::new(1000, 0);
+            let account = contract.contract_address();
+            let mut proof = vec![ProofType::Groth16 as u8];
+            proof.extend_from_slice(&[7u8; 256]);
+            let public_inputs = [[1; 32], [2; 32], [3; 32], contract.get_domain_separator()];
+            let verified = contract.verify_zk_proof(&proof, &public_inputs);
+            #[cfg(not(feature = "groth16"))]
+            assert!(verified);
+
+            // Storage access is the deterministic part of the cost: a fixed
+            // number of reads per proof, and never a write
+            let storage_rw = || ink::env::test::get_contract_storage_rw::<
+                ink::env::DefaultEnvironment,
+            >(&account);
+            let (reads_before, writes_before) = storage_rw();
+            assert_eq!(contract.verify_zk_proof(&proof, &public_inputs), verified);
+            let (reads_once, writes_once) = storage_rw();
+            let reads_per_proof = reads_once - reads_before;
+            // Only the pairing check loads anything: its verification key
+            assert_eq!(reads_per_proof, usize::from(cfg!(feature = "groth16")));
+            assert_eq!(writes_once, writes_before);
+
+            const ITERATIONS: usize = 100;
+            for _ in 0..ITERATIONS {
+                assert_eq!(contract.verify_zk_proof(&proof, &public_inputs), verified);
+            }
+            let (reads_after, writes_after) = storage_rw();
+            assert_eq!(reads_after - reads_once, ITERATIONS * reads_per_proof);
+            assert_eq!(writes_after, writes_before);
+        }
+
+        #[ink::test]
+        fn test_in_flight_cleared_on_success() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 1000, 1);
+            assert_eq!(contract.get_in_flight([1; 32]), None);
+
+            // A stuck entry can only be cleared by the owner
+            contract.in_flight.insert([2; 32], &42);
+            assert_eq!(contract.get_in_flight([2; 32]), Some(42));
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.clear_in_flight([2; 32]),
+                Err(BridgeError::Unauthorized)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(contract.clear_in_flight([2; 32]), Ok(()));
+            assert_eq!(contract.get_in_flight([2; 32]), None);
+        }
+
+        #[ink::test]
+        fn test_token_contract_must_be_approved_callee() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token = AccountId::from([0x07; 32]);
+            let mut contract = PolkadotBridgeComplete::new_with_config(1000, 0, Some(token), 32, true);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::CalleeNotApproved)
+            );
+
+            // Approving some other contract does not help
+            contract
+                .set_approved_callee(AccountId::from([0x08; 32]), true)
+                .unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::CalleeNotApproved)
+            );
+
+            contract.set_approved_callee(token, true).unwrap();
+            assert!(contract.is_approved_callee(token));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+        }
+
+        #[ink::test]
+        fn test_recovery_paths_survive_shutdown() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+
+            contract.shutdown().unwrap();
+            assert!(contract.is_shut_down());
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::BridgeShutDown)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.burn_and_bridge(3000, [9; 32], [8; 32]), Ok(()));
+            assert_eq!(contract.transfer(accounts.charlie, 2000, Vec::new()), Ok(()));
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn test_public_inputs_must_cover_schema() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let mut amount_input = [0u8; 32];
+            amount_input[16..].copy_from_slice(&1000u128.to_be_bytes());
+            let domain = contract.get_domain_separator();
+
+            let without_amount = ZKProof {
+                proof: vec![1u8; 64],
+                public_inputs: vec![[1; 32], [1; 32], bob_hash, domain],
+            };
+            let with_amount = ZKProof {
+                proof: vec![1u8; 64],
+                public_inputs: vec![[2; 32], [2; 32], bob_hash, amount_input, domain],
+            };
+
+            // The default schema binds commitment, nullifier and recipient only
+            assert_eq!(contract.get_public_input_schema(), PublicInput::BASE);
+            assert_eq!(
+                contract.verify_and_mint(
+                    without_amount.clone(), [1; 32], [1; 32], bob_hash, 1000, 0, 0
+                ),
+                receipt(1000, 0, 1000)
+            );
+
+            contract
+                .set_public_input_schema(PublicInput::AMOUNT, [0u8; 32])
+                .unwrap();
+            let mut missing_amount = without_amount;
+            missing_amount.public_inputs[0] = [2; 32];
+            missing_amount.public_inputs[1] = [2; 32];
+            assert_eq!(
+                contract.verify_and_mint(
+                    missing_amount, [2; 32], [2; 32], bob_hash, 1000, 0, 0
+                ),
+                Err(BridgeError::IncompletePublicInputs)
+            );
+
+            // Bound to a different amount than the one claimed
+            assert_eq!(
+                contract.verify_and_mint(
+                    with_amount.clone(), [2; 32], [2; 32], bob_hash, 2000, 0, 0
+                ),
+                Err(BridgeError::InvalidProof)
+            );
+            assert_eq!(
+                contract.verify_and_mint(with_amount, [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 2000);
+        }
+
+        #[ink::test]
+        fn test_schema_applies_to_every_mint_path() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            contract
+                .set_public_input_schema(PublicInput::AMOUNT | PublicInput::CHAIN_ID, [0u8; 32])
+                .unwrap();
+            let domain = contract.get_domain_separator();
+            let base_only = |seed: u8| ZKProof {
+                proof: vec![1u8; 64],
+                public_inputs: vec![[seed; 32], [seed; 32], bob_hash, domain],
+            };
+
+            assert_eq!(
+                contract.verify_and_mint_legacy(
+                    base_only(1), [1; 32], [1; 32], bob_hash, accounts.bob, 1000, 0, 0
+                ),
+                Err(BridgeError::IncompletePublicInputs)
+            );
+            assert_eq!(
+                contract
+                    .verify_and_mint_to_hash(base_only(1), [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::IncompletePublicInputs)
+            );
+            assert!(!contract.is_nullifier_used([1; 32]));
+
+            // Chain 0's id input is all zeros, which is its correct binding
+            let proof = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            assert_eq!(proof.public_inputs[4], [0u8; 32]);
+            assert_eq!(
+                contract.verify_and_mint_to_hash(proof, [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.get_hashed_balance(bob_hash), 1000);
+        }
+
+        #[ink::test]
+        fn test_global_daily_cap_resets_each_day() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_global_daily_cap(3000).unwrap();
+            mint_to(&mut contract, accounts.bob, 2000, 1);
+            mint_to(&mut contract, accounts.charlie, 1000, 2);
+            assert_eq!(contract.get_global_daily_volume(), 3000);
+
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::GlobalDailyCapExceeded)
+            );
+            assert!(!contract.is_nullifier_used([3; 32]));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY_MS);
+            assert_eq!(contract.get_global_daily_volume(), 0);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 3000)
+            );
+            assert_eq!(contract.get_global_daily_volume(), 1000);
+        }
+
+        #[ink::test]
+        fn test_commit_reveal_mint() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            contract.set_commit_reveal(true, 1000).unwrap();
+            let bob_hash = register(&mut contract, accounts.bob);
+            let proof = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            let salt = [9u8; 32];
+
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::CommitRevealRequired)
+            );
+
+            // Relayer charlie commits, then must wait out the delay
+            set_caller(accounts.charlie);
+            contract.register_relayer().unwrap();
+            let blinded =
+                contract.mint_commit_hash(accounts.charlie, proof.proof.clone(), [1; 32], salt);
+            contract.commit_mint(blinded).unwrap();
+            assert_eq!(
+                contract.reveal_mint(proof.clone(), [1; 32], [1; 32], bob_hash, 1000, 0, 0, salt),
+                Err(BridgeError::MintCommitNotReady)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+
+            // A relayer who copied the proof has no matching commitment
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.reveal_mint(proof.clone(), [1; 32], [1; 32], bob_hash, 1000, 0, 0, salt),
+                Err(BridgeError::MintCommitNotFound)
+            );
+
+            // Revealing doesn't skip the proof of work
+            set_caller(accounts.alice);
+            contract.set_pow_difficulty(8).unwrap();
+            let nonce = (0u64..)
+                .find(|nonce| contract.pow_hash(proof.proof.clone(), [1; 32], *nonce)[0] == 0)
+                .unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.reveal_mint(proof.clone(), [1; 32], [1; 32], bob_hash, 1000, 0, 0, salt),
+                Err(BridgeError::InsufficientPow)
+            );
+            assert_eq!(
+                contract.reveal_mint(proof, [1; 32], [1; 32], bob_hash, 1000, 0, nonce, salt),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_owner_implicitly_holds_every_role() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            // The owner relays without registering
+            assert!(contract.has_role(accounts.alice, Role::Relayer));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+
+            // Anyone else must register first
+            set_caller(accounts.charlie);
+            assert!(!contract.has_role(accounts.charlie, Role::Relayer));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+            contract.register_relayer().unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+
+            // Being a relayer grants nothing owner-only
+            assert!(!contract.has_role(accounts.charlie, Role::Owner));
+            assert_eq!(contract.set_paused(true), Err(BridgeError::Unauthorized));
+            assert_eq!(
+                contract.update_config(Some(1), None, None, None),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_observer_is_notified() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let observer = AccountId::from([0x0b; 32]);
+
+            assert_eq!(
+                contract.set_observer(Some(observer)),
+                Err(BridgeError::CalleeNotApproved)
+            );
+            contract.set_approved_callee(observer, true).unwrap();
+            contract.set_observer(Some(observer)).unwrap();
+
+            mint_to(&mut contract, accounts.bob, 2000, 1);
+            set_caller(accounts.bob);
+            contract.burn_and_bridge(500, [9; 32], [8; 32]).unwrap();
+            set_caller(accounts.alice);
+            contract.set_pause_flags(PauseFlags::MINT).unwrap();
+
+            assert_eq!(notification_codes(), vec![NOTIFY_MINT, NOTIFY_BURN, NOTIFY_PAUSE]);
+            let (_, _, data) = NOTIFICATIONS.with(|log| log.borrow()[0].clone());
+            assert_eq!(data, ([1u8; 32], accounts.bob, 2000u128).encode());
+        }
+
+        #[ink::test]
+        fn test_reverting_observer_does_not_block_operations() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let observer = AccountId::from(REVERTING_OBSERVER);
+            contract.set_approved_callee(observer, true).unwrap();
+            contract.set_observer(Some(observer)).unwrap();
+
+            mint_to(&mut contract, accounts.bob, 2000, 1);
+            set_caller(accounts.bob);
+            assert_eq!(contract.burn_and_bridge(500, [9; 32], [8; 32]), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_paused(true), Ok(()));
+
+            assert_eq!(contract.balance_of(accounts.bob), 1500);
+            assert!(notification_codes().is_empty());
+        }
+
+        #[ink::test]
+        fn test_effective_min_rises_with_nullifier_burst() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_nullifier_rate_threshold(2).unwrap();
+            assert_eq!(contract.effective_min_mint(), 1000);
+
+            mint_to(&mut contract, accounts.bob, 1000, 1);
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+
+            // Two dust mints in one window double the floor
+            assert_eq!(contract.effective_min_mint(), 2000);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::AmountTooLow)
+            );
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [3; 32], [3; 32], bob_hash, 2000, 0, 0),
+                receipt(2000, 0, 4000)
+            );
+
+            // The floor relaxes once the window passes
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                NULLIFIER_RATE_WINDOW_MS,
+            );
+            assert_eq!(contract.effective_min_mint(), 1000);
+        }
+
+        #[ink::test]
+        fn test_batched_mints_settle_as_one_credit() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_batch_window(60_000).unwrap();
+
+            for seed in 1..=3 {
+                mint_to(&mut contract, accounts.bob, 1000, seed);
+            }
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+            assert_eq!(contract.get_pending_credit(accounts.bob), 3000);
+            assert!((1..=3).all(|seed| contract.is_nullifier_used([seed; 32])));
+            assert_eq!(contract.settle_batch(), Err(BridgeError::BatchWindowOpen));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(60_000);
+            assert_eq!(contract.settle_batch(), Ok(1));
+            assert_eq!(contract.balance_of(accounts.bob), 3000);
+            assert_eq!(contract.get_total_minted(), 3000);
+            assert_eq!(contract.get_pending_credit(accounts.bob), 0);
+
+            let minted = emitted::<FundsMinted>();
+            assert_eq!(minted.len(), 1);
+            assert_eq!(minted[0].recipient, accounts.bob);
+            assert_eq!(minted[0].amount, 3000);
+            assert_eq!(contract.settle_batch(), Ok(0));
+        }
+
+        #[ink::test]
+        fn test_settle_batch_drains_queue_in_bounded_steps() {
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_batch_window(60_000).unwrap();
+
+            let recipients: Vec<AccountId> = (1..=MAX_SETTLE_BATCH as u8 + 2)
+                .map(|seed| AccountId::from([seed.wrapping_add(100); 32]))
+                .collect();
+            for (seed, recipient) in (1u8..).zip(recipients.iter()) {
+                mint_to(&mut contract, *recipient, 1000, seed);
+            }
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(60_000);
+            assert_eq!(contract.settle_batch(), Ok(MAX_SETTLE_BATCH));
+            assert_eq!(contract.balance_of(recipients[0]), 1000);
+            assert_eq!(contract.get_pending_credit(recipients[MAX_SETTLE_BATCH as usize]), 1000);
+
+            assert_eq!(contract.settle_batch(), Ok(2));
+            assert!(recipients.iter().all(|r| contract.balance_of(*r) == 1000));
+            assert_eq!(contract.settle_batch(), Ok(0));
+        }
+
+        #[ink::test]
+        fn test_strict_inputs_reject_non_canonical() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+
+            // Blake2 recipient hashes are rarely below r, so register the
+            // canonical reduction of bob's
+            let bob_hash =
+                contract.to_canonical_input(contract.recipient_hash_of(accounts.bob), false);
+            set_caller(accounts.bob);
+            contract.register_recipient(bob_hash).unwrap();
+            set_caller(accounts.alice);
+
+            // r + 1 reduces to 1, so it is accepted only outside strict mode
+            let mut r_plus_one = Fr(BN254_MODULUS).to_bytes_be();
+            r_plus_one[31] += 1;
+            assert!(!is_canonical_field(&r_plus_one));
+            assert!(is_canonical_field(&[1; 32]));
+
+            contract.set_strict_inputs(true).unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], r_plus_one, [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::NonCanonicalInput)
+            );
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+
+            contract.set_strict_inputs(false).unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], r_plus_one, [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+        }
+
+        #[ink::test]
+        fn test_max_relayers_caps_registration() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            contract.set_max_relayers(2).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            assert_eq!(contract.get_relayer_count(), 2);
+
+            set_caller(accounts.django);
+            assert_eq!(contract.register_relayer(), Err(BridgeError::RelayerLimitReached));
+            assert!(!contract.is_relayer(accounts.django));
+
+            // Leaving frees a seat
+            set_caller(accounts.bob);
+            assert_eq!(contract.deregister_relayer(), Ok(()));
+            assert_eq!(contract.get_relayer_count(), 1);
+
+            set_caller(accounts.django);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            assert_eq!(contract.get_relayer_count(), 2);
+        }
+
+        #[ink::test]
+        fn test_grace_period_reserves_mint_for_committed_recipient() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let charlie_hash = register(&mut contract, accounts.charlie);
+            contract.set_recipient_grace_period(60_000).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            announce(&mut contract, [7; 32], [1; 32], bob_hash, 1000).unwrap();
+            announce(&mut contract, [8; 32], [2; 32], bob_hash, 1000).unwrap();
+
+            // The announced recipient must be the one the lock's proof binds
+            let bound_to_bob = zk(&contract, vec![1u8; 64], [9; 32], [3; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.announce_lock(bound_to_bob, [9; 32], [3; 32], charlie_hash, 1000, 0),
+                Err(BridgeError::InvalidProof)
+            );
+            assert_eq!(contract.get_lock_announcement([9; 32]), None);
+
+            // Within the window the relayer can't redirect
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [7; 32], [1; 32], charlie_hash, 1000, 0, 0),
+                Err(BridgeError::RecipientMismatch)
+            );
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [7; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+
+            // Only bob can name an alternate, and it only counts after the window
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.designate_alternate_recipient([8; 32], charlie_hash),
+                Err(BridgeError::Unauthorized)
+            );
+            set_caller(accounts.bob);
+            contract.designate_alternate_recipient([8; 32], charlie_hash).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [8; 32], [2; 32], charlie_hash, 1000, 0, 0),
+                Err(BridgeError::RecipientMismatch)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(61_000);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [8; 32], [2; 32], charlie_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.charlie), 1000);
+        }
+
+        #[ink::test]
+        fn test_status_word_packs_state() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, (3 << STATUS_SUPPLY_SHIFT) + 5, 1);
+            mint_to(&mut contract, accounts.bob, 1000, 2);
+            contract.set_pause_flags(PauseFlags::BURN).unwrap();
+
+            let word = contract.status_word();
+            assert_eq!((word >> 56) as u8, contract.get_pause_flags());
+            assert_eq!((word >> 48) as u8, STATUS_WORD_VERSION);
+            let net = contract.get_total_minted() - contract.get_total_burned();
+            assert_eq!(((word >> 32) & 0xFFFF) as u128, net >> STATUS_SUPPLY_SHIFT);
+            assert_eq!((word >> 32) & 0xFFFF, 3);
+            assert_eq!(word & 0xFFFF_FFFF, contract.get_commitment_count());
+            assert_eq!(contract.get_commitment_count(), 2);
+        }
+
+        #[ink::test]
+        fn test_congestion_fee_scales_with_pending_locks() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            assert_eq!(contract.current_dynamic_fee_bps(), 30);
+
+            assert_eq!(
+                contract.set_congestion_fee(100, 50, 4),
+                Err(BridgeError::InvalidFeeBounds)
+            );
+            contract.set_congestion_fee(10, 90, 4).unwrap();
+            assert_eq!(contract.current_dynamic_fee_bps(), 10);
+
+            let bob_hash = register(&mut contract, accounts.bob);
+            for seed in 1..=6u8 {
+                announce(&mut contract, [seed; 32], [seed; 32], bob_hash, 10_000).unwrap();
+            }
+            assert_eq!(contract.get_pending_lock_count(), 6);
+            // Past the target the fee stays at the ceiling
+            assert_eq!(contract.current_dynamic_fee_bps(), 90);
+
+            // Minting drains the backlog: 5 pending is still saturated
+            mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 10_000, 0, 0).unwrap();
+            assert_eq!(contract.balance_of(accounts.bob), 10_000 - 90);
+
+            for seed in 2..=5u8 {
+                mint(&mut contract, vec![1u8; 64], [seed; 32], [seed; 32], bob_hash, 10_000, 0, 0)
+                    .unwrap();
+            }
+            // One lock left pending: a quarter of the way up
+            assert_eq!(contract.get_pending_lock_count(), 1);
+            assert_eq!(contract.current_dynamic_fee_bps(), 30);
+
+            // Announcements without a matching proof don't count
+            let bound_to_bob = zk(&contract, vec![1u8; 64], [9; 32], [9; 32], bob_hash, 10_000, 0);
+            assert_eq!(
+                contract.announce_lock(bound_to_bob, [9; 32], [9; 32], [0xab; 32], 10_000, 0),
+                Err(BridgeError::InvalidProof)
+            );
+            assert_eq!(contract.get_pending_lock_count(), 1);
+
+            // An abandoned lock stops counting once it expires
+            assert_eq!(
+                contract.expire_announcement([6; 32]),
+                Err(BridgeError::AnnouncementNotExpired)
+            );
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                ANNOUNCEMENT_TTL_MS,
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(contract.expire_announcement([6; 32]), Ok(()));
+            assert_eq!(contract.get_pending_lock_count(), 0);
+            assert_eq!(contract.current_dynamic_fee_bps(), 10);
+            assert_eq!(
+                contract.expire_announcement([6; 32]),
+                Err(BridgeError::CommitmentNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn test_mint_to_hash_and_claim_by_preimage() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+
+            // No registration: bob's account stays off-chain
+            let proof = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.verify_and_mint_to_hash(proof, [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.get_hashed_balance(bob_hash), 1000);
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+            assert_eq!(contract.get_total_minted(), 1000);
+            assert_eq!(emitted::<FundsMintedToHash>()[0].recipient_hash, bob_hash);
+
+            // Only bob can claim, and only with a proof bound to the mint
+            let claim = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [1; 32], claim.clone()),
+                Err(BridgeError::Unauthorized)
+            );
+            set_caller(accounts.bob);
+            let malformed = zk(&contract, vec![1u8; 8], [1; 32], [1; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [1; 32], malformed),
+                Err(BridgeError::InvalidProof)
+            );
+            let other_nullifier = zk(&contract, vec![1u8; 64], [1; 32], [2; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [1; 32], other_nullifier),
+                Err(BridgeError::InvalidProof)
+            );
+            let bob_only = ZKProof {
+                proof: vec![1u8; 64],
+                public_inputs: vec![
+                    bob_hash,
+                    bob_hash,
+                    bob_hash,
+                    contract.get_domain_separator(),
+                ],
+            };
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [1; 32], bob_only),
+                Err(BridgeError::InvalidProof)
+            );
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [2; 32], claim.clone()),
+                Err(BridgeError::NothingToClaim)
+            );
+            assert_eq!(contract.claim_by_preimage(accounts.bob, [1; 32], claim.clone()), Ok(1000));
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+            assert_eq!(contract.get_hashed_balance(bob_hash), 0);
+            assert_eq!(
+                contract.claim_by_preimage(accounts.bob, [1; 32], claim),
+                Err(BridgeError::NothingToClaim)
+            );
+        }
+
+        #[ink::test]
+        fn test_pow_nonce_checked_before_verification() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            contract.set_pow_difficulty(8).unwrap();
+
+            let nullifier = [1u8; 32];
+            let valid = (0u64..)
+                .find(|nonce| contract.pow_hash(vec![1u8; 64], nullifier, *nonce)[0] == 0)
+                .unwrap();
+            let invalid = (0u64..)
+                .find(|nonce| contract.pow_hash(vec![1u8; 8], nullifier, *nonce)[0] != 0)
+                .unwrap();
+            // The work is bound to the proof it was done for
+            assert_ne!(contract.pow_hash(vec![2u8; 64], nullifier, valid)[0], 0);
+
+            // A malformed proof would fail verification; the nonce is rejected first
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 8], [1; 32], nullifier, bob_hash, 1000, 0, invalid),
+                Err(BridgeError::InsufficientPow)
+            );
+            assert_eq!(contract.get_verification_count(), 0);
+
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], nullifier, bob_hash, 1000, 0, valid),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_mint_and_swap_enforces_min_out() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let dex = AccountId::from([0xd0; 32]);
+            let reverting_dex = AccountId::from(REVERTING_DEX);
+            let shorting_dex = AccountId::from(SHORTING_DEX);
+            let out_token = AccountId::from([0x70; 32]);
+            let domain = contract.get_domain_separator();
+            let params = |seed: u8| MintParams {
+                proof: ZKProof {
+                    proof: vec![1u8; 64],
+                    public_inputs: vec![[seed; 32], [seed; 32], bob_hash, domain],
+                },
+                commitment_hash: [seed; 32],
+                nullifier_hash: [seed; 32],
+                recipient_hash: bob_hash,
+                amount: 2000,
+                source_chain: 0,
+                pow_nonce: 0,
+            };
+
+            assert_eq!(
+                contract.mint_and_swap(params(1), dex, out_token, Vec::new(), 0),
+                Err(BridgeError::CalleeNotApproved)
+            );
+            contract.set_approved_callee(dex, true).unwrap();
+            contract.set_approved_callee(reverting_dex, true).unwrap();
+            contract.set_approved_callee(shorting_dex, true).unwrap();
+            assert_eq!(
+                contract.mint_and_swap(params(1), dex, out_token, Vec::new(), 0),
+                Err(BridgeError::CalleeNotApproved)
+            );
+            contract.set_approved_callee(out_token, true).unwrap();
+
+            // Only the recipient picks how their mint is swapped
+            assert_eq!(
+                contract.mint_and_swap(params(1), dex, out_token, Vec::new(), 0),
+                Err(BridgeError::Unauthorized)
+            );
+            contract.add_relayer(accounts.bob).unwrap();
+            set_caller(accounts.bob);
+
+            // The pool pays 1000 for 2000; asking for more fails. On-chain the
+            // error reverts the mint as well, which the off-chain env can't show.
+            assert_eq!(
+                contract.mint_and_swap(params(1), dex, out_token, Vec::new(), 1001),
+                Err(BridgeError::SlippageExceeded)
+            );
+            assert_eq!(
+                contract.mint_and_swap(params(2), reverting_dex, out_token, Vec::new(), 0),
+                Err(BridgeError::SwapFailed)
+            );
+            // A DEX claiming 1000 while delivering 500 is held to what arrived
+            assert_eq!(
+                contract.mint_and_swap(params(4), shorting_dex, out_token, Vec::new(), 1000),
+                Err(BridgeError::SlippageExceeded)
+            );
+            assert!(emitted::<MintFailed>().is_empty());
+
+            assert_eq!(
+                contract.mint_and_swap(params(3), dex, out_token, Vec::new(), 1000),
+                Ok(1000)
+            );
+            let swapped = emitted::<MintSwapped>();
+            assert_eq!(swapped.len(), 1);
+            assert_eq!(swapped[0].recipient, accounts.bob);
+            assert_eq!(swapped[0].amount_in, 2000);
+            assert_eq!(swapped[0].amount_out, 1000);
+        }
+
+        #[ink::test]
+        fn test_failed_withdraw_keeps_balance_for_retry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let bridge = AccountId::from([0xCC; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(bridge);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(bridge, 10_000_000);
+
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            fund_reserve(&mut contract, 10_000_000);
+            mint_to(&mut contract, accounts.bob, 2_000_000, 1);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.withdraw(AccountId::from(REJECTING_RECIPIENT), 1_500_000),
+                Err(BridgeError::TransferFailed)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 2_000_000);
+            assert_eq!(contract.get_total_burned(), 0);
+            assert_eq!(contract.get_native_reserve(), 10_000_000);
+
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.django)
+                .unwrap_or(0);
+            assert_eq!(contract.withdraw(accounts.django, 1_500_000), Ok(()));
+            let after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.django)
+                .unwrap_or(0);
+            assert_eq!(after - before, 1_500_000);
+            assert_eq!(contract.balance_of(accounts.bob), 500_000);
+            assert_eq!(contract.get_total_burned(), 1_500_000);
+        }
+
+        #[ink::test]
+        fn test_commitment_fee_override() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 100);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.set_commitment_fee_override([1; 32], Some(10)),
+                Err(BridgeError::Unauthorized)
+            );
+            set_caller(accounts.alice);
+            contract.set_fee_manager(accounts.charlie, true).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.set_commitment_fee_override([1; 32], Some(10_001)),
+                Err(BridgeError::InvalidFeeBounds)
+            );
+            contract.set_commitment_fee_override([1; 32], Some(10)).unwrap();
+            assert_eq!(contract.get_commitment_fee_override([1; 32]), Some(10));
+
+            // 0.1% on the negotiated commitment, the standard 1% elsewhere
+            set_caller(accounts.alice);
+            mint_to(&mut contract, accounts.bob, 10_000, 1);
+            assert_eq!(contract.balance_of(accounts.bob), 9_990);
+            mint_to(&mut contract, accounts.bob, 10_000, 2);
+            assert_eq!(contract.balance_of(accounts.bob), 9_990 + 9_900);
+        }
+
+        #[ink::test]
+        fn test_settlement_event_reflects_conversion() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            mint_to(&mut contract, accounts.bob, 20_000, 1);
+
+            let settlement = &emitted::<SettlementComputed>()[0];
+            assert_eq!(settlement.commitment_hash, [1; 32]);
+            assert_eq!(settlement.source_amount, 20_000);
+            assert_eq!(settlement.rate, SETTLEMENT_RATE_ONE);
+            assert_eq!(settlement.fee, 60);
+            assert_eq!(settlement.dest_amount, 19_940);
+            assert_eq!(
+                settlement.source_amount * settlement.rate / SETTLEMENT_RATE_ONE,
+                settlement.dest_amount + settlement.fee
+            );
+            assert_eq!(contract.balance_of(accounts.bob), settlement.dest_amount);
+        }
+
+        #[ink::test]
+        fn test_new_relayer_waits_out_activation_delay() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_self_registration(true).unwrap();
+            let bob_hash = register(&mut contract, accounts.bob);
+            contract.set_relayer_activation_delay(60_000).unwrap();
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            contract.add_relayer(accounts.django).unwrap();
+            set_caller(accounts.charlie);
+            contract.register_relayer().unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::RelayerNotYetActive)
+            );
+            // Admin-added relayers wait too
+            set_caller(accounts.django);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::RelayerNotYetActive)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(61_000);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 2000);
+        }
+
+        #[ink::test]
+        fn test_strict_recipient_binding_selects_resolution() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            // Strict: the named recipient is ignored, bob is resolved from the registry
+            let proof = zk(&contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.verify_and_mint_legacy(
+                    proof, [1; 32], [1; 32], bob_hash, accounts.charlie, 1000, 0, 0
+                ),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+            assert_eq!(contract.balance_of(accounts.charlie), 0);
+
+            // Loose: the relayer-named recipient is credited as given
+            contract.set_strict_recipient_binding(false).unwrap();
+            let proof = zk(&contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0);
+            assert_eq!(
+                contract.verify_and_mint_legacy(
+                    proof, [2; 32], [2; 32], bob_hash, accounts.charlie, 1000, 0, 0
+                ),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+            assert_eq!(contract.balance_of(accounts.charlie), 1000);
+        }
+
+        #[ink::test]
+        fn test_delegated_admin_daily_limit() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            contract.set_admin(accounts.bob, Some(1_000)).unwrap();
+
+            set_caller(accounts.bob);
+            contract
+                .admin_adjust_balance(accounts.charlie, 600, String::from("fix"))
+                .unwrap();
+            // Decreases count against the budget too
+            contract
+                .admin_adjust_balance(accounts.charlie, 200, String::from("fix"))
+                .unwrap();
+            assert_eq!(contract.get_admin_limits(accounts.bob).unwrap().spent_today, 1_000);
+            assert_eq!(
+                contract.admin_adjust_balance(accounts.charlie, 201, String::from("fix")),
+                Err(BridgeError::AdminLimitExceeded)
+            );
+            assert_eq!(contract.balance_of(accounts.charlie), 200);
+
+            // The budget resets the next day
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY_MS);
+            contract
+                .admin_adjust_balance(accounts.charlie, 201, String::from("fix"))
+                .unwrap();
+            assert_eq!(contract.get_admin_limits(accounts.bob).unwrap().spent_today, 1);
+
+            // Removed admins are plain accounts again
+            set_caller(accounts.alice);
+            contract.set_admin(accounts.bob, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.admin_adjust_balance(accounts.charlie, 0, String::from("fix")),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_proof_bound_expiry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            assert_eq!(contract.proof_expiry_slot(), None);
+
+            contract
+                .set_public_input_schema(PublicInput::EXPIRY, [0u8; 32])
+                .unwrap();
+            assert_eq!(contract.proof_expiry_slot(), Some(3));
+            let domain = contract.get_domain_separator();
+
+            let expiring_at = |expiry: u64, seed: u8| {
+                let mut expiry_input = [0u8; 32];
+                expiry_input[24..].copy_from_slice(&expiry.to_be_bytes());
+                ZKProof {
+                    proof: vec![1u8; 64],
+                    public_inputs: vec![[seed; 32], [seed; 32], bob_hash, expiry_input, domain],
+                }
+            };
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+            assert_eq!(
+                contract.verify_and_mint(
+                    expiring_at(9_999, 1), [1; 32], [1; 32], bob_hash, 1000, 0, 0
+                ),
+                Err(BridgeError::ProofExpired)
+            );
+            assert_eq!(
+                contract.verify_and_mint_legacy(
+                    expiring_at(9_999, 1), [1; 32], [1; 32], bob_hash, accounts.bob, 1000, 0, 0
+                ),
+                Err(BridgeError::ProofExpired)
+            );
+            assert!(!contract.is_nullifier_used([1; 32]));
+
+            assert_eq!(
+                contract.verify_and_mint(
+                    expiring_at(10_000, 2), [2; 32], [2; 32], bob_hash, 1000, 0, 0
+                ),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        /// Circuit with the given values as public inputs and one constraint
+        /// over the first two, for real Groth16 proofs
+        #[cfg(feature = "groth16")]
+        struct BindingCircuit<const N: usize>([ark_bn254::Fr; N]);
+
+        #[cfg(feature = "groth16")]
+        impl<const N: usize> ark_relations::r1cs::ConstraintSynthesizer<ark_bn254::Fr>
+            for BindingCircuit<N>
+        {
+            fn generate_constraints(
+                self,
+                cs: ark_relations::r1cs::ConstraintSystemRef<ark_bn254::Fr>,
+            ) -> ark_relations::r1cs::Result<()> {
+                use ark_relations::lc;
+
+                let mut inputs = Vec::new();
+                for value in self.0 {
+                    inputs.push(cs.new_input_variable(|| Ok(value))?);
+                }
+                let product = cs.new_witness_variable(|| Ok(self.0[0] * self.0[1]))?;
+                cs.enforce_constraint(lc!() + inputs[0], lc!() + inputs[1], lc!() + product)
+            }
+        }
+
+        /// A verifying key and a Groth16-prefixed proof over the given inputs,
+        /// the last being the domain separator
+        #[cfg(feature = "groth16")]
+        fn groth16_proof<const N: usize>(inputs: [[u8; 32]; N]) -> (Vec<u8>, Vec<u8>) {
+            use ark_bn254::{Bn254, Fr as ArkFr};
+            use ark_ff::PrimeField;
+            use ark_groth16::Groth16;
+            use ark_serialize::CanonicalSerialize;
+
+            let mut rng = ark_std::test_rng();
+            let circuit =
+                BindingCircuit(inputs.map(|input| ArkFr::from_be_bytes_mod_order(&input)));
+            let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(
+                BindingCircuit([ArkFr::from(1u8); N]),
+                &mut rng,
+            )
+            .unwrap();
+            let proof = Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &pk, &mut rng)
+                .unwrap();
+
+            let mut vk_bytes = Vec::new();
+            pk.vk.serialize_compressed(&mut vk_bytes).unwrap();
+            let mut proof_bytes = vec![ProofType::Groth16 as u8];
+            proof.serialize_compressed(&mut proof_bytes).unwrap();
+            (vk_bytes, proof_bytes)
+        }
+
+        #[cfg(feature = "groth16")]
+        #[ink::test]
+        fn test_groth16_proof_mints() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let domain = contract.get_domain_separator();
+            let (vk, proof) = groth16_proof([[1; 32], [2; 32], bob_hash, domain]);
+
+            // No key, no verification
+            assert_eq!(
+                mint(&mut contract, proof.clone(), [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            contract.set_verification_key(ProofType::Groth16, vk).unwrap();
+            assert_eq!(
+                mint(&mut contract, proof, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[cfg(feature = "groth16")]
+        #[ink::test]
+        fn test_groth16_proof_binds_expiry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            contract
+                .set_public_input_schema(PublicInput::EXPIRY, [0u8; 32])
+                .unwrap();
+            let expiry_input = |expiry: u64| {
+                let mut input = [0u8; 32];
+                input[24..].copy_from_slice(&expiry.to_be_bytes());
+                input
+            };
+            let domain = contract.get_domain_separator();
+            let (vk, proof) =
+                groth16_proof([[1; 32], [2; 32], bob_hash, expiry_input(5_000), domain]);
+            contract.set_verification_key(ProofType::Groth16, vk).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+
+            // A relayer extending the expiry breaks the proof
+            let extended = ZKProof {
+                proof: proof.clone(),
+                public_inputs: vec![[1; 32], [2; 32], bob_hash, expiry_input(20_000), domain],
+            };
+            assert_eq!(
+                contract.verify_and_mint(extended, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            // The expiry the prover bound has passed
+            let proven = ZKProof {
+                proof,
+                public_inputs: vec![[1; 32], [2; 32], bob_hash, expiry_input(5_000), domain],
+            };
+            assert_eq!(
+                contract.verify_and_mint(proven, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::ProofExpired)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+        }
+
+        #[cfg(feature = "groth16")]
+        #[ink::test]
+        fn test_tampered_groth16_proof_rejected() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let domain = contract.get_domain_separator();
+            let (vk, proof) = groth16_proof([[1; 32], [2; 32], bob_hash, domain]);
+            contract.set_verification_key(ProofType::Groth16, vk).unwrap();
+
+            // Valid proof, different public inputs
+            assert_eq!(
+                mint(&mut contract, proof.clone(), [3; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            // Garbage that merely looks like a proof
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 129], [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            // Same point A negated: still deserializes, fails the pairing check
+            let mut tampered = proof;
+            tampered[32] ^= 0x80;
+            assert_eq!(
+                mint(&mut contract, tampered, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn test_set_verification_key_emits_hash() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+
+            assert_eq!(
+                contract.set_verification_key(ProofType::Groth16, Vec::new()),
+                Err(BridgeError::InvalidVerificationKey)
+            );
+            assert_eq!(contract.get_verification_key(ProofType::Groth16), None);
+
+            contract
+                .set_verification_key(ProofType::Groth16, vec![0xaa; 8])
+                .unwrap();
+            contract
+                .set_verification_key(ProofType::Groth16, vec![0xcc; 8])
+                .unwrap();
+            assert_eq!(contract.get_verification_key(ProofType::Groth16), Some(vec![0xcc; 8]));
+
+            let updates = emitted::<VerificationKeyUpdated>();
+            assert_eq!(updates.len(), 2);
+            assert_eq!(updates[1].proof_type, ProofType::Groth16);
+            assert_eq!(updates[1].key_hash, PolkadotBridgeComplete::hash_proof(&[0xcc; 8]));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.set_verification_key(ProofType::Groth16, vec![0xdd; 8]),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_mismatched_public_inputs_rejected() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let charlie_hash = register(&mut contract, accounts.charlie);
+            let proof = ZKProof {
+                proof: vec![1u8; 64],
+                public_inputs: vec![[1; 32], [2; 32], bob_hash, contract.get_domain_separator()],
+            };
+
+            // Each slot in turn disagrees with the arguments
+            for (commitment, nullifier, recipient_hash) in [
+                ([9; 32], [2; 32], bob_hash),
+                ([1; 32], [9; 32], bob_hash),
+                ([1; 32], [2; 32], charlie_hash),
+                ([2; 32], [1; 32], bob_hash),
+            ] {
+                assert_eq!(
+                    contract.verify_and_mint(
+                        proof.clone(), commitment, nullifier, recipient_hash, 1000, 0, 0
+                    ),
+                    Err(BridgeError::InvalidProof)
+                );
+            }
+            assert_eq!(contract.balance_of(accounts.bob), 0);
+            assert_eq!(contract.balance_of(accounts.charlie), 0);
+            assert!(!contract.is_nullifier_used([2; 32]));
+            assert!(emitted::<MintFailed>().is_empty());
+
+            assert_eq!(
+                contract.verify_and_mint(proof, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_verify_and_mint_returns_receipt() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            let receipt =
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 10_000, 0, 0)
+                    .unwrap();
+            assert_eq!(
+                receipt,
+                MintReceipt {
+                    minted: 9_970,
+                    fee: 30,
+                    new_balance: 9_970,
+                }
+            );
+
+            let receipt =
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 20_000, 0, 0)
+                    .unwrap();
+            assert_eq!(receipt.minted, 19_940);
+            assert_eq!(receipt.fee, 60);
+            assert_eq!(receipt.new_balance, contract.balance_of(accounts.bob));
+
+            // Events carry the same figures as the receipt
+            let settlement = emitted::<SettlementComputed>().pop().unwrap();
+            assert_eq!(settlement.dest_amount, receipt.minted);
+            assert_eq!(settlement.fee, receipt.fee);
+            let minted = emitted::<FundsMinted>().pop().unwrap();
+            assert_eq!(minted.amount, receipt.minted);
+        }
+
+        #[ink::test]
+        fn test_fees_accrue_and_withdraw() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            assert_eq!(contract.get_fee_recipient(), accounts.alice);
+
+            mint_to(&mut contract, accounts.bob, 10_000, 1);
+            mint_to(&mut contract, accounts.bob, 20_000, 2);
+            mint_to(&mut contract, accounts.charlie, 100_000, 3);
+            assert_eq!(contract.get_collected_fees(), 30 + 60 + 300);
+            assert_eq!(contract.get_total_minted(), 130_000 - 390);
+
+            assert_eq!(
+                contract.withdraw_fees(accounts.alice, 391),
+                Err(BridgeError::InsufficientBalance)
+            );
+
+            // Fees go to the named account, not the caller
+            contract.set_fee_recipient(accounts.django).unwrap();
+            assert_eq!(contract.get_fee_recipient(), accounts.django);
+            assert_eq!(contract.withdraw_fees(accounts.django, 300), Ok(()));
+            assert_eq!(contract.balance_of(accounts.django), 300);
+            assert_eq!(contract.balance_of(accounts.alice), 0);
+            assert_eq!(contract.get_collected_fees(), 90);
+            assert_eq!(contract.get_total_minted(), 130_000 - 90);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.withdraw_fees(accounts.django, 90),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_fee_overflow_is_an_error() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            assert_eq!(
+                contract.calculate_fee(&[1; 32], u128::MAX),
+                Err(BridgeError::ArithmeticOverflow)
+            );
+            assert_eq!(contract.calculate_fee(&[1; 32], 10_000), Ok(30));
+
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, u128::MAX, 0, 0),
+                Err(BridgeError::ArithmeticOverflow)
+            );
+            assert!(!contract.is_nullifier_used([1; 32]));
+            assert_eq!(contract.get_total_minted(), 0);
+        }
+
+        #[ink::test]
+        fn test_owner_managed_relayers() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            set_caller(accounts.charlie);
+            assert_eq!(contract.add_relayer(accounts.charlie), Err(BridgeError::Unauthorized));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+
+            // Self-registration is no way around the whitelist
+            assert_eq!(contract.register_relayer(), Err(BridgeError::SelfRegistrationClosed));
+            assert!(!contract.is_relayer(accounts.charlie));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            contract.add_relayer(accounts.charlie).unwrap();
+            assert!(contract.is_relayer(accounts.charlie));
+            assert_eq!(contract.add_relayer(accounts.charlie), Err(BridgeError::AlreadyRegistered));
+            assert_eq!(contract.get_relayer_count(), 1);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+
+            set_caller(accounts.alice);
+            contract.remove_relayer(accounts.charlie).unwrap();
+            assert!(!contract.is_relayer(accounts.charlie));
+            assert_eq!(contract.remove_relayer(accounts.charlie), Err(BridgeError::NotRelayer));
+            assert_eq!(contract.get_relayer_count(), 0);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_self_registration_is_owner_gated() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            assert!(!contract.is_self_registration_open());
+
+            // A stranger cannot buy into the relayer set, not even for free
+            set_caller(accounts.django);
+            assert_eq!(contract.register_relayer(), Err(BridgeError::SelfRegistrationClosed));
+            assert!(!contract.is_relayer(accounts.django));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+            assert_eq!(contract.set_self_registration(true), Err(BridgeError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.set_self_registration(true).unwrap();
+            contract.add_relayer(accounts.charlie).unwrap();
+            set_caller(accounts.django);
+            assert_eq!(contract.register_relayer(), Ok(()));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+
+            // Closing self-registration benches self-registered relayers
+            // only; the owner's whitelist is kept apart
+            set_caller(accounts.alice);
+            contract.set_self_registration(false).unwrap();
+            assert!(!contract.is_relayer(accounts.django));
+            assert!(contract.is_relayer(accounts.charlie));
+            set_caller(accounts.django);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::Unauthorized)
+            );
+            assert_eq!(contract.register_relayer(), Err(BridgeError::SelfRegistrationClosed));
+            assert_eq!(contract.deregister_relayer(), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 2000)
+            );
+        }
+
+        #[ink::test]
+        fn test_permissionless_relaying() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new_with_config(1000, 0, None, 32, false);
+            let bob_hash = register(&mut contract, accounts.bob);
+
+            set_caller(accounts.charlie);
+            assert!(!contract.is_relayer(accounts.charlie));
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 1000);
+        }
+
+        #[ink::test]
+        fn test_two_step_ownership_transfer() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+
+            contract.transfer_ownership(accounts.bob).unwrap();
+            assert_eq!(contract.get_owner(), accounts.alice);
+            assert_eq!(contract.get_pending_owner(), Some(accounts.bob));
+            assert_eq!(emitted::<OwnershipTransferStarted>().len(), 1);
+
+            // Only the nominee can accept
+            set_caller(accounts.charlie);
+            assert_eq!(contract.accept_ownership(), Err(BridgeError::Unauthorized));
+            assert_eq!(contract.cancel_ownership_transfer(), Err(BridgeError::Unauthorized));
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.accept_ownership(), Ok(()));
+            assert_eq!(contract.get_owner(), accounts.bob);
+            assert_eq!(contract.get_pending_owner(), None);
+            let transferred = emitted::<OwnershipTransferred>();
+            assert_eq!(transferred.len(), 1);
+            assert_eq!(transferred[0].previous_owner, accounts.alice);
+            assert_eq!(transferred[0].new_owner, accounts.bob);
+
+            // The old owner is out; a cancelled nomination can't be accepted
+            set_caller(accounts.alice);
+            assert_eq!(contract.transfer_ownership(accounts.alice), Err(BridgeError::Unauthorized));
+            set_caller(accounts.bob);
+            contract.transfer_ownership(accounts.charlie).unwrap();
+            contract.cancel_ownership_transfer().unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(contract.accept_ownership(), Err(BridgeError::Unauthorized));
+            assert_eq!(contract.get_owner(), accounts.bob);
+        }
+
+        #[ink::test]
+        fn test_verify_and_mint_batch() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let domain = contract.get_domain_separator();
+            let item = |seed: u8, amount: u128| MintParams {
+                proof: ZKProof {
+                    proof: vec![1u8; 64],
+                    public_inputs: vec![[seed; 32], [seed; 32], bob_hash, domain],
+                },
+                commitment_hash: [seed; 32],
+                nullifier_hash: [seed; 32],
+                recipient_hash: bob_hash,
+                amount,
+                source_chain: 0,
+                pow_nonce: 0,
+            };
+
+            let mut short_proof = item(3, 1000);
+            short_proof.proof.proof = vec![1u8; 8];
+            let results = contract
+                .verify_and_mint_batch(vec![
+                    item(1, 1000),
+                    item(2, 10),
+                    short_proof,
+                    item(1, 1000),
+                    item(4, 2000),
+                ], false)
+                .unwrap();
+            assert_eq!(results.len(), 5);
+            assert_eq!(results[0], receipt(1000, 0, 1000));
+            assert_eq!(results[1], Err(BridgeError::AmountTooLow));
+            assert_eq!(results[2], Err(BridgeError::InvalidProof));
+            assert_eq!(results[3], Err(BridgeError::NullifierUsed));
+            assert_eq!(results[4].map(|receipt| receipt.minted), Ok(2000));
+            assert_eq!(contract.balance_of(accounts.bob), 3000);
+
+            // Atomic mode stops at the failing entry and returns its error,
+            // which on-chain reverts the entries before it too; the off-chain
+            // env can't show that
+            assert_eq!(
+                contract.verify_and_mint_batch(
+                    vec![item(6, 1000), item(7, 10), item(8, 1000)],
+                    true
+                ),
+                Err(BridgeError::AmountTooLow)
+            );
+            assert!(!contract.is_nullifier_used([8; 32]));
+
+            // An item failing after all its checks leaves nothing behind
+            let charlie_hash = register(&mut contract, accounts.charlie);
+            let headroom = u128::MAX - contract.get_total_minted() - 500;
+            contract
+                .admin_adjust_balance(accounts.charlie, headroom, String::new())
+                .unwrap();
+            let mut overflowing = item(5, 1000);
+            overflowing.recipient_hash = charlie_hash;
+            overflowing.proof.public_inputs[2] = charlie_hash;
+            let verified = contract.get_verification_count();
+            let results = contract.verify_and_mint_batch(vec![overflowing], false).unwrap();
+            assert_eq!(results[0], Err(BridgeError::ArithmeticOverflow));
+            assert_eq!(contract.get_verification_count(), verified);
+            assert!(!contract.is_nullifier_used([5; 32]));
+            assert_eq!(contract.get_commitment([5; 32]), None);
+            assert_eq!(contract.get_collected_fees(), 0);
+
+            let oversized = (0..=MAX_MINT_BATCH as u8).map(|seed| item(seed, 1000)).collect();
+            assert_eq!(
+                contract.verify_and_mint_batch(oversized, false),
+                Err(BridgeError::BatchTooLarge)
+            );
+        }
+
+        #[ink::test]
+        fn test_mint_rate_limit_window() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            assert_eq!(contract.get_remaining_mint_quota(), u128::MAX);
+
+            contract.update_config(None, None, Some(3000), Some(60)).unwrap();
+            mint_to(&mut contract, accounts.bob, 2000, 1);
+            assert_eq!(contract.get_remaining_mint_quota(), 1000);
+
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1500, 0, 0),
+                Err(BridgeError::RateLimitExceeded)
+            );
+            assert!(!contract.is_nullifier_used([2; 32]));
+            mint_to(&mut contract, accounts.bob, 1000, 3);
+            assert_eq!(contract.get_remaining_mint_quota(), 0);
+
+            // Still inside the window one millisecond before it closes
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(59_999);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1500, 0, 0),
+                Err(BridgeError::RateLimitExceeded)
+            );
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(60_000);
+            assert_eq!(contract.get_remaining_mint_quota(), 3000);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1500, 0, 0),
+                receipt(1500, 0, 4500)
+            );
+            assert_eq!(contract.get_remaining_mint_quota(), 1500);
+            assert_eq!(contract.balance_of(accounts.bob), 4500);
+        }
+
+        #[ink::test]
+        fn test_blacklisted_accounts_are_frozen() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            let charlie_hash = register(&mut contract, accounts.charlie);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.set_blacklisted(accounts.charlie, true),
+                Err(BridgeError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            contract.set_blacklisted(accounts.charlie, true).unwrap();
+            contract.set_blacklisted(accounts.bob, true).unwrap();
+            assert!(contract.is_blacklisted(accounts.bob));
+
+            // A blacklisted recipient can't receive a mint
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], charlie_hash, 1000, 0, 0),
+                Err(BridgeError::AccountBlacklisted)
+            );
+            assert!(!contract.is_nullifier_used([2; 32]));
+
+            // A blacklisted holder keeps its balance but can't move it
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.transfer(accounts.django, 1000, Vec::new()),
+                Err(PSP22Error::Custom(String::from("AccountBlacklisted")))
+            );
+            assert_eq!(
+                contract.burn_and_bridge(1000, [9; 32], [8; 32]),
+                Err(BridgeError::AccountBlacklisted)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 5000);
+
+            set_caller(accounts.alice);
+            contract.set_blacklisted(accounts.bob, false).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.transfer(accounts.charlie, 1000, Vec::new()),
+                Err(PSP22Error::Custom(String::from("AccountBlacklisted")))
+            );
+            assert_eq!(contract.transfer(accounts.django, 1000, Vec::new()), Ok(()));
+            assert_eq!(contract.balance_of(accounts.bob), 4000);
+        }
+
+        #[ink::test]
+        fn test_approve_and_transfer_from() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+
+            set_caller(accounts.bob);
+            contract.approve(accounts.charlie, 3000).unwrap();
+            assert_eq!(contract.allowance(accounts.bob, accounts.charlie), 3000);
+            assert_eq!(emitted::<Approval>()[0].amount, 3000);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.transfer_from(accounts.bob, accounts.django, 2000, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 3000);
+            assert_eq!(contract.balance_of(accounts.django), 2000);
+            assert_eq!(contract.allowance(accounts.bob, accounts.charlie), 1000);
+
+            let transfers = emitted::<Transfer>();
+            let transfer = transfers.last().unwrap();
+            assert_eq!(transfer.from, Some(accounts.bob));
+            assert_eq!(transfer.to, Some(accounts.django));
+            assert_eq!(transfer.value, 2000);
+
+            assert_eq!(
+                contract.transfer_from(accounts.bob, accounts.django, 1001, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            // No allowance from django at all
+            assert_eq!(
+                contract.transfer_from(accounts.django, accounts.charlie, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(contract.allowance(accounts.bob, accounts.charlie), 1000);
+            assert_eq!(contract.balance_of(accounts.bob), 3000);
+
+            set_caller(accounts.bob);
+            contract.increase_allowance(accounts.charlie, 500).unwrap();
+            assert_eq!(contract.allowance(accounts.bob, accounts.charlie), 1500);
+            assert_eq!(
+                contract.decrease_allowance(accounts.charlie, 1501),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            contract.decrease_allowance(accounts.charlie, 1500).unwrap();
+            assert_eq!(contract.allowance(accounts.bob, accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn test_mint_and_burn_emit_psp22_transfers() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+
+            set_caller(accounts.bob);
+            contract.burn_and_bridge(2000, [9; 32], [8; 32]).unwrap();
+
+            let transfers = emitted::<Transfer>();
+            assert_eq!(transfers.len(), 2);
+            assert_eq!(transfers[0].from, None);
+            assert_eq!(transfers[0].to, Some(accounts.bob));
+            assert_eq!(transfers[0].value, 5000);
+            assert_eq!(transfers[1].from, Some(accounts.bob));
+            assert_eq!(transfers[1].to, None);
+            assert_eq!(transfers[1].value, 2000);
+        }
+
+        #[ink::test]
+        fn test_token_metadata() {
+            let contract = PolkadotBridgeComplete::new(1000, 30);
+            assert_eq!(contract.token_name(), Some(String::from("Wrapped XLM")));
+            assert_eq!(contract.token_symbol(), Some(String::from("wXLM")));
+            assert_eq!(contract.token_decimals(), 7);
+
+            let contract = PolkadotBridgeComplete::new_with_metadata(
+                1000,
+                30,
+                String::from("Wrapped Lumens"),
+                String::from("WLUM"),
+                10,
+            );
+            assert_eq!(contract.token_name(), Some(String::from("Wrapped Lumens")));
+            assert_eq!(contract.token_symbol(), Some(String::from("WLUM")));
+            assert_eq!(contract.token_decimals(), 10);
+            assert_eq!(contract.current_dynamic_fee_bps(), 30);
+        }
+
+        #[ink::test]
+        fn test_reentrancy_guard() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.alice, 5000, 1);
+            let bob_hash = register(&mut contract, accounts.bob);
+            let alice_hash = register(&mut contract, accounts.alice);
+
+            // A callee re-entering each guarded message mid-call
+            let dex = AccountId::from([0xaa; 32]);
+            contract.set_approved_callee(dex, true).unwrap();
+            let reentered = contract.with_guard(|this| {
+                let proof = zk(this, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0);
+                let params = MintParams {
+                    proof: proof.clone(),
+                    commitment_hash: [2; 32],
+                    nullifier_hash: [2; 32],
+                    recipient_hash: bob_hash,
+                    amount: 1000,
+                    source_chain: 0,
+                    pow_nonce: 0,
+                };
+                let transfers = [
+                    this.transfer(accounts.bob, 1000, Vec::new()).err(),
+                    this.transfer_from(accounts.alice, accounts.bob, 1000, Vec::new()).err(),
+                ];
+                Ok((transfers, [
+                    this.burn_and_bridge(1000, [9; 32], [8; 32]).err(),
+                    this.withdraw(accounts.bob, 1000).err(),
+                    mint(this, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0).err(),
+                    this.verify_and_mint_legacy(
+                        proof.clone(),
+                        [2; 32],
+                        [2; 32],
+                        bob_hash,
+                        accounts.bob,
+                        1000,
+                        0,
+                        0,
+                    )
+                    .err(),
+                    this.verify_and_mint_to_hash(
+                        proof.clone(),
+                        [2; 32],
+                        [2; 32],
+                        bob_hash,
+                        1000,
+                        0,
+                        0,
+                    )
+                    .err(),
+                    this.reveal_mint(proof, [2; 32], [2; 32], bob_hash, 1000, 0, 0, [0; 32]).err(),
+                    this.mint_and_swap(
+                        MintParams { recipient_hash: alice_hash, ..params },
+                        dex,
+                        dex,
+                        Vec::new(),
+                        0,
+                    )
+                    .err(),
+                ]))
+            });
+            assert_eq!(
+                reentered,
+                Ok((
+                    [
+                        Some(PSP22Error::Custom(String::from("Reentrancy"))),
+                        Some(PSP22Error::Custom(String::from("Reentrancy"))),
+                    ],
+                    [Some(BridgeError::Reentrancy); 7]
+                ))
+            );
+            assert_eq!(contract.balance_of(accounts.alice), 5000);
+            assert!(!contract.is_nullifier_used([2; 32]));
+
+            // The lock is released once the outer call returns
+            assert!(!contract.locked);
+            assert_eq!(contract.transfer(accounts.bob, 1000, Vec::new()), Ok(()));
+            assert_eq!(contract.burn_and_bridge(1000, [9; 32], [8; 32]), Ok(()));
+            assert_eq!(contract.balance_of(accounts.alice), 3000);
+        }
+
+        #[ink::test]
+        fn test_nullifier_replay_emits_double_spend_attempt() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 1000, 1);
+            let charlie_hash = register(&mut contract, accounts.charlie);
+            assert!(emitted::<DoubleSpendAttempt>().is_empty());
+
+            contract.add_relayer(accounts.django).unwrap();
+            set_caller(accounts.django);
+            // On its own the replay reverts, so the error is what's reported
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [1; 32], charlie_hash, 1000, 0, 0),
+                Err(BridgeError::NullifierUsed)
+            );
+            assert!(emitted::<DoubleSpendAttempt>().is_empty());
+
+            // In a best-effort batch the call succeeds and the event persists
+            let replay = MintParams {
+                proof: zk(&contract, vec![1u8; 64], [2; 32], [1; 32], charlie_hash, 1000, 0),
+                commitment_hash: [2; 32],
+                nullifier_hash: [1; 32],
+                recipient_hash: charlie_hash,
+                amount: 1000,
+                source_chain: 0,
+                pow_nonce: 0,
+            };
+            assert_eq!(
+                contract.verify_and_mint_batch(vec![replay], false),
+                Ok(vec![Err(BridgeError::NullifierUsed)])
+            );
+
+            let attempts = emitted::<DoubleSpendAttempt>();
+            assert_eq!(attempts.len(), 1);
+            assert_eq!(attempts[0].nullifier_hash, [1; 32]);
+            assert_eq!(attempts[0].recipient_hash, charlie_hash);
+            assert_eq!(attempts[0].caller, accounts.django);
+        }
+
+        #[ink::test]
+        fn test_burn_records_commitment() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(42);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.burn_and_bridge(0, [9; 32], [8; 32]),
+                Err(BridgeError::AmountTooLow)
+            );
+            assert_eq!(contract.get_burn_commitment([9; 32]), None);
+            assert_eq!(contract.burn_and_bridge(2000, [9; 32], [8; 32]), Ok(()));
+            assert_eq!(
+                contract.get_burn_commitment([9; 32]),
+                Some(BridgeCommitment {
+                    commitment_hash: [9; 32],
+                    source_chain: POLKADOT_CHAIN_ID,
+                    amount: 2000,
+                    timestamp: 42,
+                    status: CommitmentStatus::Burned,
+                })
+            );
+            // Mint commitments aren't burns
+            assert_eq!(contract.get_burn_commitment([1; 32]), None);
+
+            // Neither a burn's nor a mint's commitment can be reused
+            assert_eq!(
+                contract.burn_and_bridge(1000, [9; 32], [8; 32]),
+                Err(BridgeError::CommitmentAlreadyProcessed)
+            );
+            assert_eq!(
+                contract.burn_and_bridge(1000, [1; 32], [8; 32]),
+                Err(BridgeError::CommitmentAlreadyProcessed)
+            );
+            assert_eq!(contract.balance_of(accounts.bob), 3000);
+            assert_eq!(contract.get_total_burned(), 2000);
+
+            // Nor can a mint overwrite the burn's record
+            set_caller(accounts.alice);
+            let bob_hash = PolkadotBridgeComplete::hash_recipient(&accounts.bob);
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [9; 32], [7; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::CommitmentAlreadyProcessed)
+            );
+            assert_eq!(
+                contract.get_commitment([9; 32]).map(|commitment| commitment.status),
+                Some(CommitmentStatus::Burned)
+            );
+            assert!(!contract.is_nullifier_used([7; 32]));
+        }
+
+        #[ink::test]
+        fn test_list_commitments_pages() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            for seed in 1..=5 {
+                mint_to(&mut contract, accounts.bob, 1000, seed);
+            }
+            set_caller(accounts.bob);
+            contract.burn_and_bridge(500, [9; 32], [8; 32]).unwrap();
+            assert_eq!(contract.commitment_count(), 6);
+            assert_eq!(contract.get_commitment_count(), 6);
+
+            let hashes = |page: Vec<BridgeCommitment>| -> Vec<[u8; 32]> {
+                page.into_iter().map(|commitment| commitment.commitment_hash).collect()
+            };
+            assert_eq!(contract.list_commitments(0, 2).len(), 2);
+            assert_eq!(
+                hashes(contract.list_commitments(2, 3)),
+                vec![[3; 32], [4; 32], [5; 32]]
+            );
+            let last = contract.list_commitments(5, 10);
+            assert_eq!(last.len(), 1);
+            assert_eq!(last[0].status, CommitmentStatus::Burned);
+            assert!(contract.list_commitments(6, 10).is_empty());
+            assert!(contract.list_commitments(u32::MAX, u32::MAX).is_empty());
+            assert_eq!(contract.list_commitments(0, u32::MAX).len(), 6);
+        }
+
+        #[ink::test]
+        fn test_total_supply_tracks_mints_and_burns() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 30);
+            mint_to(&mut contract, accounts.bob, 10_000, 1);
+            mint_to(&mut contract, accounts.charlie, 20_000, 2);
+            // Fees aren't supply until they're paid out
+            assert_eq!(contract.total_supply(), 30_000 - 90);
+
+            set_caller(accounts.bob);
+            contract.burn_and_bridge(4_000, [9; 32], [8; 32]).unwrap();
+            assert_eq!(contract.total_supply(), 26_000 - 90);
+
+            set_caller(accounts.alice);
+            contract.withdraw_fees(accounts.alice, 90).unwrap();
+            assert_eq!(contract.total_supply(), 26_000);
+            assert_eq!(
+                contract.total_supply(),
+                contract.get_total_minted() - contract.get_total_burned()
+            );
+
+            let supplies: Vec<u128> = emitted::<SupplyChanged>()
+                .into_iter()
+                .map(|event| event.supply)
+                .collect();
+            assert_eq!(supplies, vec![9_970, 29_910, 25_910, 26_000]);
+        }
+
+        #[ink::test]
+        fn test_burn_destination_is_indexed() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+
+            let stellar_key = [0xAB; 32];
+            set_caller(accounts.bob);
+            contract.burn_and_bridge(1000, [9; 32], stellar_key).unwrap();
+            assert_eq!(contract.get_burn_destination([9; 32]), Some(stellar_key));
+            assert_eq!(contract.get_burn_destination([1; 32]), None);
+
+            let burned = ink::env::test::recorded_events()
+                .find(|event| {
+                    event.topics.first().cloned()
+                        == <FundsBurned as ink::env::Event>::SIGNATURE_TOPIC
+                            .map(|topic| topic.to_vec())
+                })
+                .expect("FundsBurned emitted");
+            assert_eq!(burned.topics.len(), 3);
+            assert_eq!(burned.topics[2], stellar_key.to_vec());
+        }
+
+        #[ink::test]
+        fn test_mint_and_burn_pause_independently() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            mint_to(&mut contract, accounts.bob, 5000, 1);
+            let bob_hash = contract.recipient_hash_of(accounts.bob);
+
+            contract.set_mint_paused(true).unwrap();
+            assert!(contract.is_mint_paused());
+            assert!(!contract.is_burn_paused());
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::ContractPaused)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(contract.burn_and_bridge(1000, [9; 32], [8; 32]), Ok(()));
+            assert_eq!(contract.set_burn_paused(true), Err(BridgeError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.set_burn_paused(true).unwrap();
+            contract.set_mint_paused(false).unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 5000)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.burn_and_bridge(1000, [10; 32], [8; 32]),
+                Err(BridgeError::ContractPaused)
+            );
+
+            // `set_paused` flips everything; events name only what changed
+            set_caller(accounts.alice);
+            contract.set_paused(false).unwrap();
+            let paused: Vec<u8> = emitted::<Paused>().iter().map(|event| event.flags).collect();
+            let unpaused: Vec<u8> = emitted::<Unpaused>().iter().map(|event| event.flags).collect();
+            assert_eq!(paused, vec![PauseFlags::MINT, PauseFlags::BURN]);
+            assert_eq!(unpaused, vec![PauseFlags::MINT, PauseFlags::BURN]);
+        }
+
+        #[ink::test]
+        fn test_source_chain_registry() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut contract = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract, accounts.bob);
+            assert_eq!(contract.get_chain_config(STELLAR_CHAIN_ID).map(|c| c.enabled), Some(true));
+
+            // Unknown chain
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 1000, 7, 0),
+                Err(BridgeError::UnsupportedSourceChain)
+            );
+            assert_eq!(
+                contract.set_chain_enabled(7, true),
+                Err(BridgeError::UnsupportedSourceChain)
+            );
+
+            contract
+                .register_chain(
+                    7,
+                    ChainConfig {
+                        enabled: true,
+                        min_amount: 5000,
+                        name: b"Testnet".to_vec(),
+                    },
+                )
+                .unwrap();
+            // The chain's own minimum replaces the global one
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 4999, 7, 0),
+                Err(BridgeError::AmountTooLow)
+            );
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [1; 32], [1; 32], bob_hash, 5000, 7, 0),
+                receipt(5000, 0, 5000)
+            );
+
+            // Disabled chain
+            contract.set_chain_enabled(7, false).unwrap();
+            assert_eq!(
+                mint(&mut contract, vec![1u8; 64], [2; 32], [2; 32], bob_hash, 5000, 7, 0),
+                Err(BridgeError::UnsupportedSourceChain)
+            );
+            assert!(!contract.is_nullifier_used([2; 32]));
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_chain_enabled(7, true), Err(BridgeError::Unauthorized));
+        }
+
+        // poseidon(0x0707..07, 1000, 42) as printed by an independent BigInt
+        // implementation (scripts/generate_poseidon_constants.js) and asserted
+        // by the circuit's test_commitment_matches_contracts
+        const POSEIDON_VECTOR: [u8; 32] = [
+            0x13, 0x02, 0x08, 0xf8, 0x51, 0x18, 0x3a, 0x27,
+            0x26, 0x8a, 0x56, 0x47, 0x8b, 0x6e, 0x89, 0x16,
+            0x3e, 0xf6, 0xe4, 0xd0, 0x70, 0xea, 0x04, 0x91,
+            0xa1, 0xd1, 0x8f, 0x6f, 0x86, 0x08, 0x26, 0x0e,
+        ];
+
+        #[ink::test]
+        fn test_poseidon_commitment_cross_chain_vector() {
+            let contract = PolkadotBridgeComplete::new(1000, 0);
+            assert_eq!(
+                contract.compute_poseidon_commitment([7u8; 32], 1_000, 42),
+                POSEIDON_VECTOR
+            );
+            assert_ne!(
+                contract.compute_poseidon_commitment([7u8; 32], 1_000, 43),
+                POSEIDON_VECTOR
+            );
+        }
+
+        #[ink::test]
+        fn test_domain_separator_is_per_instance() {
+            let first = AccountId::from([0xA1; 32]);
+            let second = AccountId::from([0xB2; 32]);
+
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(first);
+            let contract_a = PolkadotBridgeComplete::new(1000, 0);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(second);
+            let contract_b = PolkadotBridgeComplete::new(1000, 0);
+
+            let mut preimage = Vec::from(<AccountId as AsRef<[u8]>>::as_ref(&first));
+            preimage.extend_from_slice(&POLKADOT_CHAIN_ID.to_be_bytes());
+            assert_eq!(
+                contract_a.get_domain_separator(),
+                PolkadotBridgeComplete::hash_proof(&preimage)
+            );
+            assert_ne!(
+                contract_a.get_domain_separator(),
+                contract_b.get_domain_separator()
+            );
+        }
+
+        #[ink::test]
+        fn test_proof_bound_to_domain_without_pairing() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0xA1; 32]));
+            let contract_a = PolkadotBridgeComplete::new(1000, 0);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0xB2; 32]));
+            let mut contract_b = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract_b, accounts.bob);
+
+            // Proven for instance A, replayed on instance B
+            let replayed = zk(&contract_a, vec![1u8; 64], [1; 32], [2; 32], bob_hash, 1000, 0);
+            assert!(!contract_b.check_proof(replayed.clone()));
+            assert_eq!(
+                contract_b.verify_and_mint(replayed, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            assert_eq!(
+                mint(&mut contract_b, vec![1u8; 64], [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
+        }
+
+        #[cfg(feature = "groth16")]
+        #[ink::test]
+        fn test_groth16_proof_bound_to_domain() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0xA1; 32]));
+            let contract_a = PolkadotBridgeComplete::new(1000, 0);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0xB2; 32]));
+            let mut contract_b = PolkadotBridgeComplete::new(1000, 0);
+            let bob_hash = register(&mut contract_b, accounts.bob);
+
+            // Proven for instance A, submitted to instance B
+            let (vk, proof) =
+                groth16_proof([[1; 32], [2; 32], bob_hash, contract_a.get_domain_separator()]);
+            contract_b.set_verification_key(ProofType::Groth16, vk).unwrap();
+            assert_eq!(
+                mint(&mut contract_b, proof, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                Err(BridgeError::InvalidProof)
+            );
+
+            let (vk, proof) =
+                groth16_proof([[1; 32], [2; 32], bob_hash, contract_b.get_domain_separator()]);
+            contract_b.set_verification_key(ProofType::Groth16, vk).unwrap();
+            assert_eq!(
+                mint(&mut contract_b, proof, [1; 32], [2; 32], bob_hash, 1000, 0, 0),
+                receipt(1000, 0, 1000)
+            );
         }
     }
 }
@@ -0,0 +1,315 @@
+//! Poseidon over the BN254 scalar field, shared by the Stellar and Polkadot
+//! bridge contracts
+//!
+//! Matches circomlib's `poseidon` and Noir's `std::hash::poseidon::bn254`
+//! (x^5 S-box, 8 full rounds), so a commitment recomputed on-chain is the one
+//! the bridge circuit constrains. Round constants and MDS matrices are
+//! embedded for the two arities the bridges use; the tests re-derive them
+//! with the Grain LFSR of the Poseidon reference implementation.
+
+#![cfg_attr(not(test), no_std)]
+
+mod constants;
+
+use ark_bn254::Fr;
+use ark_ff::{PrimeField, Zero};
+#[cfg(test)]
+use ark_ff::{BigInteger256, Field};
+
+use constants::{MDS_3, MDS_4, ROUND_CONSTANTS_3, ROUND_CONSTANTS_4};
+
+/// Fewest inputs a single hash accepts
+pub const MIN_INPUTS: usize = 2;
+
+/// Most inputs a single hash accepts
+pub const MAX_INPUTS: usize = 3;
+
+/// Full rounds, half before and half after the partial rounds
+const FULL_ROUNDS: usize = 8;
+
+/// Bit length of the field modulus
+#[cfg(test)]
+const FIELD_BITS: u32 = 254;
+
+/// Partial rounds for `MIN_INPUTS` to `MAX_INPUTS` inputs
+#[cfg(test)]
+const PARTIAL_ROUNDS: [usize; MAX_INPUTS - MIN_INPUTS + 1] = [57, 56];
+
+/// Commitment to a lock: `poseidon(sender, amount, nonce)`
+///
+/// `sender` is the chain-specific 32-byte hash of the sender's address; it is
+/// reduced into the field like any other input.
+pub fn commitment(sender: &[u8; 32], amount: u128, nonce: u64) -> [u8; 32] {
+    let mut amount_bytes = [0u8; 32];
+    amount_bytes[16..].copy_from_slice(&amount.to_be_bytes());
+    let mut nonce_bytes = [0u8; 32];
+    nonce_bytes[24..].copy_from_slice(&nonce.to_be_bytes());
+
+    hash(&[*sender, amount_bytes, nonce_bytes])
+}
+
+/// Poseidon of `MIN_INPUTS` to `MAX_INPUTS` field elements, each given as 32
+/// big-endian bytes and reduced modulo the field order. The digest is the
+/// big-endian encoding of the resulting field element.
+///
+/// # Panics
+///
+/// If `inputs` has an unsupported length.
+pub fn hash(inputs: &[[u8; 32]]) -> [u8; 32] {
+    let digest = match inputs.len() {
+        2 => permute(inputs, &ROUND_CONSTANTS_3, &MDS_3),
+        3 => permute(inputs, &ROUND_CONSTANTS_4, &MDS_4),
+        _ => panic!("Unsupported Poseidon arity"),
+    };
+    limbs_to_be_bytes(digest.into_bigint().0)
+}
+
+/// The Poseidon permutation of `[0, inputs..]` with the given parameters,
+/// returning the first state element
+fn permute<const W: usize>(inputs: &[[u8; 32]], constants: &[Fr], mds: &[[Fr; W]; W]) -> Fr {
+    let partial_rounds = constants.len() / W - FULL_ROUNDS;
+
+    // The capacity element starts at zero
+    let mut state = [Fr::zero(); W];
+    for (element, input) in state[1..].iter_mut().zip(inputs) {
+        *element = Fr::from_be_bytes_mod_order(input);
+    }
+
+    for (round, round_constants) in constants.chunks_exact(W).enumerate() {
+        for (element, constant) in state.iter_mut().zip(round_constants) {
+            *element += constant;
+        }
+
+        let full_round = round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + partial_rounds;
+        let sbox_width = if full_round { W } else { 1 };
+        for element in state.iter_mut().take(sbox_width) {
+            let square = *element * *element;
+            *element *= square * square;
+        }
+
+        let mut mixed = [Fr::zero(); W];
+        for (out, row) in mixed.iter_mut().zip(mds) {
+            for (entry, element) in row.iter().zip(&state) {
+                *out += *entry * element;
+            }
+        }
+        state = mixed;
+    }
+
+    state[0]
+}
+
+/// Big-endian bytes of a little-endian limb array
+fn limbs_to_be_bytes(limbs: [u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+/// The self-shrinking Grain LFSR the Poseidon reference implementation
+/// generates its parameters with
+struct Grain {
+    /// 80-bit register; bit 0 is the oldest
+    register: u128,
+}
+
+#[cfg(test)]
+impl Grain {
+    /// Seed with the parameter description: prime field, x^alpha S-box,
+    /// field size, width and round counts, then 30 set bits; the first 160
+    /// clocks are discarded
+    fn new(width: usize, partial_rounds: usize) -> Self {
+        let parameters: [(u128, u32); 7] = [
+            (1, 2),
+            (0, 4),
+            (FIELD_BITS as u128, 12),
+            (width as u128, 12),
+            (FULL_ROUNDS as u128, 10),
+            (partial_rounds as u128, 10),
+            ((1 << 30) - 1, 30),
+        ];
+
+        let mut register = 0u128;
+        let mut position = 0;
+        for (value, bits) in parameters {
+            for bit in (0..bits).rev() {
+                register |= ((value >> bit) & 1) << position;
+                position += 1;
+            }
+        }
+
+        let mut grain = Grain { register };
+        for _ in 0..160 {
+            grain.clock();
+        }
+        grain
+    }
+
+    fn clock(&mut self) -> u64 {
+        let register = self.register;
+        let bit = ((register >> 62)
+            ^ (register >> 51)
+            ^ (register >> 38)
+            ^ (register >> 23)
+            ^ (register >> 13)
+            ^ register)
+            & 1;
+        self.register = (register >> 1) | (bit << 79);
+        bit as u64
+    }
+
+    /// Next output bit: of each pair of clocked bits, the second is kept
+    /// when the first is set
+    fn bit(&mut self) -> u64 {
+        loop {
+            let keep = self.clock();
+            let bit = self.clock();
+            if keep == 1 {
+                return bit;
+            }
+        }
+    }
+
+    /// Next `FIELD_BITS` output bits, most significant first, as
+    /// little-endian limbs
+    fn limbs(&mut self) -> [u64; 4] {
+        let mut limbs = [0u64; 4];
+        for _ in 0..FIELD_BITS {
+            let bit = self.bit();
+            limbs = [
+                (limbs[0] << 1) | bit,
+                (limbs[1] << 1) | (limbs[0] >> 63),
+                (limbs[2] << 1) | (limbs[1] >> 63),
+                (limbs[3] << 1) | (limbs[2] >> 63),
+            ];
+        }
+        limbs
+    }
+
+    /// Round constant: sampled until below the modulus
+    fn field_element(&mut self) -> Fr {
+        loop {
+            if let Some(element) = Fr::from_bigint(BigInteger256::new(self.limbs())) {
+                return element;
+            }
+        }
+    }
+
+    /// Cauchy MDS matrix `1 / (x_i + y_j)` from `2 * width` distinct samples,
+    /// each reduced modulo the field order
+    fn mds(&mut self, width: usize) -> [[Fr; MAX_INPUTS + 1]; MAX_INPUTS + 1] {
+        loop {
+            let mut samples = [Fr::zero(); 2 * (MAX_INPUTS + 1)];
+            let samples = &mut samples[..2 * width];
+            loop {
+                for sample in samples.iter_mut() {
+                    *sample = Fr::from_be_bytes_mod_order(&limbs_to_be_bytes(self.limbs()));
+                }
+                if samples
+                    .iter()
+                    .enumerate()
+                    .all(|(i, sample)| !samples[..i].contains(sample))
+                {
+                    break;
+                }
+            }
+
+            let (xs, ys) = samples.split_at(width);
+            let mut matrix = [[Fr::zero(); MAX_INPUTS + 1]; MAX_INPUTS + 1];
+            let mut invertible = true;
+            for (row, x) in matrix.iter_mut().zip(xs) {
+                for (entry, y) in row.iter_mut().zip(ys) {
+                    match (*x + y).inverse() {
+                        Some(inverse) => *entry = inverse,
+                        None => invertible = false,
+                    }
+                }
+            }
+            if invertible {
+                return matrix;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(value: u64) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&value.to_be_bytes());
+        bytes
+    }
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        let digit = |c: u8| match c {
+            b'0'..=b'9' => c - b'0',
+            _ => c - b'a' + 10,
+        };
+        let mut bytes = [0u8; 32];
+        for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+            *byte = (digit(pair[0]) << 4) | digit(pair[1]);
+        }
+        bytes
+    }
+
+    // Reference values from circomlib's poseidon tests
+    #[test]
+    fn test_matches_circomlib() {
+        assert_eq!(
+            hash(&[field(1), field(2)]),
+            from_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+        assert_eq!(
+            hash(&[field(1), field(2), field(3)]),
+            from_hex("0e7732d89e6939c0ff03d5e58dab6302f3230e269dc5b968f725df34ab36d732")
+        );
+    }
+
+    /// The embedded constants are the ones the Grain LFSR derives
+    fn assert_constants_match<const W: usize>(constants: &[Fr], mds: &[[Fr; W]; W]) {
+        let partial_rounds = PARTIAL_ROUNDS[W - 1 - MIN_INPUTS];
+        assert_eq!(constants.len(), (FULL_ROUNDS + partial_rounds) * W);
+
+        let mut grain = Grain::new(W, partial_rounds);
+        for constant in constants {
+            assert_eq!(*constant, grain.field_element());
+        }
+        let derived = grain.mds(W);
+        for (row, derived_row) in mds.iter().zip(&derived) {
+            assert_eq!(row[..], derived_row[..W]);
+        }
+    }
+
+    #[test]
+    fn test_embedded_constants_match_grain() {
+        assert_constants_match(&ROUND_CONSTANTS_3, &MDS_3);
+        assert_constants_match(&ROUND_CONSTANTS_4, &MDS_4);
+    }
+
+    #[test]
+    fn test_commitment_encodes_amount_and_nonce_as_fields() {
+        let sender = [7u8; 32];
+        assert_eq!(
+            commitment(&sender, 1_000, 42),
+            hash(&[sender, field(1_000), field(42)])
+        );
+        assert_ne!(commitment(&sender, 1_000, 42), commitment(&sender, 1_000, 43));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported Poseidon arity")]
+    fn test_rejects_too_many_inputs() {
+        hash(&[field(1); MAX_INPUTS + 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported Poseidon arity")]
+    fn test_rejects_too_few_inputs() {
+        hash(&[field(1); MIN_INPUTS - 1]);
+    }
+}
@@ -2,18 +2,62 @@
 // Locks funds on Stellar and verifies ZK proofs for cross-chain bridging
 
 #![no_std]
+#[cfg(any(test, feature = "groth16"))]
+extern crate alloc;
+
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Bytes, BytesN, Env, Map, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, panic_with_error, token,
+    xdr::ToXdr, Address, Bytes, BytesN, Env, Map, Symbol, Vec,
 };
 
+// Typed contract errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum BridgeError {
+    NotInitialized = 1,
+    Unauthorized = 2,
+    CommitmentCollision = 3,
+    InvalidDestinationAddress = 4,
+    ContractPaused = 5,
+    InvalidVerificationKey = 6,
+    DuplicateNonce = 7,
+    RentBudgetExhausted = 8,
+    LedgerLockLimitReached = 9,
+    ZeroAmount = 10,
+    WouldUndercollateralize = 11,
+    UnsupportedToken = 12,
+    AmountBelowMinimum = 13,
+    AmountAboveMaximum = 14,
+    ChainNotAllowed = 15,
+    AmountBelowRelayerFee = 16,
+    InvalidOraclePrice = 17,
+    StaleOraclePrice = 18,
+    CommitmentNotFound = 19,
+    CommitmentAlreadyProcessed = 20,
+    UnlockPending = 21,
+    TimeoutNotReached = 22,
+    ArithmeticOverflow = 23,
+}
+
+// Price feed consulted for USD-denominated lock limits
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// USD cents per whole token, and the ledger timestamp it was published at
+    fn price(env: Env) -> (i128, u64);
+}
+
 // Bridge commitment structure
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BridgeCommitment {
     pub commitment_hash: BytesN<32>,  // Poseidon hash commitment
     pub sender: Address,               // Stellar sender address
+    pub token: Address,                // Token contract the amount is denominated in
     pub amount: i128,                  // Amount locked in smallest units
     pub timestamp: u64,                // Lock timestamp
+    pub ledger_sequence: u32,          // Lock ledger sequence
+    pub timeout_period: u64,           // Refund timeout in effect at lock time
     pub destination_chain: u32,        // 1 = Polkadot
     pub status: CommitmentStatus,      // Current status
 }
@@ -35,6 +79,68 @@ pub struct ZKProof {
     pub public_inputs: Vec<BytesN<32>>, // Public inputs: [commitment, nullifier, recipient_hash]
 }
 
+// Lock limits expressed in USD cents, converted at the oracle price
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsdLimits {
+    pub oracle: Address,
+    pub max_price_age: u64,    // Seconds before an oracle price is stale
+    pub min_cents: i128,
+    pub max_cents: i128,       // 0 = no maximum
+}
+
+// Atomic swap offer: `give_amount` of `give_token` escrowed in exchange for
+// `want_amount` of `want_token` from the counterparty bound by `counterparty_hash`.
+// Both legs are held by the contract from `match_swap` until completion or refund.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapOffer {
+    pub commitment_hash: BytesN<32>,
+    pub sender: Address,
+    pub give_token: Address,
+    pub give_amount: i128,
+    pub want_token: Address,
+    pub want_amount: i128,
+    pub counterparty_hash: BytesN<32>, // sha256 of the counterparty's address XDR
+    pub counterparty: Option<Address>, // Set once the counterparty escrows its leg
+    pub timestamp: u64,
+    pub status: SwapStatus,
+}
+
+/// One entry of a `lock_funds_batch` call; the commitment is derived from
+/// these exactly as in `lock_funds`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockRequest {
+    pub amount: i128,
+    pub nonce: u64,
+    pub destination_chain: u32,
+}
+
+/// An unlock proposed without a proof, paid out once its challenge window
+/// passes unchallenged or the admin rejects the challenge raised against it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingUnlock {
+    pub relayer: Address,
+    pub nullifier_hash: BytesN<32>,
+    pub recipient_hash: BytesN<32>,
+    pub relayer_bond: i128,
+    pub proposed_at: u64,
+    pub challenger: Option<Address>,
+    pub challenger_bond: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum SwapStatus {
+    Open = 0,
+    Completed = 1,
+    Refunded = 2,
+    Matched = 3,
+}
+
 // Storage keys
 #[contracttype]
 pub enum DataKey {
@@ -44,8 +150,523 @@ pub enum DataKey {
     Nullifier(BytesN<32>),         // Map: nullifier_hash -> bool (prevent double-spend)
     TotalLocked,                   // Total amount locked
     MinLockAmount,                 // Minimum lockable amount
-    RelayerFee,                    // Fee for relayers
+    RelayerFee,                    // Relayer fee taken from each claimed commitment
     VerificationKey,               // ZK verifier public key
+    SenderNonce(Address, u64),     // Map: (sender, nonce) -> commitment_hash (retry de-dup)
+    FeeVault,                      // Accrued relayer fees held by the contract
+    Relayer(Address),              // Map: relayer -> bool (may claim from the fee vault)
+    RentBudget,                    // Prepaid rent budget for new commitment entries
+    StatusCount(CommitmentStatus), // Map: status -> number of commitments currently in that status
+    StatusEntry(CommitmentStatus, u32), // Map: (status, position) -> commitment_hash
+    StatusPosition(BytesN<32>),    // Map: commitment_hash -> position in its status's entries
+    MaxLocksPerLedger,             // Cap on new commitments per ledger (0 = unlimited)
+    LedgerLockCount,               // New commitments created in LastLockLedger
+    LastLockLedger,                // Ledger sequence of the most recent lock
+    Swap(BytesN<32>),              // Map: swap_id -> SwapOffer (see swap_id)
+    CommitmentReuseCount(BytesN<32>), // Map: commitment_hash -> times relocked after refund (claim nonce)
+    UsdLimits,                     // Optional USD-denominated lock limits
+    Shutdown,                      // Permanent kill switch: no new locks or claims
+    MinLedgersElapsed,             // Ledgers that must pass, besides the timeout, before a refund
+    ImportOpen,                    // Commitment import from a prior bridge version still allowed
+    TimeoutPeriod,                 // Refund timeout applied to new commitments
+    ChainAddressType(u32),         // Map: destination chain -> expected ADDRESS_TYPE_*
+    Destination(BytesN<32>),       // Map: commitment_hash -> (address type, destination address)
+    CommitmentStatus(BytesN<32>),  // Map: commitment_hash -> status, readable without loading the commitment
+    Paused,                        // Emergency pause of locks and claims, lifted by the admin
+    UserLocked(Address),           // Map: sender -> amount in their Locked commitments (absent when zero)
+    PendingAdmin,                  // Admin nominated by propose_admin, until they accept
+    TotalLockedByToken(Address),    // Map: token -> amount locked in that token
+    SupportedToken(Address),        // Map: token -> bool (lockable besides the primary token)
+    AllowedChain(u32),              // Map: destination chain -> bool (serviced by relayers)
+    MaxLockAmount,                 // Maximum lockable amount (0 = no maximum)
+    DomainSeparator,               // Hash of (contract address, network id) bound into every proof
+    MerkleRoot,                    // Root of the commitment Merkle tree
+    MerkleNextIndex,               // Number of leaves inserted into the commitment Merkle tree
+    MerkleFilledSubtrees,          // Per level, the left node awaiting its right sibling
+    LeafIndex(BytesN<32>),         // Map: commitment_hash -> leaf index in the Merkle tree
+    SwapEscrow(Address),           // Map: token -> amount escrowed by open swaps
+    ChallengeWindow,               // Seconds an optimistic unlock stays open to challenge (0 = off)
+    ChallengeBond,                 // Bond posted by both the proposing relayer and a challenger
+    PendingUnlock(BytesN<32>),     // Map: commitment_hash -> PendingUnlock
+    BondEscrow,                    // Bonds held for pending unlocks and their challenges
+    SwapNullifier(BytesN<32>),     // Map: nullifier_hash -> bool, spent by complete_swap
+    ScopedNullifier(BytesN<32>),   // Map: scoped_nullifier -> bool, spent by a relocked commitment
+}
+
+// TTL (in ledgers) given to each new commitment entry: ~8 days at 5s ledgers,
+// enough to outlive the refund timeout
+const COMMITMENT_TTL_LEDGERS: u32 = 138_240;
+
+// Rent is estimated as entry_bytes * ttl_ledgers / RENT_BYTE_LEDGERS_PER_UNIT
+const RENT_BYTE_LEDGERS_PER_UNIT: i128 = 1_000_000;
+
+// Maximum page size for commitment listings
+const MAX_PAGE_LIMIT: u32 = 50;
+
+// Maximum number of recipients in a split unlock
+const MAX_SPLIT_RECIPIENTS: u32 = 8;
+
+// Maximum number of locks in one `lock_funds_batch` call
+const MAX_LOCK_BATCH: u32 = 16;
+
+// Maximum number of commitments in one `refund_batch` call
+const MAX_REFUND_BATCH: u32 = 16;
+
+// Depth of the commitment Merkle tree (2^20 leaves)
+const MERKLE_TREE_DEPTH: u32 = 20;
+
+// Roots of empty subtrees of height 0..=MERKLE_TREE_DEPTH (all-zero leaves),
+// as printed by scripts/generate_poseidon_constants.js
+const MERKLE_ZEROS: [[u8; 32]; MERKLE_TREE_DEPTH as usize + 1] = [
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ],
+    [
+        0x20, 0x98, 0xf5, 0xfb, 0x9e, 0x23, 0x9e, 0xab,
+        0x3c, 0xea, 0xc3, 0xf2, 0x7b, 0x81, 0xe4, 0x81,
+        0xdc, 0x31, 0x24, 0xd5, 0x5f, 0xfe, 0xd5, 0x23,
+        0xa8, 0x39, 0xee, 0x84, 0x46, 0xb6, 0x48, 0x64,
+    ],
+    [
+        0x10, 0x69, 0x67, 0x3d, 0xcd, 0xb1, 0x22, 0x63,
+        0xdf, 0x30, 0x1a, 0x6f, 0xf5, 0x84, 0xa7, 0xec,
+        0x26, 0x1a, 0x44, 0xcb, 0x9d, 0xc6, 0x8d, 0xf0,
+        0x67, 0xa4, 0x77, 0x44, 0x60, 0xb1, 0xf1, 0xe1,
+    ],
+    [
+        0x18, 0xf4, 0x33, 0x31, 0x53, 0x7e, 0xe2, 0xaf,
+        0x2e, 0x3d, 0x75, 0x8d, 0x50, 0xf7, 0x21, 0x06,
+        0x46, 0x7c, 0x6e, 0xea, 0x50, 0x37, 0x1d, 0xd5,
+        0x28, 0xd5, 0x7e, 0xb2, 0xb8, 0x56, 0xd2, 0x38,
+    ],
+    [
+        0x07, 0xf9, 0xd8, 0x37, 0xcb, 0x17, 0xb0, 0xd3,
+        0x63, 0x20, 0xff, 0xe9, 0x3b, 0xa5, 0x23, 0x45,
+        0xf1, 0xb7, 0x28, 0x57, 0x1a, 0x56, 0x82, 0x65,
+        0xca, 0xac, 0x97, 0x55, 0x9d, 0xbc, 0x95, 0x2a,
+    ],
+    [
+        0x2b, 0x94, 0xcf, 0x5e, 0x87, 0x46, 0xb3, 0xf5,
+        0xc9, 0x63, 0x1f, 0x4c, 0x5d, 0xf3, 0x29, 0x07,
+        0xa6, 0x99, 0xc5, 0x8c, 0x94, 0xb2, 0xad, 0x4d,
+        0x7b, 0x5c, 0xec, 0x16, 0x39, 0x18, 0x3f, 0x55,
+    ],
+    [
+        0x2d, 0xee, 0x93, 0xc5, 0xa6, 0x66, 0x45, 0x96,
+        0x46, 0xea, 0x7d, 0x22, 0xcc, 0xa9, 0xe1, 0xbc,
+        0xfe, 0xd7, 0x1e, 0x69, 0x51, 0xb9, 0x53, 0x61,
+        0x1d, 0x11, 0xdd, 0xa3, 0x2e, 0xa0, 0x9d, 0x78,
+    ],
+    [
+        0x07, 0x82, 0x95, 0xe5, 0xa2, 0x2b, 0x84, 0xe9,
+        0x82, 0xcf, 0x60, 0x1e, 0xb6, 0x39, 0x59, 0x7b,
+        0x8b, 0x05, 0x15, 0xa8, 0x8c, 0xb5, 0xac, 0x7f,
+        0xa8, 0xa4, 0xaa, 0xbe, 0x3c, 0x87, 0x34, 0x9d,
+    ],
+    [
+        0x2f, 0xa5, 0xe5, 0xf1, 0x8f, 0x60, 0x27, 0xa6,
+        0x50, 0x1b, 0xec, 0x86, 0x45, 0x64, 0x47, 0x2a,
+        0x61, 0x6b, 0x2e, 0x27, 0x4a, 0x41, 0x21, 0x1a,
+        0x44, 0x4c, 0xbe, 0x3a, 0x99, 0xf3, 0xcc, 0x61,
+    ],
+    [
+        0x0e, 0x88, 0x43, 0x76, 0xd0, 0xd8, 0xfd, 0x21,
+        0xec, 0xb7, 0x80, 0x38, 0x9e, 0x94, 0x1f, 0x66,
+        0xe4, 0x5e, 0x7a, 0xcc, 0xe3, 0xe2, 0x28, 0xab,
+        0x3e, 0x21, 0x56, 0xa6, 0x14, 0xfc, 0xd7, 0x47,
+    ],
+    [
+        0x1b, 0x72, 0x01, 0xda, 0x72, 0x49, 0x4f, 0x1e,
+        0x28, 0x71, 0x7a, 0xd1, 0xa5, 0x2e, 0xb4, 0x69,
+        0xf9, 0x58, 0x92, 0xf9, 0x57, 0x71, 0x35, 0x33,
+        0xde, 0x61, 0x75, 0xe5, 0xda, 0x19, 0x0a, 0xf2,
+    ],
+    [
+        0x1f, 0x8d, 0x88, 0x22, 0x72, 0x5e, 0x36, 0x38,
+        0x52, 0x00, 0xc0, 0xb2, 0x01, 0x24, 0x98, 0x19,
+        0xa6, 0xe6, 0xe1, 0xe4, 0x65, 0x08, 0x08, 0xb5,
+        0xbe, 0xbc, 0x6b, 0xfa, 0xce, 0x7d, 0x76, 0x36,
+    ],
+    [
+        0x2c, 0x5d, 0x82, 0xf6, 0x6c, 0x91, 0x4b, 0xaf,
+        0xb9, 0x70, 0x15, 0x89, 0xba, 0x8c, 0xfc, 0xfb,
+        0x61, 0x62, 0xb0, 0xa1, 0x2a, 0xcf, 0x88, 0xa8,
+        0xd0, 0x87, 0x9a, 0x04, 0x71, 0xb5, 0xf8, 0x5a,
+    ],
+    [
+        0x14, 0xc5, 0x41, 0x48, 0xa0, 0x94, 0x0b, 0xb8,
+        0x20, 0x95, 0x7f, 0x5a, 0xdf, 0x3f, 0xa1, 0x13,
+        0x4e, 0xf5, 0xc4, 0xaa, 0xa1, 0x13, 0xf4, 0x64,
+        0x64, 0x58, 0xf2, 0x70, 0xe0, 0xbf, 0xbf, 0xd0,
+    ],
+    [
+        0x19, 0x0d, 0x33, 0xb1, 0x2f, 0x98, 0x6f, 0x96,
+        0x1e, 0x10, 0xc0, 0xee, 0x44, 0xd8, 0xb9, 0xaf,
+        0x11, 0xbe, 0x25, 0x58, 0x8c, 0xad, 0x89, 0xd4,
+        0x16, 0x11, 0x8e, 0x4b, 0xf4, 0xeb, 0xe8, 0x0c,
+    ],
+    [
+        0x22, 0xf9, 0x8a, 0xa9, 0xce, 0x70, 0x41, 0x52,
+        0xac, 0x17, 0x35, 0x49, 0x14, 0xad, 0x73, 0xed,
+        0x11, 0x67, 0xae, 0x65, 0x96, 0xaf, 0x51, 0x0a,
+        0xa5, 0xb3, 0x64, 0x93, 0x25, 0xe0, 0x6c, 0x92,
+    ],
+    [
+        0x2a, 0x7c, 0x7c, 0x9b, 0x6c, 0xe5, 0x88, 0x0b,
+        0x9f, 0x6f, 0x22, 0x8d, 0x72, 0xbf, 0x6a, 0x57,
+        0x5a, 0x52, 0x6f, 0x29, 0xc6, 0x6e, 0xcc, 0xee,
+        0xf8, 0xb7, 0x53, 0xd3, 0x8b, 0xba, 0x73, 0x23,
+    ],
+    [
+        0x2e, 0x81, 0x86, 0xe5, 0x58, 0x69, 0x8e, 0xc1,
+        0xc6, 0x7a, 0xf9, 0xc1, 0x4d, 0x46, 0x3f, 0xfc,
+        0x47, 0x00, 0x43, 0xc9, 0xc2, 0x98, 0x8b, 0x95,
+        0x4d, 0x75, 0xdd, 0x64, 0x3f, 0x36, 0xb9, 0x92,
+    ],
+    [
+        0x0f, 0x57, 0xc5, 0x57, 0x1e, 0x9a, 0x4e, 0xab,
+        0x49, 0xe2, 0xc8, 0xcf, 0x05, 0x0d, 0xae, 0x94,
+        0x8a, 0xef, 0x6e, 0xad, 0x64, 0x73, 0x92, 0x27,
+        0x35, 0x46, 0x24, 0x9d, 0x1c, 0x1f, 0xf1, 0x0f,
+    ],
+    [
+        0x18, 0x30, 0xee, 0x67, 0xb5, 0xfb, 0x55, 0x4a,
+        0xd5, 0xf6, 0x3d, 0x43, 0x88, 0x80, 0x0e, 0x1c,
+        0xfe, 0x78, 0xe3, 0x10, 0x69, 0x7d, 0x46, 0xe4,
+        0x3c, 0x9c, 0xe3, 0x61, 0x34, 0xf7, 0x2c, 0xca,
+    ],
+    [
+        0x21, 0x34, 0xe7, 0x6a, 0xc5, 0xd2, 0x1a, 0xab,
+        0x18, 0x6c, 0x2b, 0xe1, 0xdd, 0x8f, 0x84, 0xee,
+        0x88, 0x0a, 0x1e, 0x46, 0xea, 0xf7, 0x12, 0xf9,
+        0xd3, 0x71, 0xb6, 0xdf, 0x22, 0x19, 0x1f, 0x3e,
+    ],
+];
+
+// Destination chain allowed from initialization
+pub const POLKADOT_CHAIN_ID: u32 = 1;
+
+// Refund timeout until configured (7 days)
+const DEFAULT_TIMEOUT_PERIOD: u64 = 604_800;
+
+// Destination address formats accepted by `lock_funds_to`
+pub const ADDRESS_TYPE_RAW32: u32 = 0; // 32-byte public key / account id
+pub const ADDRESS_TYPE_SS58: u32 = 1; // Decoded SS58: prefix byte, 32-byte key, 2-byte checksum
+pub const ADDRESS_TYPE_EVM: u32 = 2; // 20-byte account
+
+// Time after which an unmatched swap can be refunded (7 days)
+const SWAP_TIMEOUT: u64 = 604_800;
+
+// Event definitions. Every event is published through this module; bump
+// SCHEMA_VERSION whenever an event's topics or data change shape.
+pub mod events {
+    use soroban_sdk::{contracttype, Address, BytesN, Env, Symbol};
+
+    pub const SCHEMA_VERSION: u32 = 4;
+
+    /// Data of the `funds_locked` event
+    #[contracttype]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct FundsLockedEvent {
+        pub commitment_hash: BytesN<32>,
+        pub sender: Address,
+        pub token: Address,
+        pub amount: i128,
+        pub destination_chain: u32,
+    }
+
+    /// Data of the `unlock_approved` event
+    #[contracttype]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct UnlockApprovedEvent {
+        pub commitment_hash: BytesN<32>,
+        pub nullifier_hash: BytesN<32>,
+        pub recipient_hash: BytesN<32>,
+        pub amount: i128,
+        pub destination_chain: u32,
+    }
+
+    /// Data of the `refunded` event
+    #[contracttype]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct RefundedEvent {
+        pub commitment_hash: BytesN<32>,
+        pub sender: Address,
+        pub amount: i128,
+    }
+
+    /// Data of the `config_updated` event: the configuration after the update
+    #[contracttype]
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct ConfigUpdatedEvent {
+        pub admin: Address,
+        pub min_lock_amount: i128,
+        pub max_lock_amount: i128,
+        pub relayer_fee: i128,
+        pub timeout_period: u64,
+    }
+
+    pub fn initialized(env: &Env, admin: Address, token_contract: Address) {
+        env.events().publish(
+            (Symbol::new(env, "initialized"),),
+            (admin, token_contract, SCHEMA_VERSION),
+        );
+    }
+
+    pub fn funds_locked(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        sender: Address,
+        token: Address,
+        amount: i128,
+        destination_chain: u32,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "funds_locked"),),
+            FundsLockedEvent {
+                commitment_hash,
+                sender,
+                token,
+                amount,
+                destination_chain,
+            },
+        );
+    }
+
+    pub fn unlock_approved(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipient_hash: BytesN<32>,
+        amount: i128,
+        destination_chain: u32,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "unlock_approved"),),
+            UnlockApprovedEvent {
+                commitment_hash,
+                nullifier_hash,
+                recipient_hash,
+                amount,
+                destination_chain,
+            },
+        );
+    }
+
+    pub fn refunded(env: &Env, commitment_hash: BytesN<32>, sender: Address, amount: i128) {
+        env.events().publish(
+            (Symbol::new(env, "refunded"),),
+            RefundedEvent {
+                commitment_hash,
+                sender,
+                amount,
+            },
+        );
+    }
+
+    pub fn claimed(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipient: Address,
+        amount: i128,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "claimed"), recipient),
+            (commitment_hash, nullifier_hash, amount),
+        );
+    }
+
+    pub fn funds_relocked(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        sender: Address,
+        amount: i128,
+        claim_nonce: u32,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "funds_relocked"),),
+            (commitment_hash, sender, amount, claim_nonce),
+        );
+    }
+
+    pub fn swap_locked(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        sender: Address,
+        give_amount: i128,
+        want_amount: i128,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "swap_locked"),),
+            (commitment_hash, sender, give_amount, want_amount),
+        );
+    }
+
+    pub fn swap_matched(env: &Env, commitment_hash: BytesN<32>, counterparty: Address) {
+        env.events().publish(
+            (Symbol::new(env, "swap_matched"),),
+            (commitment_hash, counterparty),
+        );
+    }
+
+    pub fn swap_completed(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        sender: Address,
+        counterparty: Address,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "swap_completed"),),
+            (commitment_hash, sender, counterparty),
+        );
+    }
+
+    pub fn swap_refunded(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        sender: Address,
+        give_amount: i128,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "swap_refunded"),),
+            (commitment_hash, sender, give_amount),
+        );
+    }
+
+    pub fn force_refunded(
+        env: &Env,
+        admin: Address,
+        commitment_hash: BytesN<32>,
+        amount: i128,
+        reason: Symbol,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "force_refunded"), admin),
+            (commitment_hash, amount, reason),
+        );
+    }
+
+    pub fn config_updated(
+        env: &Env,
+        admin: Address,
+        min_lock_amount: i128,
+        max_lock_amount: i128,
+        relayer_fee: i128,
+        timeout_period: u64,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "config_updated"),),
+            ConfigUpdatedEvent {
+                admin,
+                min_lock_amount,
+                max_lock_amount,
+                relayer_fee,
+                timeout_period,
+            },
+        );
+    }
+
+    pub fn refund_timeout_changed(env: &Env, old: u64, new: u64, active_locked_count: u32) {
+        env.events().publish(
+            (Symbol::new(env, "refund_timeout_changed"),),
+            (old, new, active_locked_count),
+        );
+    }
+
+    pub fn allowed_chain_updated(env: &Env, chain: u32, allowed: bool) {
+        env.events()
+            .publish((Symbol::new(env, "allowed_chain_updated"), chain), allowed);
+    }
+
+    pub fn relayer_updated(env: &Env, relayer: Address, enabled: bool) {
+        env.events().publish(
+            (Symbol::new(env, "relayer_updated"),),
+            (relayer, enabled),
+        );
+    }
+
+    pub fn rent_topped_up(env: &Env, admin: Address, budget: i128) {
+        env.events().publish(
+            (Symbol::new(env, "rent_topped_up"),),
+            (admin, budget),
+        );
+    }
+
+    pub fn fee_vault_claimed(env: &Env, relayer: Address, amount: i128) {
+        env.events().publish(
+            (Symbol::new(env, "fee_vault_claimed"),),
+            (relayer, amount),
+        );
+    }
+
+    pub fn shutdown(env: &Env, admin: Address) {
+        env.events().publish((Symbol::new(env, "shutdown"),), admin);
+    }
+
+    pub fn verification_key_set(env: &Env, admin: Address, key_hash: BytesN<32>) {
+        env.events()
+            .publish((Symbol::new(env, "verification_key_set"), admin), key_hash);
+    }
+
+    pub fn admin_transfer_started(env: &Env, current_admin: Address, new_admin: Address) {
+        env.events().publish(
+            (Symbol::new(env, "admin_transfer_started"), current_admin),
+            new_admin,
+        );
+    }
+
+    pub fn admin_transferred(env: &Env, previous_admin: Address, new_admin: Address) {
+        env.events().publish(
+            (Symbol::new(env, "admin_transferred"), previous_admin),
+            new_admin,
+        );
+    }
+
+    pub fn paused_changed(env: &Env, admin: Address, paused: bool) {
+        env.events()
+            .publish((Symbol::new(env, "paused_changed"), admin), paused);
+    }
+
+    pub fn unlock_proposed(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        relayer: Address,
+        challenge_deadline: u64,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "unlock_proposed"), relayer),
+            (commitment_hash, challenge_deadline),
+        );
+    }
+
+    pub fn unlock_challenged(env: &Env, commitment_hash: BytesN<32>, challenger: Address) {
+        env.events().publish(
+            (Symbol::new(env, "unlock_challenged"), challenger),
+            commitment_hash,
+        );
+    }
+
+    pub fn challenge_resolved(
+        env: &Env,
+        commitment_hash: BytesN<32>,
+        upheld: bool,
+        winner: Address,
+        payout: i128,
+    ) {
+        env.events().publish(
+            (Symbol::new(env, "challenge_resolved"), winner),
+            (commitment_hash, upheld, payout),
+        );
+    }
+
+    pub fn swept(env: &Env, to: Address, amount: i128) {
+        env.events()
+            .publish((Symbol::new(env, "swept"),), (to, amount));
+    }
 }
 
 #[contract]
@@ -54,12 +675,17 @@ pub struct StellarBridgeComplete;
 #[contractimpl]
 impl StellarBridgeComplete {
     /// Initialize the bridge contract
+    ///
+    /// `timeout_period` is the refund timeout in seconds; `None` uses the
+    /// 7 day default. A `max_lock_amount` of 0 leaves locks uncapped.
     pub fn initialize(
         env: Env,
         admin: Address,
         token_contract: Address,
         min_lock_amount: i128,
+        max_lock_amount: i128,
         relayer_fee: i128,
+        timeout_period: Option<u64>,
     ) {
         // Ensure not already initialized
         if env.storage().instance().has(&DataKey::Admin) {
@@ -67,310 +693,4471 @@ impl StellarBridgeComplete {
         }
 
         admin.require_auth();
+        Self::check_lock_bounds(min_lock_amount, max_lock_amount);
 
         // Store configuration
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::TokenContract, &token_contract);
         env.storage().instance().set(&DataKey::MinLockAmount, &min_lock_amount);
+        env.storage().instance().set(&DataKey::MaxLockAmount, &max_lock_amount);
         env.storage().instance().set(&DataKey::RelayerFee, &relayer_fee);
+        env.storage().instance().set(
+            &DataKey::TimeoutPeriod,
+            &timeout_period.unwrap_or(DEFAULT_TIMEOUT_PERIOD),
+        );
         env.storage().instance().set(&DataKey::TotalLocked, &0i128);
+        env.storage().instance().set(&DataKey::ImportOpen, &true);
+        env.storage()
+            .instance()
+            .set(&DataKey::DomainSeparator, &Self::compute_domain_separator(&env));
+        env.storage()
+            .persistent()
+            .set(&DataKey::AllowedChain(POLKADOT_CHAIN_ID), &true);
 
         // Emit initialization event
-        env.events().publish(
-            (Symbol::new(&env, "initialized"),),
-            (admin.clone(), token_contract),
-        );
+        events::initialized(&env, admin.clone(), token_contract);
     }
 
     /// Lock funds with commitment for cross-chain transfer
+    ///
+    /// The commitment records the full `amount`; the relayer fee is only taken
+    /// when it is claimed, so a refund returns everything that was locked.
+    ///
+    /// The commitment is `compute_poseidon_commitment(sender, amount, nonce)`,
+    /// the value the bridge circuit proves knowledge of, so a client retrying
+    /// the same transaction gets the same commitment back instead of a second
+    /// lock. Each `(sender, nonce)` pair can only be used once.
+    ///
+    /// `token` selects the asset to lock: the primary token when `None`,
+    /// otherwise one the admin enabled with `set_supported_token`. It is
+    /// recorded in the commitment and announced in `funds_locked`, so refunds
+    /// pay out in the locked asset and relayers mint its wrapped counterpart.
+    /// The amount limits and relayer fee are denominated in the primary
+    /// token, so they only apply to primary-token locks.
     pub fn lock_funds(
         env: Env,
         sender: Address,
         amount: i128,
-        commitment_hash: BytesN<32>,
+        nonce: u64,
         destination_chain: u32,
+        token: Option<Address>,
+    ) -> BytesN<32> {
+        let token_contract = token.unwrap_or_else(|| Self::primary_token(&env));
+        Self::lock(&env, &sender, &token_contract, amount, nonce, destination_chain, None)
+    }
+
+    /// Shared body of `lock_funds` and `lock_funds_to`; `destination` is the
+    /// destination hash a `lock_funds_to` commitment is bound to
+    fn lock(
+        env: &Env,
+        sender: &Address,
+        token: &Address,
+        amount: i128,
+        nonce: u64,
+        destination_chain: u32,
+        destination: Option<&BytesN<32>>,
     ) -> BytesN<32> {
         sender.require_auth();
+        Self::ensure_not_shut_down(env);
+        Self::ensure_not_paused(env);
+        Self::close_import(env);
 
-        // Validate amount
-        let min_amount: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::MinLockAmount)
-            .unwrap_or(1_000_000); // Default 1 token (with 6 decimals)
+        let commitment = Self::try_prepare_lock(
+            env,
+            sender,
+            token,
+            amount,
+            nonce,
+            destination_chain,
+            destination,
+        )
+        .unwrap_or_else(|error| panic_with_error!(env, error));
+
+        // Transfer tokens to contract
+        let token_client = token::Client::new(env, token);
+        token_client.transfer(sender, &env.current_contract_address(), &amount);
+
+        Self::record_lock(env, &commitment, nonce);
+
+        commitment.commitment_hash
+    }
+
+    /// Lock funds for several commitments at once
+    ///
+    /// Each request is checked and derived exactly like a `lock_funds` call,
+    /// but the sender authorizes once and the summed amount moves in a single
+    /// token transfer. Every request is validated before anything is
+    /// transferred. With `abort_on_error` the first bad entry rejects the
+    /// whole batch; otherwise bad entries are skipped and the rest locked.
+    /// One result is returned per request, index for index. Batches always
+    /// lock the primary token.
+    pub fn lock_funds_batch(
+        env: Env,
+        sender: Address,
+        locks: Vec<LockRequest>,
+        abort_on_error: bool,
+    ) -> Vec<Result<BytesN<32>, BridgeError>> {
+        sender.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::ensure_not_paused(&env);
+        Self::close_import(&env);
 
-        if amount < min_amount {
-            panic!("Amount below minimum");
+        if locks.is_empty() || locks.len() > MAX_LOCK_BATCH {
+            panic!("Invalid lock batch size");
         }
 
-        // Check if commitment already exists
-        if env
-            .storage()
-            .persistent()
-            .has(&DataKey::Commitment(commitment_hash.clone()))
-        {
-            panic!("Commitment already exists");
+        let token_contract = Self::primary_token(&env);
+        let mut results: Vec<Result<BytesN<32>, BridgeError>> = Vec::new(&env);
+        let mut prepared: Vec<(BridgeCommitment, u64)> = Vec::new(&env);
+        let mut total: i128 = 0;
+        for lock in locks.iter() {
+            // Accepted entries aren't recorded yet, so check nonces among them
+            let result = if prepared.iter().any(|(_, nonce)| nonce == lock.nonce) {
+                Err(BridgeError::DuplicateNonce)
+            } else {
+                Self::try_prepare_lock(
+                    &env,
+                    &sender,
+                    &token_contract,
+                    lock.amount,
+                    lock.nonce,
+                    lock.destination_chain,
+                    None,
+                )
+            };
+            match result {
+                Ok(commitment) => {
+                    total = total.checked_add(lock.amount).expect("Batch amount overflow");
+                    results.push_back(Ok(commitment.commitment_hash.clone()));
+                    prepared.push_back((commitment, lock.nonce));
+                }
+                Err(error) if abort_on_error => panic_with_error!(&env, error),
+                Err(error) => results.push_back(Err(error)),
+            }
         }
 
-        // Transfer tokens to contract
-        let token_contract: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::TokenContract)
-            .unwrap();
-        let token_client = token::Client::new(&env, &token_contract);
-        token_client.transfer(&sender, &env.current_contract_address(), &amount);
+        if total > 0 {
+            let token_client = token::Client::new(&env, &token_contract);
+            token_client.transfer(&sender, &env.current_contract_address(), &total);
+        }
 
-        // Create commitment record
-        let commitment = BridgeCommitment {
-            commitment_hash: commitment_hash.clone(),
-            sender: sender.clone(),
-            amount,
-            timestamp: env.ledger().timestamp(),
-            destination_chain,
-            status: CommitmentStatus::Locked,
-        };
+        for (commitment, nonce) in prepared.iter() {
+            Self::record_lock(&env, &commitment, nonce);
+        }
 
-        // Store commitment
-        env.storage()
-            .persistent()
-            .set(&DataKey::Commitment(commitment_hash.clone()), &commitment);
+        results
+    }
 
-        // Update total locked
-        let total_locked: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TotalLocked)
-            .unwrap_or(0);
-        env.storage()
-            .instance()
-            .set(&DataKey::TotalLocked, &(total_locked + amount));
+    /// Validate a lock and build its commitment, returning the first failed
+    /// check. Nothing is written unless every check passes, and then only
+    /// the per-ledger lock count and the rent charge.
+    fn try_prepare_lock(
+        env: &Env,
+        sender: &Address,
+        token: &Address,
+        amount: i128,
+        nonce: u64,
+        destination_chain: u32,
+        destination: Option<&BytesN<32>>,
+    ) -> Result<BridgeCommitment, BridgeError> {
+        let is_primary = *token == Self::primary_token(env);
+        if !is_primary && !Self::is_supported_token(env.clone(), token.clone()) {
+            return Err(BridgeError::UnsupportedToken);
+        }
 
-        // Emit lock event
-        env.events().publish(
-            (Symbol::new(&env, "funds_locked"),),
-            (
-                commitment_hash.clone(),
-                sender,
-                amount,
-                destination_chain,
-            ),
-        );
+        // Unconditional invariant, independent of the configurable minimum
+        if amount <= 0 {
+            return Err(BridgeError::ZeroAmount);
+        }
 
-        commitment_hash
-    }
+        // Validate amount
+        if is_primary {
+            if amount < Self::min_lock_amount(env) {
+                return Err(BridgeError::AmountBelowMinimum);
+            }
+            let max_lock_amount = Self::max_lock_amount(env);
+            if max_lock_amount > 0 && amount > max_lock_amount {
+                return Err(BridgeError::AmountAboveMaximum);
+            }
+        }
 
-    /// Verify ZK proof and unlock funds on destination chain
-    /// This is called by relayers with a valid ZK proof
-    pub fn verify_and_unlock(
-        env: Env,
-        proof: Bytes,
-        commitment_hash: BytesN<32>,
-        nullifier_hash: BytesN<32>,
-        recipient_hash: BytesN<32>,
-    ) -> bool {
-        // Check if nullifier already used (prevent double-spend)
+        // Only lock towards chains a relayer services
+        if !Self::is_chain_allowed(env.clone(), destination_chain) {
+            return Err(BridgeError::ChainNotAllowed);
+        }
+
+        // Enforce USD-denominated limits when an oracle is configured
+        if is_primary {
+            Self::check_usd_limits(env, amount)?;
+        }
+
+        // Reject a replayed (sender, nonce) pair
         if env
             .storage()
             .persistent()
-            .has(&DataKey::Nullifier(nullifier_hash.clone()))
+            .has(&DataKey::SenderNonce(sender.clone(), nonce))
         {
-            panic!("Nullifier already used - double spend attempt");
+            return Err(BridgeError::DuplicateNonce);
         }
 
-        // Get commitment
-        let commitment: BridgeCommitment = env
+        let commitment_hash = Self::derive_commitment(env, sender, nonce, amount, destination);
+
+        // A hit here means another lock already owns the hash; the caller
+        // should retry with a fresh nonce
+        if env
             .storage()
             .persistent()
-            .get(&DataKey::Commitment(commitment_hash.clone()))
-            .expect("Commitment not found");
+            .has(&DataKey::Commitment(commitment_hash.clone()))
+        {
+            return Err(BridgeError::CommitmentCollision);
+        }
 
-        // Check commitment status
-        if commitment.status != CommitmentStatus::Locked {
-            panic!("Commitment already processed");
+        // The relayer fee comes out of the claim; leave something to bridge
+        if is_primary && amount <= Self::relayer_fee(env) {
+            return Err(BridgeError::AmountBelowRelayerFee);
         }
 
-        // Verify ZK proof
-        let is_valid = Self::verify_zk_proof(
-            &env,
-            &proof,
-            &commitment_hash,
-            &nullifier_hash,
-            &recipient_hash,
-        );
+        let commitment = BridgeCommitment {
+            commitment_hash,
+            sender: sender.clone(),
+            token: token.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+            ledger_sequence: env.ledger().sequence(),
+            timeout_period: Self::timeout_period(env),
+            destination_chain,
+            status: CommitmentStatus::Locked,
+        };
 
-        if !is_valid {
-            panic!("Invalid ZK proof");
+        // Throttle the number of new commitments per ledger, and pay for the
+        // commitment entry; both only write once everything else passed
+        let rent = Self::estimate_rent(commitment.clone().to_xdr(env).len());
+        let rent_budget = Self::rent_budget_after(env, rent)?;
+        Self::count_ledger_lock(env)?;
+        if let Some(budget) = rent_budget {
+            env.storage().instance().set(&DataKey::RentBudget, &budget);
         }
 
-        // Mark nullifier as used
+        Ok(commitment)
+    }
+
+    /// Store a prepared lock whose tokens have already been transferred in
+    fn record_lock(env: &Env, commitment: &BridgeCommitment, nonce: u64) {
+        let commitment_hash = &commitment.commitment_hash;
+        let sender = &commitment.sender;
+
+        // Store commitment
         env.storage()
             .persistent()
-            .set(&DataKey::Nullifier(nullifier_hash.clone()), &true);
+            .set(&DataKey::Commitment(commitment_hash.clone()), commitment);
 
-        // Update commitment status
-        let mut updated_commitment = commitment.clone();
-        updated_commitment.status = CommitmentStatus::Claimed;
+        Self::add_to_status_index(env, CommitmentStatus::Locked, commitment_hash);
+
+        // Record the nonce as used by this sender
         env.storage()
             .persistent()
-            .set(&DataKey::Commitment(commitment_hash.clone()), &updated_commitment);
+            .set(&DataKey::SenderNonce(sender.clone(), nonce), commitment_hash);
 
-        // Emit unlock event for relayers to process on destination chain
-        env.events().publish(
-            (Symbol::new(&env, "unlock_approved"),),
-            (
-                commitment_hash,
-                nullifier_hash,
-                recipient_hash,
-                commitment.amount,
-                commitment.destination_chain,
-            ),
+        // Extend the commitment entry's TTL, paid for in `prepare_lock`
+        env.storage().persistent().extend_ttl(
+            &DataKey::Commitment(commitment_hash.clone()),
+            COMMITMENT_TTL_LEDGERS,
+            COMMITMENT_TTL_LEDGERS,
         );
 
-        true
+        // Update total locked
+        Self::adjust_total_locked(env, &commitment.token, commitment.amount);
+        Self::adjust_user_locked(env, sender, commitment.amount);
+
+        // Emit lock event
+        events::funds_locked(
+            env,
+            commitment_hash.clone(),
+            sender.clone(),
+            commitment.token.clone(),
+            commitment.amount,
+            commitment.destination_chain,
+        );
+
+        Self::insert_merkle_leaf(env, commitment_hash);
     }
 
-    /// Internal ZK proof verification
-    /// In production, this would use a proper ZK verifier contract
-    fn verify_zk_proof(
-        env: &Env,
-        proof: &Bytes,
-        commitment: &BytesN<32>,
-        nullifier: &BytesN<32>,
-        recipient: &BytesN<32>,
-    ) -> bool {
-        // Simplified verification for testnet
-        // In production, this would:
-        // 1. Load verification key from storage
-        // 2. Verify the Groth16/Plonk proof
-        // 3. Check public inputs match commitment, nullifier, recipient_hash
-        
-        // For now, verify proof is not empty and has minimum length
-        if proof.len() < 32 {
-            return false;
+    /// Append `leaf` to the commitment Merkle tree and update its root
+    ///
+    /// Only the rightmost filled node of each level is kept, and the roots of
+    /// empty subtrees are precomputed, so an insert costs one hash per level.
+    /// The filled nodes share one persistent entry kept near the maximum TTL:
+    /// losing it to archival would leave every later insert without its
+    /// left siblings.
+    fn insert_merkle_leaf(env: &Env, leaf: &BytesN<32>) -> u32 {
+        let index: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MerkleNextIndex)
+            .unwrap_or(0);
+        if index >= 1 << MERKLE_TREE_DEPTH {
+            panic!("Merkle tree full");
         }
 
-        // Verify all public inputs are non-zero
-        let zero_hash = BytesN::from_array(env, &[0u8; 32]);
-        if commitment == &zero_hash || nullifier == &zero_hash || recipient == &zero_hash {
-            return false;
+        let mut filled: Vec<BytesN<32>> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::MerkleFilledSubtrees)
+            .unwrap_or_else(|| {
+                let mut filled = Vec::new(env);
+                for zero in &MERKLE_ZEROS[..MERKLE_TREE_DEPTH as usize] {
+                    filled.push_back(BytesN::from_array(env, zero));
+                }
+                filled
+            });
+
+        let mut node = leaf.to_array();
+        let mut position = index;
+        for level in 0..MERKLE_TREE_DEPTH {
+            if position & 1 == 0 {
+                filled.set(level, BytesN::from_array(env, &node));
+                node = Self::merkle_hash(&node, &MERKLE_ZEROS[level as usize]);
+            } else {
+                let left = filled.get_unchecked(level).to_array();
+                node = Self::merkle_hash(&left, &node);
+            }
+            position >>= 1;
         }
 
-        // TODO: Add actual ZK proof verification using Groth16 verifier
-        // This would call into a verifier contract or use native Soroban crypto
-        
-        true
+        let max_ttl = env.storage().max_ttl();
+        env.storage()
+            .persistent()
+            .set(&DataKey::MerkleFilledSubtrees, &filled);
+        env.storage().persistent().extend_ttl(
+            &DataKey::MerkleFilledSubtrees,
+            max_ttl - COMMITMENT_TTL_LEDGERS,
+            max_ttl,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::MerkleRoot, &BytesN::from_array(env, &node));
+        env.storage()
+            .instance()
+            .set(&DataKey::MerkleNextIndex, &(index + 1));
+        env.storage()
+            .persistent()
+            .set(&DataKey::LeafIndex(leaf.clone()), &index);
+        env.storage().persistent().extend_ttl(
+            &DataKey::LeafIndex(leaf.clone()),
+            COMMITMENT_TTL_LEDGERS,
+            COMMITMENT_TTL_LEDGERS,
+        );
+
+        index
     }
 
-    /// Refund locked funds if timeout expires (emergency)
-    pub fn refund(env: Env, commitment_hash: BytesN<32>) {
-        let commitment: BridgeCommitment = env
+    /// Node hash of the commitment Merkle tree: Poseidon, as in the bridge
+    /// circuit. Swapping the tree's hash only touches this function.
+    fn merkle_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        bridge_poseidon::hash(&[*left, *right])
+    }
+
+    /// Lock funds like `lock_funds`, committing to a destination address
+    ///
+    /// `destination_address` must be well-formed for `destination_address_type`
+    /// and, when the admin configured one for `destination_chain`, that type
+    /// must be the chain's expected one. Otherwise the call fails with
+    /// `InvalidDestinationAddress` before any funds move.
+    ///
+    /// The destination is bound into the commitment (see `derive_commitment`)
+    /// and only unlocks with `destination_hash` as the recipient, so a relayer
+    /// cannot redirect it.
+    pub fn lock_funds_to(
+        env: Env,
+        sender: Address,
+        amount: i128,
+        nonce: u64,
+        destination_chain: u32,
+        destination_address: Bytes,
+        destination_address_type: u32,
+    ) -> BytesN<32> {
+        let expected: Option<u32> = env
             .storage()
-            .persistent()
-            .get(&DataKey::Commitment(commitment_hash.clone()))
-            .expect("Commitment not found");
+            .instance()
+            .get(&DataKey::ChainAddressType(destination_chain));
+        if expected.is_some_and(|expected| expected != destination_address_type)
+            || !Self::is_valid_address(&destination_address, destination_address_type)
+        {
+            panic_with_error!(&env, BridgeError::InvalidDestinationAddress);
+        }
 
-        // Only sender can refund
-        commitment.sender.require_auth();
+        let destination = Self::destination_hash(
+            env.clone(),
+            destination_address_type,
+            destination_address.clone(),
+        );
+        let commitment_hash = Self::lock(
+            &env,
+            &sender,
+            &Self::primary_token(&env),
+            amount,
+            nonce,
+            destination_chain,
+            Some(&destination),
+        );
+        env.storage().persistent().set(
+            &DataKey::Destination(commitment_hash.clone()),
+            &(destination_address_type, destination_address),
+        );
 
-        // Check if enough time has passed (7 days = 604800 seconds)
-        let timeout_period = 604800u64;
-        let current_time = env.ledger().timestamp();
-        
-        if current_time < commitment.timestamp + timeout_period {
-            panic!("Timeout period not reached");
-        }
+        commitment_hash
+    }
 
-        if commitment.status != CommitmentStatus::Locked {
-            panic!("Commitment already processed");
+    /// Length and prefix checks for a destination address of the given type
+    fn is_valid_address(address: &Bytes, address_type: u32) -> bool {
+        match address_type {
+            ADDRESS_TYPE_RAW32 => address.len() == 32,
+            // Single-byte SS58 prefixes only cover network ids below 64
+            ADDRESS_TYPE_SS58 => address.len() == 35 && address.get_unchecked(0) < 64,
+            ADDRESS_TYPE_EVM => address.len() == 20,
+            _ => false,
         }
+    }
+
+    /// Recipient hash a `lock_funds_to` commitment unlocks to:
+    /// sha256(address_type as 4 big-endian bytes || destination_address)
+    pub fn destination_hash(
+        env: Env,
+        destination_address_type: u32,
+        destination_address: Bytes,
+    ) -> BytesN<32> {
+        let mut preimage = Bytes::from_array(&env, &destination_address_type.to_be_bytes());
+        preimage.append(&destination_address);
+        env.crypto().sha256(&preimage).into()
+    }
 
-        // Transfer tokens back to sender
-        let token_contract: Address = env
+    /// Reject a recipient other than the destination a commitment is bound to
+    fn check_destination(env: &Env, commitment_hash: &BytesN<32>, recipient_hash: &BytesN<32>) {
+        let destination: Option<(u32, Bytes)> = env
             .storage()
-            .instance()
-            .get(&DataKey::TokenContract)
-            .unwrap();
-        let token_client = token::Client::new(&env, &token_contract);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &commitment.sender,
-            &commitment.amount,
-        );
+            .persistent()
+            .get(&DataKey::Destination(commitment_hash.clone()));
+        if let Some((address_type, address)) = destination {
+            if Self::destination_hash(env.clone(), address_type, address) != *recipient_hash {
+                panic_with_error!(env, BridgeError::InvalidDestinationAddress);
+            }
+        }
+    }
 
-        // Update commitment status
-        let mut updated_commitment = commitment.clone();
-        updated_commitment.status = CommitmentStatus::Refunded;
+    /// Get the destination address type and address committed by `lock_funds_to`
+    pub fn get_destination(env: Env, commitment_hash: BytesN<32>) -> Option<(u32, Bytes)> {
         env.storage()
             .persistent()
-            .set(&DataKey::Commitment(commitment_hash.clone()), &updated_commitment);
+            .get(&DataKey::Destination(commitment_hash))
+    }
 
-        // Update total locked
-        let total_locked: i128 = env
+    fn check_usd_limits(env: &Env, amount: i128) -> Result<(), BridgeError> {
+        let limits: Option<UsdLimits> = env.storage().instance().get(&DataKey::UsdLimits);
+        let limits = match limits {
+            Some(limits) => limits,
+            None => return Ok(()),
+        };
+
+        let (price, published_at) = PriceOracleClient::new(env, &limits.oracle).price();
+        if price <= 0 {
+            return Err(BridgeError::InvalidOraclePrice);
+        }
+        let expires_at = published_at
+            .checked_add(limits.max_price_age)
+            .ok_or(BridgeError::ArithmeticOverflow)?;
+        if env.ledger().timestamp() > expires_at {
+            return Err(BridgeError::StaleOraclePrice);
+        }
+
+        if amount < Self::cents_to_units(env, limits.min_cents, price)? {
+            return Err(BridgeError::AmountBelowMinimum);
+        }
+        if limits.max_cents > 0 && amount > Self::cents_to_units(env, limits.max_cents, price)? {
+            return Err(BridgeError::AmountAboveMaximum);
+        }
+        Ok(())
+    }
+
+    /// Convert USD cents to token units at `price` cents per whole token
+    fn cents_to_units(env: &Env, cents: i128, price: i128) -> Result<i128, BridgeError> {
+        let token_contract = Self::primary_token(env);
+        let decimals = token::Client::new(env, &token_contract).decimals();
+        10i128
+            .checked_pow(decimals)
+            .and_then(|scale| cents.checked_mul(scale))
+            .map(|units| units / price)
+            .ok_or(BridgeError::ArithmeticOverflow)
+    }
+
+    fn ensure_not_shut_down(env: &Env) {
+        if env
             .storage()
             .instance()
-            .get(&DataKey::TotalLocked)
-            .unwrap_or(0);
-        env.storage()
+            .get(&DataKey::Shutdown)
+            .unwrap_or(false)
+        {
+            panic!("Bridge shut down");
+        }
+    }
+
+    fn ensure_not_paused(env: &Env) {
+        if env
+            .storage()
             .instance()
-            .set(&DataKey::TotalLocked, &(total_locked - commitment.amount));
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+        {
+            panic_with_error!(env, BridgeError::ContractPaused);
+        }
+    }
 
-        // Emit refund event
-        env.events().publish(
-            (Symbol::new(&env, "refunded"),),
-            (commitment_hash, commitment.sender, commitment.amount),
-        );
+    /// Minimum lock amount; 1 token (6 decimals) until configured
+    fn min_lock_amount(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinLockAmount)
+            .unwrap_or(1_000_000)
     }
 
-    /// Get commitment details
-    pub fn get_commitment(env: Env, commitment_hash: BytesN<32>) -> BridgeCommitment {
+    /// Maximum lock amount; 0 (no maximum) until configured
+    fn max_lock_amount(env: &Env) -> i128 {
         env.storage()
-            .persistent()
-            .get(&DataKey::Commitment(commitment_hash))
-            .expect("Commitment not found")
+            .instance()
+            .get(&DataKey::MaxLockAmount)
+            .unwrap_or(0)
     }
 
-    /// Check if nullifier is used
-    pub fn is_nullifier_used(env: Env, nullifier_hash: BytesN<32>) -> bool {
+    /// A maximum must be 0 (no maximum) or at least the minimum; anything
+    /// else would reject every lock
+    fn check_lock_bounds(min_lock_amount: i128, max_lock_amount: i128) {
+        if max_lock_amount < 0 || (max_lock_amount > 0 && max_lock_amount < min_lock_amount) {
+            panic!("Invalid lock amount bounds");
+        }
+    }
+
+    /// Refund timeout snapshotted into new commitments; 7 days until configured
+    fn timeout_period(env: &Env) -> u64 {
         env.storage()
-            .persistent()
-            .get(&DataKey::Nullifier(nullifier_hash))
-            .unwrap_or(false)
+            .instance()
+            .get(&DataKey::TimeoutPeriod)
+            .unwrap_or(DEFAULT_TIMEOUT_PERIOD)
     }
 
-    /// Get total locked amount
-    pub fn get_total_locked(env: Env) -> i128 {
+    /// Token locked when `lock_funds` is not given one
+    fn primary_token(env: &Env) -> Address {
         env.storage()
             .instance()
-            .get(&DataKey::TotalLocked)
-            .unwrap_or(0)
+            .get(&DataKey::TokenContract)
+            .unwrap_or_else(|| panic_with_error!(env, BridgeError::NotInitialized))
     }
 
-    /// Admin function to update configuration
-    pub fn update_config(
-        env: Env,
-        admin: Address,
-        min_lock_amount: Option<i128>,
-        relayer_fee: Option<i128>,
-    ) {
-        // Verify admin
-        let current_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != current_admin {
-            panic!("Unauthorized");
+    /// Add `delta` to the locked total of `token`; `TotalLocked` keeps
+    /// tracking the primary token alone
+    fn adjust_total_locked(env: &Env, token: &Address, delta: i128) {
+        let key = DataKey::TotalLockedByToken(token.clone());
+        let locked: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(locked + delta));
+
+        if *token == Self::primary_token(env) {
+            let total_locked: i128 = env
+                .storage()
+                .instance()
+                .get(&DataKey::TotalLocked)
+                .unwrap_or(0);
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalLocked, &(total_locked + delta));
         }
-        admin.require_auth();
+    }
 
-        if let Some(min_amount) = min_lock_amount {
-            env.storage().instance().set(&DataKey::MinLockAmount, &min_amount);
+    /// Add `delta` to the amount of `token` escrowed by open swaps
+    fn adjust_swap_escrow(env: &Env, token: &Address, delta: i128) {
+        let key = DataKey::SwapEscrow(token.clone());
+        let escrowed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(escrowed + delta));
+    }
+
+    /// Add `delta` to `user`'s locked total, dropping the entry once it
+    /// reaches zero
+    fn adjust_user_locked(env: &Env, user: &Address, delta: i128) {
+        let key = DataKey::UserLocked(user.clone());
+        let locked: i128 = env.storage().persistent().get(&key).unwrap_or(0) + delta;
+        if locked == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &locked);
         }
+    }
 
-        if let Some(fee) = relayer_fee {
-            env.storage().instance().set(&DataKey::RelayerFee, &fee);
+    /// Relayer fee taken from each claimed commitment; none until configured
+    fn relayer_fee(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RelayerFee)
+            .unwrap_or(0)
+    }
+
+    /// Split the relayer fee off a claimed commitment, returning `(fee, net)`
+    ///
+    /// The fee may have been raised since the lock, so it is checked again
+    /// against the locked amount here. It is denominated in the primary token,
+    /// so commitments in other tokens bridge their full amount.
+    fn split_relayer_fee(env: &Env, commitment: &BridgeCommitment) -> (i128, i128) {
+        if commitment.token != Self::primary_token(env) {
+            return (0, commitment.amount);
         }
+        let fee = Self::relayer_fee(env).max(0);
+        if fee > commitment.amount {
+            panic!("Relayer fee exceeds locked amount");
+        }
+        (fee, commitment.amount - fee)
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "config_updated"),),
-            admin,
-        );
+    /// Move a claimed commitment's relayer fee out of the locked total and
+    /// into the fee vault
+    fn accrue_fee_vault(env: &Env, token: &Address, fee: i128) {
+        let fee_vault: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeVault)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeVault, &(fee_vault + fee));
+        Self::adjust_total_locked(env, token, -fee);
+    }
+
+    /// Stored admin; `NotInitialized` before `initialize`
+    fn admin(env: &Env) -> Result<Address, BridgeError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(BridgeError::NotInitialized)
+    }
+
+    /// Panic unless `admin` is the stored admin and has authorized the call
+    fn require_admin(env: &Env, admin: &Address) {
+        let current_admin = Self::admin(env).unwrap_or_else(|error| panic_with_error!(env, error));
+        if *admin != current_admin {
+            panic_with_error!(env, BridgeError::Unauthorized);
+        }
+        admin.require_auth();
+    }
+
+    /// The first native operation ends the migration window
+    fn close_import(env: &Env) {
+        env.storage().instance().remove(&DataKey::ImportOpen);
+    }
+
+    /// Count a new lock against the per-ledger cap, resetting on a new ledger
+    fn count_ledger_lock(env: &Env) -> Result<(), BridgeError> {
+        let sequence = env.ledger().sequence();
+        let last_ledger: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastLockLedger)
+            .unwrap_or(0);
+        let count: u32 = if last_ledger == sequence {
+            env.storage()
+                .instance()
+                .get(&DataKey::LedgerLockCount)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+
+        let cap: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::MaxLocksPerLedger)
+            .unwrap_or(0);
+        if cap > 0 && count >= cap {
+            return Err(BridgeError::LedgerLockLimitReached);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::LastLockLedger, &sequence);
+        env.storage()
+            .instance()
+            .set(&DataKey::LedgerLockCount, &(count + 1));
+        Ok(())
+    }
+
+    /// Move a commitment to a new status, keeping the per-status indices in sync
+    fn set_status(env: &Env, commitment: &mut BridgeCommitment, status: CommitmentStatus) {
+        Self::remove_from_status_index(env, commitment.status, &commitment.commitment_hash);
+        commitment.status = status;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Commitment(commitment.commitment_hash.clone()), commitment);
+        Self::add_to_status_index(env, status, &commitment.commitment_hash);
+    }
+
+    /// Record `commitment_hash` under `status`, both in the per-status index
+    /// and in the commitment's status shadow entry
+    ///
+    /// Each status index is a dense run of `StatusEntry` keys plus a count,
+    /// so adding and removing touch a constant number of entries however
+    /// many commitments share the status.
+    fn add_to_status_index(env: &Env, status: CommitmentStatus, commitment_hash: &BytesN<32>) {
+        let shadow_key = DataKey::CommitmentStatus(commitment_hash.clone());
+        env.storage().persistent().set(&shadow_key, &status);
+        env.storage()
+            .persistent()
+            .extend_ttl(&shadow_key, COMMITMENT_TTL_LEDGERS, COMMITMENT_TTL_LEDGERS);
+
+        let position = Self::status_count(env, status);
+        Self::set_status_entry(env, status, position, commitment_hash);
+        env.storage()
+            .instance()
+            .set(&DataKey::StatusCount(status), &(position + 1));
+    }
+
+    /// Drop `commitment_hash` from the index of `status`, moving the last
+    /// entry into its place
+    fn remove_from_status_index(
+        env: &Env,
+        status: CommitmentStatus,
+        commitment_hash: &BytesN<32>,
+    ) {
+        let position_key = DataKey::StatusPosition(commitment_hash.clone());
+        let Some(position) = env.storage().persistent().get::<_, u32>(&position_key) else {
+            return;
+        };
+        let count = Self::status_count(env, status);
+        if position >= count {
+            return;
+        }
+
+        let last = count - 1;
+        if position != last {
+            let moved: BytesN<32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::StatusEntry(status, last))
+                .unwrap();
+            Self::set_status_entry(env, status, position, &moved);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::StatusEntry(status, last));
+        env.storage().persistent().remove(&position_key);
+        env.storage()
+            .instance()
+            .set(&DataKey::StatusCount(status), &last);
+    }
+
+    /// Store `commitment_hash` at `position` of the index of `status`
+    fn set_status_entry(
+        env: &Env,
+        status: CommitmentStatus,
+        position: u32,
+        commitment_hash: &BytesN<32>,
+    ) {
+        let entry_key = DataKey::StatusEntry(status, position);
+        let position_key = DataKey::StatusPosition(commitment_hash.clone());
+        env.storage().persistent().set(&entry_key, commitment_hash);
+        env.storage().persistent().set(&position_key, &position);
+        for key in [entry_key, position_key] {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, COMMITMENT_TTL_LEDGERS, COMMITMENT_TTL_LEDGERS);
+        }
+    }
+
+    /// Number of commitments currently in `status`
+    fn status_count(env: &Env, status: CommitmentStatus) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::StatusCount(status))
+            .unwrap_or(0)
+    }
+
+    /// Estimated rent for keeping an entry of `entry_bytes` alive for the commitment TTL
+    fn estimate_rent(entry_bytes: u32) -> i128 {
+        let cost = entry_bytes as i128 * COMMITMENT_TTL_LEDGERS as i128;
+        // Round up so small entries are never free
+        (cost + RENT_BYTE_LEDGERS_PER_UNIT - 1) / RENT_BYTE_LEDGERS_PER_UNIT
+    }
+
+    /// Prepaid rent budget left after paying `cost`, or `None` while rent
+    /// tracking is off: nothing is charged until the admin first tops up
+    fn rent_budget_after(env: &Env, cost: i128) -> Result<Option<i128>, BridgeError> {
+        let budget: Option<i128> = env.storage().instance().get(&DataKey::RentBudget);
+        match budget {
+            Some(budget) if budget < cost => Err(BridgeError::RentBudgetExhausted),
+            budget => Ok(budget.map(|budget| budget - cost)),
+        }
+    }
+
+    /// Deterministic commitment for a lock: poseidon(sha256(sender), amount, nonce)
+    ///
+    /// This is the commitment the bridge circuit constrains, so the hash a
+    /// lock records is the one a proof is generated against. The sender is
+    /// part of the preimage, so distinct senders never share a commitment
+    /// even with otherwise identical inputs; `(sender, nonce)` pairs are
+    /// single-use, so neither does one sender's locks. A `lock_funds_to`
+    /// lock enters as sha256(sender XDR || destination hash) instead.
+    fn derive_commitment(
+        env: &Env,
+        sender: &Address,
+        nonce: u64,
+        amount: i128,
+        destination: Option<&BytesN<32>>,
+    ) -> BytesN<32> {
+        let sender_hash = match destination {
+            Some(destination) => {
+                let mut preimage = sender.clone().to_xdr(env);
+                preimage.extend_from_array(&destination.to_array());
+                env.crypto().sha256(&preimage).into()
+            }
+            None => Self::hash_address(env, sender),
+        };
+        Self::poseidon_commitment(env, &sender_hash, amount as u128, nonce)
+    }
+
+    /// Verify ZK proof and unlock funds on destination chain
+    /// This is called by relayers with a valid ZK proof
+    ///
+    /// The configured relayer fee is deducted from `commitment.amount` and
+    /// accrues in the fee vault, like every other claim path's, for relayers
+    /// to take out with `claim_fee_vault`; the `unlock_approved` event
+    /// carries the remainder.
+    pub fn verify_and_unlock(
+        env: Env,
+        relayer: Address,
+        proof: Bytes,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipient_hash: BytesN<32>,
+    ) -> bool {
+        relayer.require_auth();
+
+        let commitment =
+            Self::claim_commitment(&env, &proof, &commitment_hash, &nullifier_hash, &recipient_hash);
+
+        let (fee, net) = Self::split_relayer_fee(&env, &commitment);
+        Self::accrue_fee_vault(&env, &commitment.token, fee);
+
+        // Emit unlock event for relayers to process on destination chain
+        events::unlock_approved(
+            &env,
+            commitment_hash,
+            nullifier_hash,
+            recipient_hash,
+            net,
+            commitment.destination_chain,
+        );
+
+        true
+    }
+
+    /// Verify a ZK proof and pay the locked funds out to `recipient` on Stellar
+    ///
+    /// Same-chain counterpart of `verify_and_unlock`: the proof's recipient
+    /// public input is `sha256(recipient XDR)`, and the bridged amount leaves
+    /// the contract instead of being signalled to another chain. There is no
+    /// calling relayer to pay, so the relayer fee accrues in the fee vault.
+    pub fn verify_and_claim(
+        env: Env,
+        proof: Bytes,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipient: Address,
+    ) -> i128 {
+        let recipient_hash = Self::hash_address(&env, &recipient);
+        let commitment =
+            Self::claim_commitment(&env, &proof, &commitment_hash, &nullifier_hash, &recipient_hash);
+
+        let (fee, net) = Self::split_relayer_fee(&env, &commitment);
+        Self::accrue_fee_vault(&env, &commitment.token, fee);
+
+        let token_client = token::Client::new(&env, &commitment.token);
+        token_client.transfer(&env.current_contract_address(), &recipient, &net);
+
+        Self::adjust_total_locked(&env, &commitment.token, -net);
+
+        events::claimed(&env, commitment_hash, nullifier_hash, recipient, net);
+
+        net
+    }
+
+    /// Verify a ZK proof and split the unlock across several recipients
+    ///
+    /// `recipients` pairs each recipient hash with its share; the shares must
+    /// sum to the locked amount less the relayer fee, which accrues in the fee
+    /// vault. The proof's recipient public input is the digest of the whole
+    /// list (see `recipients_digest`), so a relayer cannot reorder or re-split
+    /// it. One `unlock_approved` event is emitted per share.
+    pub fn verify_and_unlock_multi(
+        env: Env,
+        proof: Bytes,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipients: Vec<(BytesN<32>, i128)>,
+    ) -> bool {
+        if recipients.is_empty() || recipients.len() > MAX_SPLIT_RECIPIENTS {
+            panic!("Invalid recipient split");
+        }
+
+        let digest = Self::recipients_digest(&env, &recipients);
+        let commitment =
+            Self::claim_commitment(&env, &proof, &commitment_hash, &nullifier_hash, &digest);
+
+        let mut total: i128 = 0;
+        for (_, share) in recipients.iter() {
+            if share <= 0 {
+                panic!("Invalid recipient split");
+            }
+            total += share;
+        }
+        let (fee, net) = Self::split_relayer_fee(&env, &commitment);
+        if total != net {
+            panic!("Recipient shares must sum to the locked amount");
+        }
+        Self::accrue_fee_vault(&env, &commitment.token, fee);
+
+        for (recipient_hash, share) in recipients.iter() {
+            events::unlock_approved(
+                &env,
+                commitment_hash.clone(),
+                nullifier_hash.clone(),
+                recipient_hash,
+                share,
+                commitment.destination_chain,
+            );
+        }
+
+        true
+    }
+
+    /// Recipient public input for a split unlock: sha256(recipient_hash || share, ...)
+    fn recipients_digest(env: &Env, recipients: &Vec<(BytesN<32>, i128)>) -> BytesN<32> {
+        let mut preimage = Bytes::new(env);
+        for (recipient_hash, share) in recipients.iter() {
+            preimage.extend_from_array(&recipient_hash.to_array());
+            preimage.extend_from_array(&share.to_be_bytes());
+        }
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Propose an unlock without a proof (optimistic mode)
+    ///
+    /// Only available while the admin has set a challenge window. A
+    /// whitelisted relayer posts the challenge bond, and the nullifier is
+    /// reserved straight away. Unless someone challenges within the window,
+    /// `finalize_unlock` then approves the unlock as `verify_and_unlock`
+    /// would and returns the bond.
+    pub fn propose_unlock(
+        env: Env,
+        relayer: Address,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+        recipient_hash: BytesN<32>,
+    ) {
+        relayer.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::ensure_not_paused(&env);
+        Self::close_import(&env);
+
+        let window = Self::challenge_window(&env);
+        if window == 0 {
+            panic!("Optimistic unlock disabled");
+        }
+        if !Self::is_relayer(env.clone(), relayer.clone()) {
+            panic_with_error!(&env, BridgeError::Unauthorized);
+        }
+
+        let status: CommitmentStatus = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CommitmentStatus(commitment_hash.clone()))
+            .expect("Commitment not found");
+        if status != CommitmentStatus::Locked {
+            panic!("Commitment already processed");
+        }
+        Self::ensure_no_pending_unlock(&env, &commitment_hash);
+        Self::check_destination(&env, &commitment_hash, &recipient_hash);
+
+        let nullifier_key = Self::nullifier_key(&env, &commitment_hash, &nullifier_hash);
+        if env.storage().persistent().has(&nullifier_key) {
+            panic!("Nullifier already used - double spend attempt");
+        }
+        env.storage().persistent().set(&nullifier_key, &true);
+
+        let bond = Self::challenge_bond(&env);
+        Self::take_bond(&env, &relayer, bond);
+
+        let proposed_at = env.ledger().timestamp();
+        env.storage().persistent().set(
+            &DataKey::PendingUnlock(commitment_hash.clone()),
+            &PendingUnlock {
+                relayer: relayer.clone(),
+                nullifier_hash,
+                recipient_hash,
+                relayer_bond: bond,
+                proposed_at,
+                challenger: None,
+                challenger_bond: 0,
+            },
+        );
+
+        events::unlock_proposed(&env, commitment_hash, relayer, proposed_at + window);
+    }
+
+    /// Challenge a pending unlock within its window, posting the challenge bond
+    ///
+    /// Only one challenge is taken per unlock; the admin settles it with
+    /// `resolve_challenge`.
+    pub fn challenge_unlock(env: Env, challenger: Address, commitment_hash: BytesN<32>) {
+        challenger.require_auth();
+
+        let mut pending = Self::pending_unlock(&env, &commitment_hash);
+        if pending.challenger.is_some() {
+            panic!("Unlock already challenged");
+        }
+        if env.ledger().timestamp() >= pending.proposed_at + Self::challenge_window(&env) {
+            panic!("Challenge window closed");
+        }
+
+        let bond = Self::challenge_bond(&env);
+        Self::take_bond(&env, &challenger, bond);
+
+        pending.challenger = Some(challenger.clone());
+        pending.challenger_bond = bond;
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingUnlock(commitment_hash.clone()), &pending);
+
+        events::unlock_challenged(&env, commitment_hash, challenger);
+    }
+
+    /// Admin function to adjudicate a challenged unlock
+    ///
+    /// If the fraud proof is `upheld`, the unlock is canceled, its nullifier
+    /// released and the challenger gets both bonds back. Otherwise the
+    /// challenger's bond is forfeited to the relayer and the unlock is
+    /// approved.
+    pub fn resolve_challenge(env: Env, commitment_hash: BytesN<32>, upheld: bool) {
+        Self::admin(&env)
+            .unwrap_or_else(|error| panic_with_error!(&env, error))
+            .require_auth();
+
+        let pending = Self::pending_unlock(&env, &commitment_hash);
+        let Some(challenger) = pending.challenger.clone() else {
+            panic!("Unlock not challenged");
+        };
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingUnlock(commitment_hash.clone()));
+
+        let payout = pending.relayer_bond + pending.challenger_bond;
+        let winner = if upheld {
+            let nullifier_key =
+                Self::nullifier_key(&env, &commitment_hash, &pending.nullifier_hash);
+            env.storage().persistent().remove(&nullifier_key);
+            challenger
+        } else {
+            Self::approve_pending_unlock(&env, &commitment_hash, &pending);
+            pending.relayer.clone()
+        };
+        Self::release_bond(&env, &winner, payout);
+
+        events::challenge_resolved(&env, commitment_hash, upheld, winner, payout);
+    }
+
+    /// Approve an unchallenged unlock once its window has passed, returning
+    /// the relayer's bond
+    pub fn finalize_unlock(env: Env, commitment_hash: BytesN<32>) {
+        let pending = Self::pending_unlock(&env, &commitment_hash);
+        if pending.challenger.is_some() {
+            panic!("Unlock challenged");
+        }
+        if env.ledger().timestamp() < pending.proposed_at + Self::challenge_window(&env) {
+            panic!("Challenge window open");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingUnlock(commitment_hash.clone()));
+        Self::approve_pending_unlock(&env, &commitment_hash, &pending);
+        Self::release_bond(&env, &pending.relayer, pending.relayer_bond);
+    }
+
+    /// Get the pending optimistic unlock of a commitment, if any
+    pub fn get_pending_unlock(env: Env, commitment_hash: BytesN<32>) -> Option<PendingUnlock> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingUnlock(commitment_hash))
+    }
+
+    /// Admin function to configure optimistic unlocks: the challenge window in
+    /// seconds (0 disables `propose_unlock`) and the bond, in the primary
+    /// token, that relayers and challengers post
+    pub fn set_challenge_config(env: Env, admin: Address, window: u64, bond: i128) {
+        Self::require_admin(&env, &admin);
+
+        if bond < 0 {
+            panic!("Invalid challenge bond");
+        }
+
+        env.storage().instance().set(&DataKey::ChallengeWindow, &window);
+        env.storage().instance().set(&DataKey::ChallengeBond, &bond);
+    }
+
+    /// Get the challenge window and bond of the optimistic unlock mode
+    pub fn get_challenge_config(env: Env) -> (u64, i128) {
+        (Self::challenge_window(&env), Self::challenge_bond(&env))
+    }
+
+    /// Challenge window of optimistic unlocks; 0 (disabled) until configured
+    fn challenge_window(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ChallengeWindow)
+            .unwrap_or(0)
+    }
+
+    /// Bond posted for optimistic unlocks and their challenges; none until configured
+    fn challenge_bond(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ChallengeBond)
+            .unwrap_or(0)
+    }
+
+    fn pending_unlock(env: &Env, commitment_hash: &BytesN<32>) -> PendingUnlock {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingUnlock(commitment_hash.clone()))
+            .expect("No pending unlock")
+    }
+
+    /// Claims and refunds must wait for a pending optimistic unlock to settle
+    fn ensure_no_pending_unlock(env: &Env, commitment_hash: &BytesN<32>) {
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::PendingUnlock(commitment_hash.clone()))
+        {
+            panic!("Unlock pending");
+        }
+    }
+
+    /// Mark the commitment behind a settled optimistic unlock claimed; the
+    /// relayer fee accrues in the fee vault as for any other unlock
+    fn approve_pending_unlock(env: &Env, commitment_hash: &BytesN<32>, pending: &PendingUnlock) {
+        let mut commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .expect("Commitment not found");
+        Self::set_status(env, &mut commitment, CommitmentStatus::Claimed);
+        Self::adjust_user_locked(env, &commitment.sender, -commitment.amount);
+
+        let (fee, net) = Self::split_relayer_fee(env, &commitment);
+        Self::accrue_fee_vault(env, &commitment.token, fee);
+
+        events::unlock_approved(
+            env,
+            commitment_hash.clone(),
+            pending.nullifier_hash.clone(),
+            pending.recipient_hash.clone(),
+            net,
+            commitment.destination_chain,
+        );
+    }
+
+    /// Move a bond from `from` into the contract
+    fn take_bond(env: &Env, from: &Address, bond: i128) {
+        if bond > 0 {
+            token::Client::new(env, &Self::primary_token(env)).transfer(
+                from,
+                &env.current_contract_address(),
+                &bond,
+            );
+        }
+        Self::adjust_bond_escrow(env, bond);
+    }
+
+    /// Pay `amount` of held bonds out to `to`
+    fn release_bond(env: &Env, to: &Address, amount: i128) {
+        Self::adjust_bond_escrow(env, -amount);
+        if amount > 0 {
+            token::Client::new(env, &Self::primary_token(env)).transfer(
+                &env.current_contract_address(),
+                to,
+                &amount,
+            );
+        }
+    }
+
+    fn adjust_bond_escrow(env: &Env, delta: i128) {
+        let escrowed: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BondEscrow)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::BondEscrow, &(escrowed + delta));
+    }
+
+    /// Verify a claim against a locked commitment and mark it claimed
+    fn claim_commitment(
+        env: &Env,
+        proof: &Bytes,
+        commitment_hash: &BytesN<32>,
+        nullifier_hash: &BytesN<32>,
+        recipient_hash: &BytesN<32>,
+    ) -> BridgeCommitment {
+        Self::ensure_not_shut_down(env);
+        Self::ensure_not_paused(env);
+        Self::close_import(env);
+
+        // Nullifiers are scoped to the commitment's current claim nonce
+        let nullifier_key = Self::nullifier_key(env, commitment_hash, nullifier_hash);
+
+        // Check if nullifier already used (prevent double-spend)
+        if env.storage().persistent().has(&nullifier_key) {
+            panic!("Nullifier already used - double spend attempt");
+        }
+
+        // Check commitment status through the shadow entry, so processed
+        // commitments are turned away without loading the full record
+        let status: CommitmentStatus = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CommitmentStatus(commitment_hash.clone()))
+            .expect("Commitment not found");
+        if status != CommitmentStatus::Locked {
+            panic!("Commitment already processed");
+        }
+        Self::ensure_no_pending_unlock(env, commitment_hash);
+        Self::check_destination(env, commitment_hash, recipient_hash);
+
+        // Get commitment
+        let mut commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .expect("Commitment not found");
+
+        // Verify ZK proof
+        let is_valid =
+            Self::verify_zk_proof(env, proof, commitment_hash, nullifier_hash, recipient_hash);
+
+        if !is_valid {
+            panic!("Invalid ZK proof");
+        }
+
+        // Mark nullifier as used
+        env.storage().persistent().set(&nullifier_key, &true);
+
+        // Update commitment status
+        Self::set_status(env, &mut commitment, CommitmentStatus::Claimed);
+        Self::adjust_user_locked(env, &commitment.sender, -commitment.amount);
+
+        commitment
+    }
+
+    /// Storage key of a nullifier claimed against `commitment_hash`; every
+    /// nullifier read and write goes through it. Relocked cycles live under
+    /// their own key, so a raw nullifier can never land on a scoped one.
+    fn nullifier_key(
+        env: &Env,
+        commitment_hash: &BytesN<32>,
+        nullifier_hash: &BytesN<32>,
+    ) -> DataKey {
+        if Self::get_claim_nonce(env.clone(), commitment_hash.clone()) == 0 {
+            return DataKey::Nullifier(nullifier_hash.clone());
+        }
+        DataKey::ScopedNullifier(Self::scoped_nullifier(env, commitment_hash, nullifier_hash))
+    }
+
+    /// Nullifier as stored for a claim against `commitment_hash`.
+    /// The first claim cycle uses the nullifier as-is; after a refund and relock
+    /// it becomes sha256(nullifier || commitment_hash || claim_nonce), so every
+    /// cycle of a reused commitment gets a fresh nullifier domain.
+    fn scoped_nullifier(
+        env: &Env,
+        commitment_hash: &BytesN<32>,
+        nullifier_hash: &BytesN<32>,
+    ) -> BytesN<32> {
+        let claim_nonce = Self::get_claim_nonce(env.clone(), commitment_hash.clone());
+        if claim_nonce == 0 {
+            return nullifier_hash.clone();
+        }
+        let mut preimage = Bytes::from_array(env, &nullifier_hash.to_array());
+        preimage.extend_from_array(&commitment_hash.to_array());
+        preimage.extend_from_array(&claim_nonce.to_be_bytes());
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Internal ZK proof verification
+    ///
+    /// A BN254 Groth16 pairing check of the compressed proof `(A, B, C)`
+    /// against the stored verification key, over the public inputs
+    /// `[commitment, nullifier, recipient, domain_separator]` reduced into the
+    /// field. Nothing verifies until a key is set. Soroban has no pairing host
+    /// function, so this runs in guest code and is only built with `groth16`.
+    #[cfg(feature = "groth16")]
+    fn verify_zk_proof(
+        env: &Env,
+        proof: &Bytes,
+        commitment: &BytesN<32>,
+        nullifier: &BytesN<32>,
+        recipient: &BytesN<32>,
+    ) -> bool {
+        use ark_bn254::{Bn254, Fr};
+        use ark_ff::PrimeField;
+        use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
+        use ark_serialize::CanonicalDeserialize;
+
+        let Some(vk_bytes) = env
+            .storage()
+            .instance()
+            .get::<_, Bytes>(&DataKey::VerificationKey)
+        else {
+            return false;
+        };
+        let vk_buffer = Self::to_buffer(&vk_bytes);
+        let Ok(vk) = VerifyingKey::<Bn254>::deserialize_compressed(&vk_buffer[..]) else {
+            return false;
+        };
+        let Ok(proof) = Proof::<Bn254>::deserialize_compressed(&Self::to_buffer(proof)[..]) else {
+            return false;
+        };
+
+        let domain = Self::get_domain_separator(env.clone());
+        let inputs = [commitment, nullifier, recipient, &domain]
+            .map(|input| Fr::from_be_bytes_mod_order(&input.to_array()));
+        Groth16::<Bn254>::verify_proof(&prepare_verifying_key(&vk), &proof, &inputs)
+            .unwrap_or(false)
+    }
+
+    /// Whether `vk` is a compressed arkworks `VerifyingKey<Bn254>`
+    #[cfg(feature = "groth16")]
+    fn is_valid_verification_key(vk: &Bytes) -> bool {
+        use ark_bn254::Bn254;
+        use ark_groth16::VerifyingKey;
+        use ark_serialize::CanonicalDeserialize;
+
+        VerifyingKey::<Bn254>::deserialize_compressed(&Self::to_buffer(vk)[..]).is_ok()
+    }
+
+    /// Without `groth16` the key is stored as given
+    #[cfg(not(feature = "groth16"))]
+    fn is_valid_verification_key(_vk: &Bytes) -> bool {
+        true
+    }
+
+    #[cfg(feature = "groth16")]
+    fn to_buffer(bytes: &Bytes) -> alloc::vec::Vec<u8> {
+        let mut buffer = alloc::vec![0u8; bytes.len() as usize];
+        bytes.copy_into_slice(&mut buffer);
+        buffer
+    }
+
+    /// Internal ZK proof verification
+    ///
+    /// Without the `groth16` feature only the proof's shape is checked.
+    #[cfg(not(feature = "groth16"))]
+    fn verify_zk_proof(
+        env: &Env,
+        proof: &Bytes,
+        commitment: &BytesN<32>,
+        nullifier: &BytesN<32>,
+        recipient: &BytesN<32>,
+    ) -> bool {
+        // For now, verify proof is not empty and has minimum length
+        if proof.len() < 32 {
+            return false;
+        }
+
+        // Verify all public inputs are non-zero
+        let zero_hash = BytesN::from_array(env, &[0u8; 32]);
+        if commitment == &zero_hash || nullifier == &zero_hash || recipient == &zero_hash {
+            return false;
+        }
+
+        true
+    }
+
+    /// Refund locked funds if timeout expires (emergency)
+    ///
+    /// Stays available after shutdown so locked funds can always be recovered.
+    pub fn refund(env: Env, commitment_hash: BytesN<32>) {
+        let commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .unwrap_or_else(|| panic_with_error!(&env, BridgeError::CommitmentNotFound));
+
+        // Only sender can refund
+        commitment.sender.require_auth();
+
+        let commitment = Self::check_refundable(&env, &commitment.sender, &commitment_hash)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+        Self::refund_commitment(&env, commitment);
+    }
+
+    /// Refund several of `sender`'s expired commitments at once
+    ///
+    /// Each commitment is checked exactly as in `refund`. With
+    /// `abort_on_error` the first one that can't be refunded rejects the
+    /// whole batch; otherwise it is skipped and the rest are refunded. One
+    /// result, the refunded amount, is returned per commitment.
+    pub fn refund_batch(
+        env: Env,
+        sender: Address,
+        commitment_hashes: Vec<BytesN<32>>,
+        abort_on_error: bool,
+    ) -> Vec<Result<i128, BridgeError>> {
+        sender.require_auth();
+
+        if commitment_hashes.is_empty() || commitment_hashes.len() > MAX_REFUND_BATCH {
+            panic!("Invalid refund batch size");
+        }
+
+        let mut results = Vec::new(&env);
+        for commitment_hash in commitment_hashes.iter() {
+            match Self::check_refundable(&env, &sender, &commitment_hash) {
+                Ok(commitment) => {
+                    results.push_back(Ok(commitment.amount));
+                    Self::refund_commitment(&env, commitment);
+                }
+                Err(error) if abort_on_error => panic_with_error!(&env, error),
+                Err(error) => results.push_back(Err(error)),
+            }
+        }
+        results
+    }
+
+    /// Load a commitment of `sender` whose refund timeout has passed
+    ///
+    /// The timeout in effect when the funds were locked applies, so a later
+    /// config change can't move the deadline.
+    fn check_refundable(
+        env: &Env,
+        sender: &Address,
+        commitment_hash: &BytesN<32>,
+    ) -> Result<BridgeCommitment, BridgeError> {
+        let commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .ok_or(BridgeError::CommitmentNotFound)?;
+        if commitment.sender != *sender {
+            return Err(BridgeError::Unauthorized);
+        }
+        if commitment.status != CommitmentStatus::Locked {
+            return Err(BridgeError::CommitmentAlreadyProcessed);
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&DataKey::PendingUnlock(commitment_hash.clone()))
+        {
+            return Err(BridgeError::UnlockPending);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if current_time < commitment.timestamp.saturating_add(commitment.timeout_period) {
+            return Err(BridgeError::TimeoutNotReached);
+        }
+
+        // Timestamps are validator-set; also require real ledger progress
+        if env.ledger().sequence()
+            < commitment
+                .ledger_sequence
+                .saturating_add(Self::get_min_ledgers_elapsed(env.clone()))
+        {
+            return Err(BridgeError::TimeoutNotReached);
+        }
+
+        Ok(commitment)
+    }
+
+    /// Refund a locked commitment immediately once the bridge is shut down;
+    /// no claim can happen any more, so there is nothing to wait for
+    pub fn shutdown_refund(env: Env, commitment_hash: BytesN<32>) {
+        if !Self::is_shut_down(env.clone()) {
+            panic!("Bridge not shut down");
+        }
+
+        let commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash))
+            .expect("Commitment not found");
+
+        commitment.sender.require_auth();
+
+        Self::refund_commitment(&env, commitment);
+    }
+
+    /// Admin function to refund a locked commitment to its sender right
+    /// away, e.g. one flagged as fraudulent or malformed by monitoring
+    ///
+    /// Skips the timeout; `reason` is recorded in the `force_refunded` event.
+    pub fn force_refund(env: Env, admin: Address, commitment_hash: BytesN<32>, reason: Symbol) {
+        Self::require_admin(&env, &admin);
+
+        let commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .expect("Commitment not found");
+        let amount = commitment.amount;
+
+        Self::refund_commitment(&env, commitment);
+
+        events::force_refunded(&env, admin, commitment_hash, amount, reason);
+    }
+
+    fn refund_commitment(env: &Env, commitment: BridgeCommitment) {
+        let commitment_hash = commitment.commitment_hash.clone();
+
+        if commitment.status != CommitmentStatus::Locked {
+            panic!("Commitment already processed");
+        }
+        Self::ensure_no_pending_unlock(env, &commitment_hash);
+
+        // Transfer tokens back to sender, in the asset they locked
+        let token_client = token::Client::new(env, &commitment.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &commitment.sender,
+            &commitment.amount,
+        );
+
+        // Update commitment status
+        let mut updated_commitment = commitment.clone();
+        Self::set_status(env, &mut updated_commitment, CommitmentStatus::Refunded);
+
+        // Update total locked
+        Self::adjust_total_locked(env, &commitment.token, -commitment.amount);
+        Self::adjust_user_locked(env, &commitment.sender, -commitment.amount);
+
+        // Emit refund event
+        events::refunded(env, commitment_hash, commitment.sender, commitment.amount);
+    }
+
+    /// Re-lock a refunded commitment under the same hash, bumping its claim nonce
+    pub fn relock(env: Env, commitment_hash: BytesN<32>) -> u32 {
+        let mut commitment: BridgeCommitment = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash.clone()))
+            .expect("Commitment not found");
+
+        commitment.sender.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::close_import(&env);
+
+        if commitment.status != CommitmentStatus::Refunded {
+            panic!("Commitment not refunded");
+        }
+
+        Self::count_ledger_lock(&env).unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        // The refund returned the full amount, so the relock takes it all back
+        let token_client = token::Client::new(&env, &commitment.token);
+        token_client.transfer(
+            &commitment.sender,
+            &env.current_contract_address(),
+            &commitment.amount,
+        );
+
+        let claim_nonce = Self::get_claim_nonce(env.clone(), commitment_hash.clone()) + 1;
+        env.storage().persistent().set(
+            &DataKey::CommitmentReuseCount(commitment_hash.clone()),
+            &claim_nonce,
+        );
+
+        commitment.timestamp = env.ledger().timestamp();
+        commitment.ledger_sequence = env.ledger().sequence();
+        Self::set_status(&env, &mut commitment, CommitmentStatus::Locked);
+        env.storage().persistent().extend_ttl(
+            &DataKey::Commitment(commitment_hash.clone()),
+            COMMITMENT_TTL_LEDGERS,
+            COMMITMENT_TTL_LEDGERS,
+        );
+
+        Self::adjust_total_locked(&env, &commitment.token, commitment.amount);
+        Self::adjust_user_locked(&env, &commitment.sender, commitment.amount);
+
+        events::funds_relocked(
+            &env,
+            commitment_hash,
+            commitment.sender,
+            commitment.amount,
+            claim_nonce,
+        );
+
+        claim_nonce
+    }
+
+    /// Get the claim nonce of a commitment (number of times it was relocked)
+    pub fn get_claim_nonce(env: Env, commitment_hash: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CommitmentReuseCount(commitment_hash))
+            .unwrap_or(0)
+    }
+
+    /// Lock `give_amount` of `give_token` as one leg of an atomic swap.
+    /// Returns the swap id, which is scoped to the sender so a swap can never
+    /// occupy another user's lock commitment
+    #[allow(clippy::too_many_arguments)]
+    pub fn lock_swap(
+        env: Env,
+        sender: Address,
+        give_token: Address,
+        give_amount: i128,
+        want_token: Address,
+        want_amount: i128,
+        commitment_hash: BytesN<32>,
+        counterparty_hash: BytesN<32>,
+    ) -> BytesN<32> {
+        sender.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::ensure_not_paused(&env);
+        Self::close_import(&env);
+
+        if give_amount <= 0 || want_amount <= 0 {
+            panic_with_error!(&env, BridgeError::ZeroAmount);
+        }
+
+        let swap_id = Self::swap_id(&env, &sender, &commitment_hash);
+        if env.storage().persistent().has(&DataKey::Swap(swap_id.clone())) {
+            panic_with_error!(&env, BridgeError::CommitmentCollision);
+        }
+
+        token::Client::new(&env, &give_token).transfer(
+            &sender,
+            &env.current_contract_address(),
+            &give_amount,
+        );
+        Self::adjust_swap_escrow(&env, &give_token, give_amount);
+
+        let offer = SwapOffer {
+            commitment_hash: commitment_hash.clone(),
+            sender: sender.clone(),
+            give_token,
+            give_amount,
+            want_token,
+            want_amount,
+            counterparty_hash,
+            counterparty: None,
+            timestamp: env.ledger().timestamp(),
+            status: SwapStatus::Open,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Swap(swap_id.clone()), &offer);
+
+        events::swap_locked(&env, commitment_hash, sender, give_amount, want_amount);
+
+        swap_id
+    }
+
+    /// Match an open swap: the bound counterparty escrows the wanted leg
+    pub fn match_swap(env: Env, counterparty: Address, swap_id: BytesN<32>) {
+        counterparty.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::ensure_not_paused(&env);
+
+        let mut offer = Self::get_swap(env.clone(), swap_id.clone()).expect("Swap not found");
+        if offer.status != SwapStatus::Open {
+            panic!("Swap already processed");
+        }
+        if Self::hash_address(&env, &counterparty) != offer.counterparty_hash {
+            panic!("Not the swap counterparty");
+        }
+
+        token::Client::new(&env, &offer.want_token).transfer(
+            &counterparty,
+            &env.current_contract_address(),
+            &offer.want_amount,
+        );
+        Self::adjust_swap_escrow(&env, &offer.want_token, offer.want_amount);
+
+        offer.counterparty = Some(counterparty.clone());
+        offer.status = SwapStatus::Matched;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Swap(swap_id), &offer);
+
+        events::swap_matched(&env, offer.commitment_hash, counterparty);
+    }
+
+    /// Complete a matched swap with a valid proof, releasing each escrowed
+    /// leg to the other party
+    ///
+    /// Swap nullifiers are spent under their own `SwapNullifier` keys, so a
+    /// swap can never burn or reuse the nullifier of a lock claim.
+    pub fn complete_swap(
+        env: Env,
+        counterparty: Address,
+        swap_id: BytesN<32>,
+        proof: Bytes,
+        nullifier_hash: BytesN<32>,
+    ) {
+        counterparty.require_auth();
+        Self::ensure_not_shut_down(&env);
+        Self::ensure_not_paused(&env);
+        Self::close_import(&env);
+
+        let mut offer = Self::get_swap(env.clone(), swap_id.clone()).expect("Swap not found");
+        let commitment_hash = offer.commitment_hash.clone();
+
+        if offer.status != SwapStatus::Matched {
+            panic!("Swap not matched");
+        }
+        if offer.counterparty.as_ref() != Some(&counterparty) {
+            panic!("Not the swap counterparty");
+        }
+
+        let nullifier_key = DataKey::SwapNullifier(nullifier_hash.clone());
+        if env.storage().persistent().has(&nullifier_key) {
+            panic!("Nullifier already used - double spend attempt");
+        }
+
+        if !Self::verify_zk_proof(
+            &env,
+            &proof,
+            &commitment_hash,
+            &nullifier_hash,
+            &offer.counterparty_hash,
+        ) {
+            panic!("Invalid ZK proof");
+        }
+
+        env.storage().persistent().set(&nullifier_key, &true);
+        offer.status = SwapStatus::Completed;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Swap(swap_id), &offer);
+        Self::adjust_swap_escrow(&env, &offer.give_token, -offer.give_amount);
+        Self::adjust_swap_escrow(&env, &offer.want_token, -offer.want_amount);
+
+        token::Client::new(&env, &offer.want_token).transfer(
+            &env.current_contract_address(),
+            &offer.sender,
+            &offer.want_amount,
+        );
+        token::Client::new(&env, &offer.give_token).transfer(
+            &env.current_contract_address(),
+            &counterparty,
+            &offer.give_amount,
+        );
+
+        events::swap_completed(&env, commitment_hash, offer.sender, counterparty);
+    }
+
+    /// Refund an uncompleted swap after the swap timeout, returning every
+    /// escrowed leg to the party that put it up
+    ///
+    /// The legs can only go back to their owners, so anyone may call it and
+    /// a matched counterparty is not left waiting on the swap creator.
+    pub fn refund_swap(env: Env, swap_id: BytesN<32>) {
+        let mut offer = Self::get_swap(env.clone(), swap_id.clone()).expect("Swap not found");
+        let commitment_hash = offer.commitment_hash.clone();
+
+        if offer.status != SwapStatus::Open && offer.status != SwapStatus::Matched {
+            panic!("Swap already processed");
+        }
+
+        if env.ledger().timestamp() < offer.timestamp + SWAP_TIMEOUT {
+            panic!("Timeout period not reached");
+        }
+
+        offer.status = SwapStatus::Refunded;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Swap(swap_id), &offer);
+        Self::adjust_swap_escrow(&env, &offer.give_token, -offer.give_amount);
+
+        token::Client::new(&env, &offer.give_token).transfer(
+            &env.current_contract_address(),
+            &offer.sender,
+            &offer.give_amount,
+        );
+        if let Some(counterparty) = offer.counterparty.as_ref() {
+            Self::adjust_swap_escrow(&env, &offer.want_token, -offer.want_amount);
+            token::Client::new(&env, &offer.want_token).transfer(
+                &env.current_contract_address(),
+                counterparty,
+                &offer.want_amount,
+            );
+        }
+
+        events::swap_refunded(&env, commitment_hash, offer.sender, offer.give_amount);
+    }
+
+    /// Check if a swap nullifier is spent
+    pub fn is_swap_nullifier_used(env: Env, nullifier_hash: BytesN<32>) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SwapNullifier(nullifier_hash))
+            .unwrap_or(false)
+    }
+
+    /// Get swap details
+    pub fn get_swap(env: Env, swap_id: BytesN<32>) -> Option<SwapOffer> {
+        env.storage().persistent().get(&DataKey::Swap(swap_id))
+    }
+
+    /// Storage id of a swap: sha256(sender XDR || commitment_hash)
+    fn swap_id(env: &Env, sender: &Address, commitment_hash: &BytesN<32>) -> BytesN<32> {
+        let mut preimage = sender.clone().to_xdr(env);
+        preimage.append(&Bytes::from_array(env, &commitment_hash.to_array()));
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Hash an address for binding it into a commitment: sha256(address XDR)
+    fn hash_address(env: &Env, address: &Address) -> BytesN<32> {
+        env.crypto().sha256(&address.clone().to_xdr(env)).into()
+    }
+
+    /// SHA-256 of this contract's address and the network id, so a proof
+    /// generated for one deployment does not verify on another
+    fn compute_domain_separator(env: &Env) -> BytesN<32> {
+        let mut preimage = env.current_contract_address().to_xdr(env);
+        preimage.extend_from_array(&env.ledger().network_id().to_array());
+        env.crypto().sha256(&preimage).into()
+    }
+
+    /// Poseidon commitment to `(sender_hash, amount, nonce)`, the same
+    /// digest the Polkadot contract and the bridge circuit compute
+    fn poseidon_commitment(
+        env: &Env,
+        sender_hash: &BytesN<32>,
+        amount: u128,
+        nonce: u64,
+    ) -> BytesN<32> {
+        let digest = bridge_poseidon::commitment(&sender_hash.to_array(), amount, nonce);
+        BytesN::from_array(env, &digest)
+    }
+
+    /// Poseidon commitment a client proves knowledge of for a lock of
+    /// `amount` with `nonce` from `sender`, who enters as sha256(address XDR)
+    pub fn compute_poseidon_commitment(
+        env: Env,
+        sender: Address,
+        amount: i128,
+        nonce: u64,
+    ) -> BytesN<32> {
+        if amount <= 0 {
+            panic_with_error!(&env, BridgeError::ZeroAmount);
+        }
+        let sender_hash = Self::hash_address(&env, &sender);
+        Self::poseidon_commitment(&env, &sender_hash, amount as u128, nonce)
+    }
+
+    /// Get commitment details
+    pub fn get_commitment(env: Env, commitment_hash: BytesN<32>) -> BridgeCommitment {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Commitment(commitment_hash))
+            .expect("Commitment not found")
+    }
+
+    /// Get the root of the commitment Merkle tree
+    ///
+    /// Every lock appends its commitment hash as the next leaf. Empty leaves
+    /// are zero, so before the first lock this is the empty tree's root.
+    pub fn get_merkle_root(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::MerkleRoot)
+            .unwrap_or_else(|| BytesN::from_array(&env, &MERKLE_ZEROS[MERKLE_TREE_DEPTH as usize]))
+    }
+
+    /// Get the leaf index of a commitment in the Merkle tree
+    pub fn get_leaf_index(env: Env, commitment_hash: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::LeafIndex(commitment_hash))
+            .expect("Commitment not in Merkle tree")
+    }
+
+    /// Get a commitment's status without loading the full record
+    pub fn get_commitment_status(env: Env, commitment_hash: BytesN<32>) -> Option<CommitmentStatus> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CommitmentStatus(commitment_hash))
+    }
+
+    /// List commitments currently in `status`, paginated (limit capped at 50)
+    ///
+    /// Removing a commitment from a status moves that status's last entry
+    /// into the freed position, so order is not stable across status changes.
+    pub fn get_commitments_by_status(
+        env: Env,
+        status: CommitmentStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(BytesN<32>, BridgeCommitment)> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_LIMIT))
+            .min(Self::status_count(&env, status));
+        let mut page = Vec::new(&env);
+        for i in start..end {
+            let commitment_hash: BytesN<32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::StatusEntry(status, i))
+                .unwrap();
+            let commitment: BridgeCommitment = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Commitment(commitment_hash.clone()))
+                .unwrap();
+            page.push_back((commitment_hash, commitment));
+        }
+        page
+    }
+
+    /// Check if a nullifier is used for `commitment_hash` in its current
+    /// claim cycle, the scope claims check it in
+    pub fn is_nullifier_used(
+        env: Env,
+        commitment_hash: BytesN<32>,
+        nullifier_hash: BytesN<32>,
+    ) -> bool {
+        env.storage()
+            .persistent()
+            .get(&Self::nullifier_key(&env, &commitment_hash, &nullifier_hash))
+            .unwrap_or(false)
+    }
+
+    /// Get the commitment created by a (sender, nonce) pair, if any
+    pub fn get_nonce_commitment(env: Env, sender: Address, nonce: u64) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SenderNonce(sender, nonce))
+    }
+
+    /// Get the relayer fees accrued in the fee vault
+    pub fn get_fee_vault(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::FeeVault)
+            .unwrap_or(0)
+    }
+
+    /// Check if an address may claim from the fee vault
+    pub fn is_relayer(env: Env, relayer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Relayer(relayer))
+            .unwrap_or(false)
+    }
+
+    /// Check the contract holds enough tokens to cover every liability:
+    /// outstanding locked commitments plus the accrued fee vault
+    pub fn solvency_check(env: Env) -> bool {
+        let token_contract = Self::primary_token(&env);
+        let token_client = token::Client::new(&env, &token_contract);
+        let balance = token_client.balance(&env.current_contract_address());
+
+        let total_locked: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0);
+        let fee_vault: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeVault)
+            .unwrap_or(0);
+
+        balance >= total_locked + fee_vault
+    }
+
+    /// Get the remaining prepaid rent budget (None while rent tracking is off)
+    pub fn get_rent_budget(env: Env) -> Option<i128> {
+        env.storage().instance().get(&DataKey::RentBudget)
+    }
+
+    /// Get the rent charged against the budget for a commitment entry from `sender`
+    pub fn estimate_commitment_rent(env: Env, sender: Address) -> i128 {
+        let sample = BridgeCommitment {
+            commitment_hash: BytesN::from_array(&env, &[0u8; 32]),
+            sender,
+            token: env.current_contract_address(),
+            amount: 0,
+            timestamp: 0,
+            ledger_sequence: 0,
+            timeout_period: 0,
+            destination_chain: 0,
+            status: CommitmentStatus::Locked,
+        };
+        Self::estimate_rent(sample.to_xdr(&env).len())
+    }
+
+    /// Whether the bridge has been permanently shut down
+    pub fn is_shut_down(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Shutdown)
+            .unwrap_or(false)
+    }
+
+    /// Whether locks and claims are paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Get the USD-denominated lock limits, if configured
+    pub fn get_usd_limits(env: Env) -> Option<UsdLimits> {
+        env.storage().instance().get(&DataKey::UsdLimits)
+    }
+
+    /// Get the locked amount of a commitment, the most a proof can claim
+    pub fn get_commitment_amount(env: Env, commitment_hash: BytesN<32>) -> Option<i128> {
+        env.storage()
+            .persistent()
+            .get::<_, BridgeCommitment>(&DataKey::Commitment(commitment_hash))
+            .map(|commitment| commitment.amount)
+    }
+
+    /// Get the bridge's own contract address
+    pub fn contract_address(env: Env) -> Address {
+        env.current_contract_address()
+    }
+
+    /// Get total locked amount
+    pub fn get_total_locked(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0)
+    }
+
+    /// Get the maximum lock amount; 0 means no maximum
+    pub fn get_max_lock_amount(env: Env) -> i128 {
+        Self::max_lock_amount(&env)
+    }
+
+    /// Get the refund timeout applied to new commitments, in seconds
+    pub fn get_timeout_period(env: Env) -> u64 {
+        Self::timeout_period(&env)
+    }
+
+    /// Amount `user` currently has in Locked commitments
+    pub fn get_user_locked(env: Env, user: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UserLocked(user))
+            .unwrap_or(0)
+    }
+
+    /// Whether `lock_funds` accepts `chain` as a destination
+    pub fn is_chain_allowed(env: Env, chain: u32) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AllowedChain(chain))
+            .unwrap_or(false)
+    }
+
+    /// Admin function to allow locks towards a destination chain
+    pub fn add_allowed_chain(env: Env, admin: Address, chain: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::AllowedChain(chain), &true);
+
+        events::allowed_chain_updated(&env, chain, true);
+    }
+
+    /// Admin function to stop new locks towards a destination chain.
+    /// Outstanding commitments to it can still be claimed or refunded.
+    pub fn remove_allowed_chain(env: Env, admin: Address, chain: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::AllowedChain(chain));
+
+        events::allowed_chain_updated(&env, chain, false);
+    }
+
+    /// Amount currently locked in `token`
+    pub fn get_total_locked_by_token(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TotalLockedByToken(token))
+            .unwrap_or(0)
+    }
+
+    /// Whether `lock_funds` accepts `token`; the primary token always is
+    pub fn is_supported_token(env: Env, token: Address) -> bool {
+        token == Self::primary_token(&env)
+            || env
+                .storage()
+                .persistent()
+                .get(&DataKey::SupportedToken(token))
+                .unwrap_or(false)
+    }
+
+    /// Admin function to allow or stop locks of a token besides the primary one.
+    /// Outstanding commitments in a delisted token can still be refunded and claimed.
+    pub fn set_supported_token(env: Env, admin: Address, token: Address, supported: bool) {
+        Self::require_admin(&env, &admin);
+
+        if supported {
+            env.storage()
+                .persistent()
+                .set(&DataKey::SupportedToken(token), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::SupportedToken(token));
+        }
+    }
+
+    /// Admin function to update configuration
+    ///
+    /// A new refund timeout only applies to commitments locked afterwards;
+    /// outstanding ones keep the deadline they were locked with.
+    pub fn update_config(
+        env: Env,
+        admin: Address,
+        min_lock_amount: Option<i128>,
+        max_lock_amount: Option<i128>,
+        relayer_fee: Option<i128>,
+        timeout_period: Option<u64>,
+    ) {
+        // Verify admin
+        Self::require_admin(&env, &admin);
+        Self::check_lock_bounds(
+            min_lock_amount.unwrap_or_else(|| Self::min_lock_amount(&env)),
+            max_lock_amount.unwrap_or_else(|| Self::max_lock_amount(&env)),
+        );
+
+        if let Some(min_amount) = min_lock_amount {
+            env.storage().instance().set(&DataKey::MinLockAmount, &min_amount);
+        }
+
+        if let Some(max_amount) = max_lock_amount {
+            env.storage().instance().set(&DataKey::MaxLockAmount, &max_amount);
+        }
+
+        if let Some(fee) = relayer_fee {
+            env.storage().instance().set(&DataKey::RelayerFee, &fee);
+        }
+
+        if let Some(timeout) = timeout_period {
+            let old = Self::timeout_period(&env);
+            env.storage().instance().set(&DataKey::TimeoutPeriod, &timeout);
+
+            let active_locked = Self::status_count(&env, CommitmentStatus::Locked);
+            events::refund_timeout_changed(&env, old, timeout, active_locked);
+        }
+
+        events::config_updated(
+            &env,
+            admin,
+            Self::min_lock_amount(&env),
+            Self::max_lock_amount(&env),
+            Self::relayer_fee(&env),
+            Self::timeout_period(&env),
+        );
+    }
+
+    /// Admin function to allow or revoke a relayer's fee vault claims
+    pub fn set_relayer(env: Env, admin: Address, relayer: Address, enabled: bool) {
+        Self::require_admin(&env, &admin);
+
+        if enabled {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Relayer(relayer.clone()), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::Relayer(relayer.clone()));
+        }
+
+        events::relayer_updated(&env, relayer, enabled);
+    }
+
+    /// Admin function to set the Groth16 verification key: a compressed
+    /// arkworks `VerifyingKey<Bn254>` for the four public inputs
+    /// `verify_zk_proof` checks
+    ///
+    /// With `groth16`, a key that does not deserialize is rejected up front
+    /// rather than failing every later verification.
+    pub fn set_verification_key(env: Env, admin: Address, vk: Bytes) {
+        Self::require_admin(&env, &admin);
+        if vk.is_empty() || !Self::is_valid_verification_key(&vk) {
+            panic_with_error!(&env, BridgeError::InvalidVerificationKey);
+        }
+
+        env.storage().instance().set(&DataKey::VerificationKey, &vk);
+
+        events::verification_key_set(&env, admin, env.crypto().sha256(&vk).into());
+    }
+
+    /// Get the Groth16 verification key, if set
+    pub fn get_verification_key(env: Env) -> Option<Bytes> {
+        env.storage().instance().get(&DataKey::VerificationKey)
+    }
+
+    /// Get the domain separator proofs for this deployment must commit to
+    ///
+    /// Deployments initialized before the separator was stored derive it on
+    /// read; it only depends on the contract address and the network.
+    pub fn get_domain_separator(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::DomainSeparator)
+            .unwrap_or_else(|| Self::compute_domain_separator(&env))
+    }
+
+    /// Admin function to set the destination address type `lock_funds_to`
+    /// expects for a chain
+    pub fn set_chain_address_type(env: Env, admin: Address, chain: u32, address_type: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ChainAddressType(chain), &address_type);
+    }
+
+    /// Admin function to cap new commitments per ledger (0 = unlimited)
+    pub fn set_max_locks_per_ledger(env: Env, admin: Address, max_locks: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxLocksPerLedger, &max_locks);
+    }
+
+    /// Get the per-ledger commitment cap (0 = unlimited)
+    pub fn get_max_locks_per_ledger(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxLocksPerLedger)
+            .unwrap_or(0)
+    }
+
+    /// Admin function to carry commitments over from a prior bridge version
+    ///
+    /// Only allowed while imports are open: from `initialize` until
+    /// `finalize_import` or the first native lock, claim or swap. The backing
+    /// tokens must be moved to this contract separately.
+    pub fn import_commitments(
+        env: Env,
+        admin: Address,
+        commitments: Vec<(BytesN<32>, BridgeCommitment)>,
+    ) {
+        Self::require_admin(&env, &admin);
+
+        if !Self::is_import_open(env.clone()) {
+            panic!("Import closed");
+        }
+
+        for (commitment_hash, commitment) in commitments.iter() {
+            if commitment.commitment_hash != commitment_hash {
+                panic!("Commitment hash mismatch");
+            }
+            let key = DataKey::Commitment(commitment_hash.clone());
+            if env.storage().persistent().has(&key) {
+                panic_with_error!(&env, BridgeError::CommitmentCollision);
+            }
+
+            env.storage().persistent().set(&key, &commitment);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, COMMITMENT_TTL_LEDGERS, COMMITMENT_TTL_LEDGERS);
+            Self::add_to_status_index(&env, commitment.status, &commitment_hash);
+
+            if commitment.status == CommitmentStatus::Locked {
+                Self::adjust_total_locked(&env, &commitment.token, commitment.amount);
+                Self::adjust_user_locked(&env, &commitment.sender, commitment.amount);
+            }
+        }
+    }
+
+    /// Admin function to close the import window for good
+    pub fn finalize_import(env: Env, admin: Address) {
+        Self::require_admin(&env, &admin);
+
+        Self::close_import(&env);
+    }
+
+    /// Whether commitments can still be imported
+    pub fn is_import_open(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::ImportOpen)
+            .unwrap_or(false)
+    }
+
+    /// Admin function to require ledger progress, on top of the timeout, before refunds
+    pub fn set_min_ledgers_elapsed(env: Env, admin: Address, min_ledgers: u32) {
+        Self::require_admin(&env, &admin);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MinLedgersElapsed, &min_ledgers);
+    }
+
+    /// Get the ledgers that must pass since a lock before it can be refunded
+    pub fn get_min_ledgers_elapsed(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinLedgersElapsed)
+            .unwrap_or(0)
+    }
+
+    /// Admin function to prepay rent for future commitment entries
+    pub fn top_up_rent_budget(env: Env, admin: Address, amount: i128) {
+        Self::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let budget: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RentBudget)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::RentBudget, &(budget + amount));
+
+        events::rent_topped_up(&env, admin, budget + amount);
+    }
+
+    /// Claim accrued relayer fees from the fee vault
+    pub fn claim_fee_vault(env: Env, relayer: Address, amount: i128) {
+        relayer.require_auth();
+
+        if !Self::is_relayer(env.clone(), relayer.clone()) {
+            panic!("Not a relayer");
+        }
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        let fee_vault: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeVault)
+            .unwrap_or(0);
+        if amount > fee_vault {
+            panic!("Insufficient fee vault balance");
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeVault, &(fee_vault - amount));
+
+        let token_contract = Self::primary_token(&env);
+        let token_client = token::Client::new(&env, &token_contract);
+        token_client.transfer(&env.current_contract_address(), &relayer, &amount);
+
+        events::fee_vault_claimed(&env, relayer, amount);
+    }
+
+    /// Admin: express lock limits in USD cents using a price oracle
+    pub fn set_usd_limits(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        max_price_age: u64,
+        min_cents: i128,
+        max_cents: i128,
+    ) {
+        Self::require_admin(&env, &admin);
+
+        if min_cents < 0 || max_cents < 0 || (max_cents > 0 && max_cents < min_cents) {
+            panic!("Invalid limits");
+        }
+
+        env.storage().instance().set(
+            &DataKey::UsdLimits,
+            &UsdLimits {
+                oracle,
+                max_price_age,
+                min_cents,
+                max_cents,
+            },
+        );
+    }
+
+    /// Admin: go back to raw token-unit limits only
+    pub fn clear_usd_limits(env: Env, admin: Address) {
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().remove(&DataKey::UsdLimits);
+    }
+
+    /// Admin: permanently shut the bridge down. Locks, claims and swaps stop;
+    /// refunds stay available, with `shutdown_refund` skipping the timeout.
+    pub fn shutdown(env: Env, admin: Address) {
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().set(&DataKey::Shutdown, &true);
+
+        events::shutdown(&env, admin);
+    }
+
+    /// Admin: pause or resume locks and claims. Unlike `shutdown` this is
+    /// reversible, and refunds stay available throughout.
+    pub fn set_paused(env: Env, admin: Address, paused: bool) {
+        Self::require_admin(&env, &admin);
+
+        env.storage().instance().set(&DataKey::Paused, &paused);
+
+        events::paused_changed(&env, admin, paused);
+    }
+
+    /// Admin: nominate `new_admin`, who takes over once they `accept_admin`.
+    /// A later proposal replaces an earlier one.
+    pub fn propose_admin(env: Env, current_admin: Address, new_admin: Address) {
+        Self::require_admin(&env, &current_admin);
+
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+
+        events::admin_transfer_started(&env, current_admin, new_admin);
+    }
+
+    /// Complete an admin transfer; only the proposed admin can accept
+    pub fn accept_admin(env: Env, new_admin: Address) {
+        let pending: Option<Address> = env.storage().instance().get(&DataKey::PendingAdmin);
+        if pending.as_ref() != Some(&new_admin) {
+            panic_with_error!(&env, BridgeError::Unauthorized);
+        }
+        new_admin.require_auth();
+
+        let previous_admin =
+            Self::admin(&env).unwrap_or_else(|error| panic_with_error!(&env, error));
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        events::admin_transferred(&env, previous_admin, new_admin);
+    }
+
+    /// Get the admin nominated by `propose_admin`, if any
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    /// Token balance not backing a locked commitment, an open swap, the
+    /// fee vault or a challenge bond
+    pub fn get_free_balance(env: Env) -> i128 {
+        let token_contract = Self::primary_token(&env);
+        let token_client = token::Client::new(&env, &token_contract);
+        let balance = token_client.balance(&env.current_contract_address());
+
+        let total_locked: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalLocked)
+            .unwrap_or(0);
+        let fee_vault: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::FeeVault)
+            .unwrap_or(0);
+        let swap_escrow: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SwapEscrow(token_contract))
+            .unwrap_or(0);
+        let bond_escrow: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BondEscrow)
+            .unwrap_or(0);
+
+        (balance - total_locked - fee_vault - swap_escrow - bond_escrow).max(0)
+    }
+
+    /// Admin: sweep surplus tokens, never touching funds owed to refunds,
+    /// swaps or relayers
+    pub fn sweep(env: Env, admin: Address, to: Address, amount: i128) {
+        Self::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+
+        if amount > Self::get_free_balance(env.clone()) {
+            panic_with_error!(&env, BridgeError::WouldUndercollateralize);
+        }
+
+        let token_contract = Self::primary_token(&env);
+        let token_client = token::Client::new(&env, &token_contract);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        events::swept(&env, to, amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events, Ledger};
+    use soroban_sdk::{Symbol, TryFromVal};
+
+    #[contract]
+    pub struct MockOracle;
+
+    #[contractimpl]
+    impl MockOracle {
+        pub fn set_price(env: Env, price: i128, published_at: u64) {
+            env.storage().instance().set(&0u32, &(price, published_at));
+        }
+
+        pub fn price(env: Env) -> (i128, u64) {
+            env.storage().instance().get(&0u32).unwrap()
+        }
+    }
+
+    // Tests that claim with a placeholder proof rely on the shape-only
+    // verifier and are compiled out under `groth16`, where the Groth16 tests
+    // below cover verification instead
+
+    fn setup<'a>(
+        env: &Env,
+    ) -> (
+        StellarBridgeCompleteClient<'a>,
+        Address,
+        token::Client<'a>,
+        token::StellarAssetClient<'a>,
+    ) {
+        env.mock_all_auths();
+
+        let admin = Address::generate(env);
+        let token_id = env.register_stellar_asset_contract(admin.clone());
+        let token = token::Client::new(env, &token_id);
+        let token_admin = token::StellarAssetClient::new(env, &token_id);
+
+        let contract_id = env.register_contract(None, StellarBridgeComplete);
+        let client = StellarBridgeCompleteClient::new(env, &contract_id);
+        client.initialize(&admin, &token_id, &1_000i128, &0i128, &10i128, &None);
+
+        (client, admin, token, token_admin)
+    }
+
+    #[test]
+    fn test_lock_funds_is_deterministic_per_nonce() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        assert_eq!(client.get_nonce_commitment(&sender, &1u64), Some(commitment.clone()));
+        assert_eq!(client.get_commitment(&commitment).amount, 2_000);
+
+        // A retry with the same (sender, nonce) is rejected and moves no funds
+        assert_eq!(
+            client.try_lock_funds(&sender, &2_000, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::DuplicateNonce.into()))
+        );
+        assert_eq!(token.balance(&sender), 8_000);
+        assert_eq!(client.get_total_locked(), 2_000);
+
+        // A fresh nonce locks normally
+        let second = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        assert_ne!(commitment, second);
+        assert_eq!(client.get_total_locked(), 4_000);
+    }
+
+    #[test]
+    fn test_same_nonce_different_senders() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token_admin.mint(&alice, &10_000);
+        token_admin.mint(&bob, &10_000);
+
+        let a = client.lock_funds(&alice, &2_000, &7u64, &1u32, &None);
+        let b = client.lock_funds(&bob, &2_000, &7u64, &1u32, &None);
+        assert_ne!(a, b);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_fee_vault_accrues_and_claims() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let relayer = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+
+        // Nothing accrues at lock time
+        let first = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let second = client.lock_funds(&sender, &3_000, &2u64, &1u32, &None);
+        assert_eq!(client.get_fee_vault(), 0);
+
+        // Claims without a calling relayer leave the fee in the vault
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        client.verify_and_claim(&proof, &first, &BytesN::from_array(&env, &[2u8; 32]), &recipient);
+        client.verify_and_claim(&proof, &second, &BytesN::from_array(&env, &[4u8; 32]), &recipient);
+        assert_eq!(token.balance(&recipient), 5_000 - 20);
+        assert_eq!(client.get_fee_vault(), 20);
+        assert_eq!(client.get_total_locked(), 0);
+        assert!(client.solvency_check());
+
+        // Only registered relayers can claim
+        assert!(client.try_claim_fee_vault(&relayer, &15).is_err());
+        client.set_relayer(&admin, &relayer, &true);
+
+        client.claim_fee_vault(&relayer, &15);
+        assert_eq!(token.balance(&relayer), 15);
+        assert_eq!(client.get_fee_vault(), 5);
+        assert!(client.solvency_check());
+
+        // Cannot claim more than has accrued
+        assert!(client.try_claim_fee_vault(&relayer, &6).is_err());
+    }
+
+    #[test]
+    fn test_rent_budget_limits_locks() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+
+        let rent = client.estimate_commitment_rent(&sender);
+        assert!(rent > 0);
+        assert_eq!(client.get_rent_budget(), None);
+
+        // Budget below the cost of one entry rejects the lock
+        client.top_up_rent_budget(&admin, &(rent - 1));
+        assert_eq!(
+            client.try_lock_funds(&sender, &2_000, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::RentBudgetExhausted.into()))
+        );
+        assert_eq!(client.get_total_locked(), 0);
+
+        // After a top-up the lock goes through and the budget is charged
+        client.top_up_rent_budget(&admin, &rent);
+        client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        assert_eq!(client.get_rent_budget(), Some(rent - 1));
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_commitments_by_status_follow_transitions() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+
+        let first = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let second = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        let third = client.lock_funds(&sender, &2_000, &3u64, &1u32, &None);
+        assert_eq!(
+            client
+                .get_commitments_by_status(&CommitmentStatus::Locked, &0, &10)
+                .len(),
+            3
+        );
+
+        // Claim the first
+        client.verify_and_unlock(
+            &relayer,
+            &Bytes::from_array(&env, &[1u8; 64]),
+            &first,
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &BytesN::from_array(&env, &[3u8; 32]),
+        );
+
+        // Refund the second after the timeout
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&second);
+
+        let locked = client.get_commitments_by_status(&CommitmentStatus::Locked, &0, &10);
+        let claimed = client.get_commitments_by_status(&CommitmentStatus::Claimed, &0, &10);
+        let refunded = client.get_commitments_by_status(&CommitmentStatus::Refunded, &0, &10);
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked.get_unchecked(0).0, third);
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed.get_unchecked(0).0, first);
+        assert_eq!(claimed.get_unchecked(0).1.status, CommitmentStatus::Claimed);
+        assert_eq!(refunded.len(), 1);
+        assert_eq!(refunded.get_unchecked(0).0, second);
+
+        // Pagination past the end is empty
+        assert_eq!(
+            client
+                .get_commitments_by_status(&CommitmentStatus::Locked, &1, &10)
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_ledger_lock_limit_resets_each_ledger() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &20_000);
+        client.set_max_locks_per_ledger(&admin, &2);
+
+        client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        assert_eq!(
+            client.try_lock_funds(&sender, &2_000, &3u64, &1u32, &None),
+            Err(Ok(BridgeError::LedgerLockLimitReached.into()))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        client.lock_funds(&sender, &2_000, &3u64, &1u32, &None);
+        assert_eq!(client.get_total_locked(), 3 * 2_000);
+    }
+
+    #[test]
+    fn test_zero_amount_rejected_with_zero_minimum() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        client.update_config(&admin, &Some(0), &None, &Some(0), &None);
+
+        assert_eq!(
+            client.try_lock_funds(&sender, &0, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::ZeroAmount.into()))
+        );
+        assert_eq!(
+            client.try_lock_funds(&sender, &-5, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::ZeroAmount.into()))
+        );
+        assert_eq!(token.balance(&sender), 10_000);
+        assert_eq!(client.get_nonce_commitment(&sender, &1u64), None);
+
+        client.lock_funds(&sender, &1, &1u64, &1u32, &None);
+        assert_eq!(client.get_total_locked(), 1);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_swap_completes_with_counterparty() {
+        let env = Env::default();
+        let (client, admin, token_a, token_a_admin) = setup(&env);
+        let token_b_id = env.register_stellar_asset_contract(admin.clone());
+        let token_b = token::Client::new(&env, &token_b_id);
+        let token_b_admin = token::StellarAssetClient::new(&env, &token_b_id);
+
+        let maker = Address::generate(&env);
+        let taker = Address::generate(&env);
+        token_a_admin.mint(&maker, &1_000);
+        token_b_admin.mint(&taker, &500);
+
+        let commitment = BytesN::from_array(&env, &[5u8; 32]);
+        let taker_hash = StellarBridgeComplete::hash_address(&env, &taker);
+        let swap_id = client.lock_swap(
+            &maker,
+            &token_a.address,
+            &1_000,
+            &token_b_id,
+            &500,
+            &commitment,
+            &taker_hash,
+        );
+        assert_eq!(token_a.balance(&maker), 0);
+
+        // Someone other than the bound counterparty cannot take the swap
+        let stranger = Address::generate(&env);
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[6u8; 32]);
+        assert!(client.try_match_swap(&stranger, &swap_id).is_err());
+
+        // Nothing completes until the counterparty's leg is escrowed too
+        assert!(client
+            .try_complete_swap(&taker, &swap_id, &proof, &nullifier)
+            .is_err());
+        client.match_swap(&taker, &swap_id);
+        assert_eq!(token_b.balance(&taker), 0);
+        assert_eq!(token_b.balance(&client.address), 500);
+        assert_eq!(client.get_swap(&swap_id).unwrap().status, SwapStatus::Matched);
+
+        // Swaps pause with the rest of the bridge
+        client.set_paused(&admin, &true);
+        assert_eq!(
+            client.try_complete_swap(&taker, &swap_id, &proof, &nullifier),
+            Err(Ok(BridgeError::ContractPaused.into()))
+        );
+        client.set_paused(&admin, &false);
+
+        client.complete_swap(&taker, &swap_id, &proof, &nullifier);
+        assert_eq!(token_a.balance(&taker), 1_000);
+        assert_eq!(token_b.balance(&maker), 500);
+        assert_eq!(client.get_swap(&swap_id).unwrap().status, SwapStatus::Completed);
+
+        // Swap nullifiers live apart from lock-claim nullifiers
+        assert!(client.is_swap_nullifier_used(&nullifier));
+        assert!(!client.is_nullifier_used(&commitment, &nullifier));
+    }
+
+    #[test]
+    fn test_expired_swap_is_refundable() {
+        let env = Env::default();
+        let (client, admin, token_a, token_a_admin) = setup(&env);
+        let token_b_id = env.register_stellar_asset_contract(admin.clone());
+        let token_b = token::Client::new(&env, &token_b_id);
+        let token_b_admin = token::StellarAssetClient::new(&env, &token_b_id);
+
+        let maker = Address::generate(&env);
+        let taker = Address::generate(&env);
+        token_a_admin.mint(&maker, &1_000);
+        token_b_admin.mint(&taker, &500);
+
+        let commitment = BytesN::from_array(&env, &[5u8; 32]);
+        let taker_hash = StellarBridgeComplete::hash_address(&env, &taker);
+        let swap_id = client.lock_swap(
+            &maker,
+            &token_a.address,
+            &1_000,
+            &token_b_id,
+            &500,
+            &commitment,
+            &taker_hash,
+        );
+
+        client.match_swap(&taker, &swap_id);
+        assert!(client.try_refund_swap(&swap_id).is_err());
+
+        // Both escrowed legs go back to the parties that put them up
+        env.ledger().with_mut(|l| l.timestamp += SWAP_TIMEOUT);
+        client.refund_swap(&swap_id);
+        assert_eq!(token_a.balance(&maker), 1_000);
+        assert_eq!(token_b.balance(&taker), 500);
+        assert_eq!(client.get_swap(&swap_id).unwrap().status, SwapStatus::Refunded);
+    }
+
+    #[test]
+    fn test_contract_address_matches_registered_id() {
+        let env = Env::default();
+        let (client, _, _, _) = setup(&env);
+        assert_eq!(client.contract_address(), client.address);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_relocked_commitment_uses_fresh_nullifier_domain() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+
+        // Another commitment consumes the nullifier in the first-cycle domain
+        let other = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        client.verify_and_unlock(&relayer, &proof, &other, &nullifier, &recipient);
+
+        let commitment = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&commitment);
+        assert_eq!(client.get_claim_nonce(&commitment), 0);
+
+        assert_eq!(client.relock(&commitment), 1);
+        assert_eq!(client.get_claim_nonce(&commitment), 1);
+        // The refund returned the full 2_000 and the relock took exactly that back
+        assert_eq!(token.balance(&sender), 10_000 - 2 * 2_000);
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Locked
+        );
+
+        // Same nullifier, new cycle: no collision with the earlier claim
+        assert!(client.verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient));
+        let scoped = env.as_contract(&client.address, || {
+            StellarBridgeComplete::scoped_nullifier(&env, &commitment, &nullifier)
+        });
+        assert_ne!(scoped, nullifier);
+        assert!(client.is_nullifier_used(&commitment, &nullifier));
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_nullifier_scopes_do_not_leak() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+
+        let first = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let second = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        let relocked = client.lock_funds(&sender, &2_000, &3u64, &1u32, &None);
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&relocked);
+        client.relock(&relocked);
+
+        // A first-cycle claim spends the nullifier for every first-cycle
+        // commitment, and the getter reads the same key the claim wrote
+        client.verify_and_unlock(&relayer, &proof, &first, &nullifier, &recipient);
+        assert!(client.is_nullifier_used(&first, &nullifier));
+        assert!(client.is_nullifier_used(&second, &nullifier));
+        assert!(client
+            .try_verify_and_unlock(&relayer, &proof, &second, &nullifier, &recipient)
+            .is_err());
+
+        // The relocked commitment's cycle is its own scope
+        assert!(!client.is_nullifier_used(&relocked, &nullifier));
+        client.verify_and_unlock(&relayer, &proof, &relocked, &nullifier, &recipient);
+        assert!(client.is_nullifier_used(&relocked, &nullifier));
+
+        // And spending it there is not visible in the first-cycle scope
+        let fresh = BytesN::from_array(&env, &[4u8; 32]);
+        assert!(!client.is_nullifier_used(&second, &fresh));
+        let scoped = env.as_contract(&client.address, || {
+            StellarBridgeComplete::scoped_nullifier(&env, &relocked, &nullifier)
+        });
+        assert!(!client.is_nullifier_used(&second, &scoped));
+        client.verify_and_unlock(&relayer, &proof, &second, &scoped, &recipient);
+        assert!(client.is_nullifier_used(&second, &scoped));
+    }
+
+    #[test]
+    fn test_sweep_cannot_touch_locked_funds() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        client.lock_funds(&sender, &5_000, &1u64, &1u32, &None);
+
+        // Stray tokens sent straight to the bridge are the only free balance
+        token_admin.mint(&client.address, &300);
+        assert_eq!(client.get_free_balance(), 300);
+
+        let treasury = Address::generate(&env);
+        assert_eq!(
+            client.try_sweep(&admin, &treasury, &301),
+            Err(Ok(BridgeError::WouldUndercollateralize.into()))
+        );
+
+        client.sweep(&admin, &treasury, &300);
+        assert_eq!(token.balance(&treasury), 300);
+        assert_eq!(client.get_free_balance(), 0);
+        assert!(client.solvency_check());
+
+        // Tokens escrowed by an open swap are not free either
+        let maker = Address::generate(&env);
+        token_admin.mint(&maker, &700);
+        let swap_id = client.lock_swap(
+            &maker,
+            &token.address,
+            &700,
+            &Address::generate(&env),
+            &1,
+            &BytesN::from_array(&env, &[5u8; 32]),
+            &BytesN::from_array(&env, &[6u8; 32]),
+        );
+        assert_eq!(client.get_free_balance(), 0);
+        assert_eq!(
+            client.try_sweep(&admin, &treasury, &1),
+            Err(Ok(BridgeError::WouldUndercollateralize.into()))
+        );
+
+        env.ledger().with_mut(|l| l.timestamp += SWAP_TIMEOUT);
+        client.refund_swap(&swap_id);
+        assert_eq!(token.balance(&maker), 700);
+    }
+
+    #[test]
+    fn test_usd_limits_follow_oracle_price() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+        env.ledger().with_mut(|l| l.timestamp = 10_000);
+
+        let oracle_id = env.register_contract(None, MockOracle);
+        let oracle = MockOracleClient::new(&env, &oracle_id);
+        // $10 minimum, $100 maximum, prices valid for an hour
+        client.set_usd_limits(&admin, &oracle_id, &3_600, &1_000, &10_000);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000_000_000);
+
+        // At $1 per token (7 decimals), $10 is 100_000_000 units
+        oracle.set_price(&100, &10_000);
+        assert!(client.try_lock_funds(&sender, &60_000_000, &1u64, &1u32, &None).is_err());
+        assert!(client.try_lock_funds(&sender, &1_100_000_000, &1u64, &1u32, &None).is_err());
+        client.lock_funds(&sender, &100_000_000, &1u64, &1u32, &None);
+
+        // At $2 per token the same 60_000_000 units are worth $12
+        oracle.set_price(&200, &10_000);
+        client.lock_funds(&sender, &60_000_000, &2u64, &1u32, &None);
+        assert!(client.try_lock_funds(&sender, &600_000_000, &3u64, &1u32, &None).is_err());
+
+        // A price older than the staleness window is rejected
+        env.ledger().with_mut(|l| l.timestamp = 10_000 + 3_601);
+        assert!(client.try_lock_funds(&sender, &60_000_000, &3u64, &1u32, &None).is_err());
+    }
+
+    #[test]
+    fn test_usd_limits_reject_overflow() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+        env.ledger().with_mut(|l| l.timestamp = 10_000);
+
+        let oracle_id = env.register_contract(None, MockOracle);
+        let oracle = MockOracleClient::new(&env, &oracle_id);
+        oracle.set_price(&100, &10_000);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000_000_000);
+
+        // A staleness window that runs past u64::MAX
+        client.set_usd_limits(&admin, &oracle_id, &u64::MAX, &1_000, &10_000);
+        assert_eq!(
+            client.try_lock_funds(&sender, &100_000_000, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::ArithmeticOverflow.into()))
+        );
+
+        // A maximum whose token-unit value does not fit in an i128
+        client.set_usd_limits(&admin, &oracle_id, &3_600, &1_000, &(i128::MAX / 10));
+        assert_eq!(
+            client.try_lock_funds(&sender, &100_000_000, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::ArithmeticOverflow.into()))
+        );
+
+        client.set_usd_limits(&admin, &oracle_id, &3_600, &1_000, &10_000);
+        client.lock_funds(&sender, &100_000_000, &1u64, &1u32, &None);
+    }
+
+    #[test]
+    fn test_shutdown_refund_skips_timeout() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        // Before shutdown the emergency path is closed
+        assert!(client.try_shutdown_refund(&commitment).is_err());
+
+        client.shutdown(&admin);
+        assert!(client.is_shut_down());
+        assert!(client.try_lock_funds(&sender, &2_000, &2u64, &1u32, &None).is_err());
+        assert!(client
+            .try_verify_and_unlock(
+                &Address::generate(&env),
+                &Bytes::from_array(&env, &[1u8; 64]),
+                &commitment,
+                &BytesN::from_array(&env, &[2u8; 32]),
+                &BytesN::from_array(&env, &[3u8; 32]),
+            )
+            .is_err());
+
+        // No need to wait out the 7-day timeout
+        client.shutdown_refund(&commitment);
+        assert_eq!(token.balance(&sender), 5_000);
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Refunded
+        );
+        assert_eq!(client.get_total_locked(), 0);
+    }
+
+    #[test]
+    fn test_config_accessors_default_when_unset() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, StellarBridgeComplete);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(StellarBridgeComplete::min_lock_amount(&env), 1_000_000);
+            assert_eq!(StellarBridgeComplete::relayer_fee(&env), 0);
+            assert_eq!(
+                StellarBridgeComplete::admin(&env),
+                Err(BridgeError::NotInitialized)
+            );
+        });
+
+        let (client, admin, _token, _token_admin) = setup(&env);
+        env.as_contract(&client.address, || {
+            assert_eq!(StellarBridgeComplete::min_lock_amount(&env), 1_000);
+            assert_eq!(StellarBridgeComplete::relayer_fee(&env), 10);
+            assert_eq!(StellarBridgeComplete::admin(&env), Ok(admin.clone()));
+        });
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_update_config(&stranger, &Some(5), &None, &None, &None),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+    }
+
+    #[test]
+    fn test_initialized_event_carries_schema_version() {
+        let env = Env::default();
+        let (client, admin, token, _token_admin) = setup(&env);
+
+        let (_, topics, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(contract, _, _)| *contract == client.address)
+            .expect("initialized event");
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap(),
+            Symbol::new(&env, "initialized")
+        );
+        let (event_admin, event_token, version) =
+            <(Address, Address, u32)>::try_from_val(&env, &data).unwrap();
+        assert_eq!(event_admin, admin);
+        assert_eq!(event_token, token.address);
+        assert_eq!(version, events::SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_swap_cannot_squat_lock_commitment() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token_admin.mint(&alice, &10_000);
+        token_admin.mint(&bob, &10_000);
+
+        // Bob opens a swap under alice's next commitment hash
+        let taken = env.as_contract(&client.address, || {
+            StellarBridgeComplete::derive_commitment(&env, &alice, 1, 2_000, None)
+        });
+        let swap_id = client.lock_swap(
+            &bob,
+            &token.address,
+            &1_000,
+            &token.address,
+            &1_000,
+            &taken,
+            &BytesN::from_array(&env, &[4u8; 32]),
+        );
+        assert_ne!(swap_id, taken);
+
+        // Swaps live under sender-scoped ids, so alice's lock still goes through
+        let commitment = client.lock_funds(&alice, &2_000, &1u64, &1u32, &None);
+        assert_eq!(commitment, taken);
+
+        // The same sender cannot open two swaps on one commitment hash
+        assert_eq!(
+            client.try_lock_swap(
+                &bob,
+                &token.address,
+                &1_000,
+                &token.address,
+                &1_000,
+                &taken,
+                &BytesN::from_array(&env, &[4u8; 32]),
+            ),
+            Err(Ok(BridgeError::CommitmentCollision.into()))
+        );
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_unlock_split_across_two_recipients() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        // 10 goes to the fee vault on unlock, leaving 2_000 to split
+        let commitment = client.lock_funds(&sender, &2_010, &1u64, &1u32, &None);
+
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let first = BytesN::from_array(&env, &[3u8; 32]);
+        let second = BytesN::from_array(&env, &[4u8; 32]);
+
+        let short = Vec::from_array(&env, [(first.clone(), 1_500), (second.clone(), 400)]);
+        assert!(client
+            .try_verify_and_unlock_multi(&proof, &commitment, &nullifier, &short)
+            .is_err());
+
+        let split = Vec::from_array(&env, [(first, 1_500), (second, 500)]);
+        assert!(client.verify_and_unlock_multi(&proof, &commitment, &nullifier, &split));
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Claimed
+        );
+        let unlocks = env
+            .events()
+            .all()
+            .iter()
+            .filter(|(contract, topics, _)| {
+                *contract == client.address
+                    && Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap()
+                        == Symbol::new(&env, "unlock_approved")
+            })
+            .count();
+        assert_eq!(unlocks, 2);
+    }
+
+    #[test]
+    fn test_refund_needs_ledger_progress_as_well_as_time() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+        client.set_min_ledgers_elapsed(&admin, &1_000);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        assert_eq!(
+            client.get_commitment(&commitment).ledger_sequence,
+            env.ledger().sequence()
+        );
+
+        // Enough time, too few ledgers
+        env.ledger().with_mut(|l| {
+            l.timestamp += 604_800;
+            l.sequence_number += 999;
+        });
+        assert!(client.try_refund(&commitment).is_err());
+
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        client.refund(&commitment);
+        assert_eq!(token.balance(&sender), 5_000);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_imported_commitments_refund_and_unlock() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+        assert!(client.is_import_open());
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let imported = |hash: &BytesN<32>, sender: &Address| BridgeCommitment {
+            commitment_hash: hash.clone(),
+            sender: sender.clone(),
+            token: token.address.clone(),
+            amount: 3_000,
+            timestamp: 0,
+            ledger_sequence: 0,
+            timeout_period: 604_800,
+            destination_chain: 1,
+            status: CommitmentStatus::Locked,
+        };
+        let first = BytesN::from_array(&env, &[7u8; 32]);
+        let second = BytesN::from_array(&env, &[8u8; 32]);
+        client.import_commitments(
+            &admin,
+            &Vec::from_array(
+                &env,
+                [
+                    (first.clone(), imported(&first, &alice)),
+                    (second.clone(), imported(&second, &bob)),
+                ],
+            ),
+        );
+        // Backing tokens migrate alongside the records
+        token_admin.mint(&client.address, &6_000);
+        assert_eq!(client.get_total_locked(), 6_000);
+        assert!(client.solvency_check());
+
+        client.finalize_import(&admin);
+        assert!(!client.is_import_open());
+        let third = BytesN::from_array(&env, &[9u8; 32]);
+        assert!(client
+            .try_import_commitments(
+                &admin,
+                &Vec::from_array(&env, [(third.clone(), imported(&third, &alice))]),
+            )
+            .is_err());
+
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&first);
+        assert_eq!(token.balance(&alice), 3_000);
+
+        assert!(client.verify_and_unlock(
+            &relayer,
+            &Bytes::from_array(&env, &[1u8; 64]),
+            &second,
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &BytesN::from_array(&env, &[3u8; 32]),
+        ));
+        assert_eq!(client.get_total_locked(), 3_000 - 10);
+    }
+
+    #[test]
+    fn test_native_lock_closes_import() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        assert!(!client.is_import_open());
+    }
+
+    #[test]
+    fn test_get_commitment_amount() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        assert_eq!(
+            client.get_commitment_amount(&commitment),
+            Some(client.get_commitment(&commitment).amount)
+        );
+        assert_eq!(client.get_commitment_amount(&commitment), Some(2_000));
+        assert_eq!(
+            client.get_commitment_amount(&BytesN::from_array(&env, &[0xabu8; 32])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_timeout_change_keeps_in_flight_deadline() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let in_flight = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        // Lengthening the timeout doesn't push back the outstanding deadline
+        client.update_config(&admin, &None, &None, &None, &Some(2 * 604_800));
+        let (_, _, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(contract, topics, _)| {
+                *contract == client.address
+                    && Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap()
+                        == Symbol::new(&env, "refund_timeout_changed")
+            })
+            .expect("refund_timeout_changed event");
+        assert_eq!(
+            <(u64, u64, u32)>::try_from_val(&env, &data).unwrap(),
+            (604_800, 2 * 604_800, 1)
+        );
+
+        let later = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        assert_eq!(client.get_commitment(&in_flight).timeout_period, 604_800);
+        assert_eq!(client.get_commitment(&later).timeout_period, 2 * 604_800);
+
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&in_flight);
+        assert!(client.try_refund(&later).is_err());
+        assert_eq!(token.balance(&sender), 10_000 - 2_000);
+    }
+
+    #[test]
+    fn test_lock_funds_to_checks_destination_address_type() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        client.set_chain_address_type(&admin, &1u32, &ADDRESS_TYPE_SS58);
+
+        let mut ss58 = [7u8; 35];
+        ss58[0] = 0; // Polkadot network prefix
+        let ss58 = Bytes::from_array(&env, &ss58);
+        let evm = Bytes::from_array(&env, &[7u8; 20]);
+
+        // Well-formed, but not the type chain 1 expects
+        assert_eq!(
+            client.try_lock_funds_to(&sender, &2_000, &1u64, &1u32, &evm, &ADDRESS_TYPE_EVM),
+            Err(Ok(BridgeError::InvalidDestinationAddress.into()))
+        );
+        // Right type, malformed address
+        assert_eq!(
+            client.try_lock_funds_to(
+                &sender,
+                &2_000,
+                &1u64,
+                &1u32,
+                &Bytes::from_array(&env, &[7u8; 32]),
+                &ADDRESS_TYPE_SS58
+            ),
+            Err(Ok(BridgeError::InvalidDestinationAddress.into()))
+        );
+        assert_eq!(token.balance(&sender), 10_000);
+
+        let commitment =
+            client.lock_funds_to(&sender, &2_000, &1u64, &1u32, &ss58, &ADDRESS_TYPE_SS58);
+        assert_eq!(
+            client.get_destination(&commitment),
+            Some((ADDRESS_TYPE_SS58, ss58))
+        );
+
+        // Chains without an expected type take any well-formed address
+        client.add_allowed_chain(&admin, &2u32);
+        client.lock_funds_to(&sender, &2_000, &2u64, &2u32, &evm, &ADDRESS_TYPE_EVM);
+        assert_eq!(client.get_total_locked(), 2 * 2_000);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_lock_funds_to_binds_destination() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let destination = Bytes::from_array(&env, &[7u8; 32]);
+        let elsewhere = Bytes::from_array(&env, &[8u8; 32]);
+
+        // The destination is part of the commitment preimage
+        let bound = client.lock_funds_to(
+            &sender,
+            &2_000,
+            &1u64,
+            &1u32,
+            &destination,
+            &ADDRESS_TYPE_RAW32,
+        );
+        let unbound = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        let bound_hash = env.as_contract(&client.address, || {
+            let destination_hash = StellarBridgeComplete::destination_hash(
+                env.clone(),
+                ADDRESS_TYPE_RAW32,
+                destination.clone(),
+            );
+            StellarBridgeComplete::derive_commitment(
+                &env,
+                &sender,
+                1,
+                2_000,
+                Some(&destination_hash),
+            )
+        });
+        assert_eq!(bound, bound_hash);
+        assert_ne!(bound, client.compute_poseidon_commitment(&sender, &2_000, &1u64));
+        assert_eq!(client.get_commitment(&unbound).amount, 2_000);
+
+        // A relayer cannot unlock it to any other recipient
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let redirected = client.destination_hash(&ADDRESS_TYPE_RAW32, &elsewhere);
+        assert_eq!(
+            client.try_verify_and_unlock(&relayer, &proof, &bound, &nullifier, &redirected),
+            Err(Ok(BridgeError::InvalidDestinationAddress.into()))
+        );
+
+        let recipient_hash = client.destination_hash(&ADDRESS_TYPE_RAW32, &destination);
+        assert!(client.verify_and_unlock(&relayer, &proof, &bound, &nullifier, &recipient_hash));
+        assert_eq!(client.get_commitment_status(&bound), Some(CommitmentStatus::Claimed));
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_force_refund_only_for_locked_commitments() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let flagged = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let claimed = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        client.verify_and_unlock(
+            &relayer,
+            &Bytes::from_array(&env, &[1u8; 64]),
+            &claimed,
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &BytesN::from_array(&env, &[3u8; 32]),
+        );
+
+        // No timeout to wait out
+        let reason = Symbol::new(&env, "malformed");
+        client.force_refund(&admin, &flagged, &reason);
+        assert_eq!(token.balance(&sender), 5_000 - 2_000);
+        assert_eq!(
+            client.get_commitment(&flagged).status,
+            CommitmentStatus::Refunded
+        );
+
+        let event = env.events().all().last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &event.1.get_unchecked(0)).unwrap(),
+            Symbol::new(&env, "force_refunded")
+        );
+        assert_eq!(
+            <(BytesN<32>, i128, Symbol)>::try_from_val(&env, &event.2).unwrap(),
+            (flagged.clone(), 2_000, reason.clone())
+        );
+
+        assert!(client.try_force_refund(&admin, &claimed, &reason).is_err());
+        assert!(client.try_force_refund(&admin, &flagged, &reason).is_err());
+        let stranger = Address::generate(&env);
+        assert!(client.try_force_refund(&stranger, &flagged, &reason).is_err());
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_status_shadow_tracks_transitions() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let claimed = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let refunded = client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        assert_eq!(client.get_commitment_status(&claimed), Some(CommitmentStatus::Locked));
+
+        client.verify_and_unlock(
+            &relayer,
+            &Bytes::from_array(&env, &[1u8; 64]),
+            &claimed,
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &BytesN::from_array(&env, &[3u8; 32]),
+        );
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&refunded);
+        client.relock(&refunded);
+
+        for hash in [&claimed, &refunded] {
+            assert_eq!(
+                client.get_commitment_status(hash),
+                Some(client.get_commitment(hash).status)
+            );
+        }
+        assert_eq!(client.get_commitment_status(&claimed), Some(CommitmentStatus::Claimed));
+        assert_eq!(client.get_commitment_status(&refunded), Some(CommitmentStatus::Locked));
+
+        // The shadow alone turns a claimed commitment away, even if the full
+        // record were to say otherwise
+        env.as_contract(&client.address, || {
+            let mut record = StellarBridgeComplete::get_commitment(env.clone(), claimed.clone());
+            record.status = CommitmentStatus::Locked;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Commitment(claimed.clone()), &record);
+        });
+        assert!(client
+            .try_verify_and_unlock(
+                &relayer,
+                &Bytes::from_array(&env, &[1u8; 64]),
+                &claimed,
+                &BytesN::from_array(&env, &[4u8; 32]),
+                &BytesN::from_array(&env, &[3u8; 32]),
+            )
+            .is_err());
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_pause_blocks_locks_and_claims() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+
+        assert!(!client.is_paused());
+        client.set_paused(&admin, &true);
+        assert!(client.is_paused());
+        assert_eq!(
+            client.try_lock_funds(&sender, &2_000, &2u64, &1u32, &None),
+            Err(Ok(BridgeError::ContractPaused.into()))
+        );
+        assert_eq!(
+            client.try_verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient),
+            Err(Ok(BridgeError::ContractPaused.into()))
+        );
+        assert_eq!(token.balance(&sender), 8_000);
+        assert!(!client.is_nullifier_used(&commitment, &nullifier));
+
+        // Only the admin can lift it
+        assert_eq!(
+            client.try_set_paused(&Address::generate(&env), &false),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+
+        client.set_paused(&admin, &false);
+        client.lock_funds(&sender, &2_000, &2u64, &1u32, &None);
+        assert!(client.verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient));
+        assert_eq!(token.balance(&sender), 6_000);
+    }
+
+    #[test]
+    fn test_set_verification_key() {
+        let env = Env::default();
+        let (client, admin, _token, _token_admin) = setup(&env);
+        assert_eq!(client.get_verification_key(), None);
+
+        assert_eq!(
+            client.try_set_verification_key(&admin, &Bytes::new(&env)),
+            Err(Ok(BridgeError::InvalidVerificationKey.into()))
+        );
+        let garbage = Bytes::from_array(&env, &[7u8; 8]);
+        #[cfg(feature = "groth16")]
+        let vk = {
+            assert_eq!(
+                client.try_set_verification_key(&admin, &garbage),
+                Err(Ok(BridgeError::InvalidVerificationKey.into()))
+            );
+            let input = BytesN::from_array(&env, &[1u8; 32]);
+            groth16_proof(&env, [&input; 4]).0
+        };
+        #[cfg(not(feature = "groth16"))]
+        let vk = garbage;
+        assert_eq!(
+            client.try_set_verification_key(&Address::generate(&env), &vk),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+
+        client.set_verification_key(&admin, &vk);
+        assert_eq!(client.get_verification_key(), Some(vk.clone()));
+
+        let event = env.events().all().last().unwrap();
+        let key_hash = BytesN::<32>::try_from_val(&env, &event.2).unwrap();
+        assert_eq!(key_hash, BytesN::from(env.crypto().sha256(&vk)));
+    }
+
+    /// Circuit with `[commitment, nullifier, recipient, domain]` as public
+    /// inputs and one constraint over them, for real Groth16 proofs
+    #[cfg(feature = "groth16")]
+    struct BindingCircuit([ark_bn254::Fr; 4]);
+
+    #[cfg(feature = "groth16")]
+    impl ark_relations::r1cs::ConstraintSynthesizer<ark_bn254::Fr> for BindingCircuit {
+        fn generate_constraints(
+            self,
+            cs: ark_relations::r1cs::ConstraintSystemRef<ark_bn254::Fr>,
+        ) -> ark_relations::r1cs::Result<()> {
+            use ark_relations::lc;
+
+            let mut inputs = alloc::vec::Vec::new();
+            for value in self.0 {
+                inputs.push(cs.new_input_variable(|| Ok(value))?);
+            }
+            let product = cs.new_witness_variable(|| Ok(self.0[0] * self.0[1]))?;
+            cs.enforce_constraint(lc!() + inputs[0], lc!() + inputs[1], lc!() + product)
+        }
+    }
+
+    /// A compressed verification key and proof over the given inputs
+    #[cfg(feature = "groth16")]
+    fn groth16_proof(env: &Env, inputs: [&BytesN<32>; 4]) -> (Bytes, Bytes) {
+        use ark_bn254::{Bn254, Fr};
+        use ark_ff::PrimeField;
+        use ark_groth16::Groth16;
+        use ark_serialize::CanonicalSerialize;
+
+        let mut rng = ark_std::test_rng();
+        let circuit =
+            BindingCircuit(inputs.map(|input| Fr::from_be_bytes_mod_order(&input.to_array())));
+        let pk = Groth16::<Bn254>::generate_random_parameters_with_reduction(
+            BindingCircuit([Fr::from(1u8); 4]),
+            &mut rng,
+        )
+        .unwrap();
+        let proof =
+            Groth16::<Bn254>::create_random_proof_with_reduction(circuit, &pk, &mut rng).unwrap();
+
+        let mut vk_bytes = alloc::vec::Vec::new();
+        pk.vk.serialize_compressed(&mut vk_bytes).unwrap();
+        let mut proof_bytes = alloc::vec::Vec::new();
+        proof.serialize_compressed(&mut proof_bytes).unwrap();
+        (
+            Bytes::from_slice(env, &vk_bytes),
+            Bytes::from_slice(env, &proof_bytes),
+        )
+    }
+
+    #[cfg(feature = "groth16")]
+    #[test]
+    fn test_groth16_proof_unlocks() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+        let domain = client.get_domain_separator();
+        let (vk, proof) = groth16_proof(&env, [&commitment, &nullifier, &recipient, &domain]);
+
+        // No key, no verification
+        assert!(client
+            .try_verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient)
+            .is_err());
+
+        client.set_verification_key(&admin, &vk);
+        assert!(client.verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient));
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Claimed
+        );
+    }
+
+    #[cfg(feature = "groth16")]
+    #[test]
+    fn test_tampered_groth16_proof_rejected() {
+        let env = Env::default();
+        let (client, admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let relayer = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+        let domain = client.get_domain_separator();
+        let (vk, proof) = groth16_proof(&env, [&commitment, &nullifier, &recipient, &domain]);
+        client.set_verification_key(&admin, &vk);
+
+        // Valid proof, different recipient
+        let other_recipient = BytesN::from_array(&env, &[4u8; 32]);
+        assert!(client
+            .try_verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &other_recipient)
+            .is_err());
+
+        // Point A negated: still deserializes, fails the pairing check
+        let mut tampered = proof.clone();
+        tampered.set(31, tampered.get(31).unwrap() ^ 0x80);
+        assert!(client
+            .try_verify_and_unlock(&relayer, &tampered, &commitment, &nullifier, &recipient)
+            .is_err());
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Locked
+        );
+    }
+
+    #[test]
+    fn test_domain_separator_is_per_instance() {
+        let env = Env::default();
+        let (client_a, _, _, _) = setup(&env);
+        let (client_b, _, _, _) = setup(&env);
+
+        assert_eq!(client_a.get_domain_separator(), client_a.get_domain_separator());
+        assert_ne!(client_a.get_domain_separator(), client_b.get_domain_separator());
+
+        // A deployment from before the separator was stored gets the same one
+        let stored = client_a.get_domain_separator();
+        env.as_contract(&client_a.address, || {
+            env.storage().instance().remove(&DataKey::DomainSeparator);
+        });
+        assert_eq!(client_a.get_domain_separator(), stored);
+    }
+
+    #[cfg(feature = "groth16")]
+    #[test]
+    fn test_groth16_proof_bound_to_domain() {
+        let env = Env::default();
+        let (client_a, _, _, _) = setup(&env);
+        let relayer = Address::generate(&env);
+        let (client_b, admin, _token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client_b.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+
+        // Proof built for deployment A, replayed against B
+        let domain_a = client_a.get_domain_separator();
+        let (vk, proof) = groth16_proof(&env, [&commitment, &nullifier, &recipient, &domain_a]);
+        client_b.set_verification_key(&admin, &vk);
+        assert!(client_b
+            .try_verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient)
+            .is_err());
+
+        let domain_b = client_b.get_domain_separator();
+        let (vk, proof) = groth16_proof(&env, [&commitment, &nullifier, &recipient, &domain_b]);
+        client_b.set_verification_key(&admin, &vk);
+        assert!(client_b.verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient));
+    }
+
+    #[test]
+    fn test_short_timeout_refunds() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let token_id = env.register_stellar_asset_contract(admin.clone());
+        let token = token::Client::new(&env, &token_id);
+        let token_admin = token::StellarAssetClient::new(&env, &token_id);
+        let contract_id = env.register_contract(None, StellarBridgeComplete);
+        let client = StellarBridgeCompleteClient::new(&env, &contract_id);
+        client.initialize(&admin, &token_id, &1_000i128, &0i128, &10i128, &Some(3_600));
+        assert_eq!(client.get_timeout_period(), 3_600);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        env.ledger().with_mut(|l| l.timestamp += 3_599);
+        assert!(client.try_refund(&commitment).is_err());
+
+        env.ledger().with_mut(|l| l.timestamp += 1);
+        client.refund(&commitment);
+        assert_eq!(token.balance(&sender), 5_000);
+
+        client.update_config(&admin, &None, &None, &None, &Some(60));
+        assert_eq!(client.get_timeout_period(), 60);
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_unlock_accrues_fee_and_emits_net_amount() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let relayer = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient = BytesN::from_array(&env, &[3u8; 32]);
+        client.verify_and_unlock(&relayer, &proof, &commitment, &nullifier, &recipient);
+
+        // The recipient is credited the remainder on the destination chain
+        let event = env.events().all().last().unwrap();
+        let unlock = events::UnlockApprovedEvent::try_from_val(&env, &event.2).unwrap();
+        assert_eq!(unlock.amount, 1_990);
+
+        // and the fee goes to the vault, not straight to the caller
+        assert_eq!(token.balance(&relayer), 0);
+        assert_eq!(client.get_fee_vault(), 10);
+        assert_eq!(client.get_total_locked(), 1_990);
+        assert!(client.solvency_check());
+
+        client.set_relayer(&admin, &relayer, &true);
+        client.claim_fee_vault(&relayer, &10);
+        assert_eq!(token.balance(&relayer), 10);
+        assert_eq!(client.get_fee_vault(), 0);
+
+        // A fee raised above a commitment's amount cannot be taken from it
+        let small = client.lock_funds(&sender, &1_000, &2u64, &1u32, &None);
+        client.update_config(&admin, &None, &None, &Some(1_001), &None);
+        assert!(client
+            .try_verify_and_unlock(&relayer, &proof, &small, &nullifier, &recipient)
+            .is_err());
+        assert_eq!(token.balance(&relayer), 10);
+        assert_eq!(
+            client.get_commitment(&small).status,
+            CommitmentStatus::Locked
+        );
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_verify_and_claim_pays_recipient() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        assert_eq!(client.get_total_locked(), 2_000);
+
+        let proof = Bytes::from_array(&env, &[1u8; 64]);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        assert_eq!(
+            client.verify_and_claim(&proof, &commitment, &nullifier, &recipient),
+            1_990
+        );
+        assert_eq!(token.balance(&recipient), 1_990);
+        assert_eq!(client.get_total_locked(), 0);
+        assert_eq!(client.get_fee_vault(), 10);
+        assert_eq!(
+            client.get_commitment(&commitment).status,
+            CommitmentStatus::Claimed
+        );
+        assert!(client.solvency_check());
+
+        // The nullifier is spent either way
+        assert!(client
+            .try_verify_and_claim(&proof, &commitment, &nullifier, &recipient)
+            .is_err());
+    }
+
+    #[test]
+    fn test_lock_funds_batch() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let request = |amount: i128, nonce: u64| LockRequest {
+            amount,
+            nonce,
+            destination_chain: 1,
+        };
+
+        let results = client.lock_funds_batch(
+            &sender,
+            &Vec::from_array(&env, [request(2_000, 1), request(3_000, 2), request(1_500, 3)]),
+            &true,
+        );
+        assert_eq!(results.len(), 3);
+        let commitment = |i: u32| results.get(i).unwrap().unwrap();
+        assert_eq!(token.balance(&sender), 10_000 - 6_500);
+        assert_eq!(client.get_total_locked(), 6_500);
+        assert_eq!(client.get_commitment(&commitment(0)).amount, 2_000);
+        assert_eq!(client.get_commitment(&commitment(1)).amount, 3_000);
+        assert_eq!(client.get_commitment(&commitment(2)).amount, 1_500);
+
+        // Same derivation as single locks, so a replayed nonce is caught
+        assert_eq!(
+            client.try_lock_funds(&sender, &2_000, &1u64, &1u32, &None),
+            Err(Ok(BridgeError::DuplicateNonce.into()))
+        );
+
+        // With abort_on_error one bad entry rejects the whole batch before
+        // anything moves
+        assert_eq!(
+            client.try_lock_funds_batch(
+                &sender,
+                &Vec::from_array(&env, [request(2_000, 4), request(2_000, 4)]),
+                &true,
+            ),
+            Err(Ok(BridgeError::DuplicateNonce.into()))
+        );
+        let failing_middle =
+            Vec::from_array(&env, [request(1_000, 5), request(10, 6), request(1_500, 7)]);
+        assert_eq!(
+            client.try_lock_funds_batch(&sender, &failing_middle, &true),
+            Err(Ok(BridgeError::AmountBelowMinimum.into()))
+        );
+        assert_eq!(token.balance(&sender), 10_000 - 6_500);
+        assert_eq!(client.get_total_locked(), 6_500);
+
+        // Without it the bad entry is skipped and the others lock
+        let results = client.lock_funds_batch(&sender, &failing_middle, &false);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.get(1).unwrap(), Err(BridgeError::AmountBelowMinimum));
+        assert_eq!(client.get_commitment(&results.get(0).unwrap().unwrap()).amount, 1_000);
+        assert_eq!(client.get_commitment(&results.get(2).unwrap().unwrap()).amount, 1_500);
+        assert_eq!(client.get_nonce_commitment(&sender, &6u64), None);
+        assert_eq!(token.balance(&sender), 10_000 - 9_000);
+        assert_eq!(client.get_total_locked(), 9_000);
+    }
+
+    #[test]
+    fn test_refund_batch() {
+        let env = Env::default();
+        let (client, _admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let other = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        token_admin.mint(&other, &10_000);
+        let first = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let foreign = client.lock_funds(&other, &2_000, &1u64, &1u32, &None);
+        let last = client.lock_funds(&sender, &3_000, &2u64, &1u32, &None);
+        let batch = Vec::from_array(&env, [first.clone(), foreign.clone(), last.clone()]);
+
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+
+        // With abort_on_error the commitment sender doesn't own rejects it all
+        assert_eq!(
+            client.try_refund_batch(&sender, &batch, &true),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+        assert_eq!(token.balance(&sender), 5_000);
+        assert_eq!(client.get_total_locked(), 7_000);
+
+        // Without it the others are refunded and the failure reported
+        let results = client.refund_batch(&sender, &batch, &false);
+        assert_eq!(
+            results,
+            Vec::from_array(&env, [Ok(2_000), Err(BridgeError::Unauthorized), Ok(3_000)])
+        );
+        assert_eq!(token.balance(&sender), 10_000);
+        assert_eq!(client.get_commitment_status(&first), Some(CommitmentStatus::Refunded));
+        assert_eq!(client.get_commitment_status(&foreign), Some(CommitmentStatus::Locked));
+        assert_eq!(client.get_total_locked(), 2_000);
+
+        // Refunded commitments are reported as processed on a second pass
+        assert_eq!(
+            client.refund_batch(&sender, &Vec::from_array(&env, [last]), &false),
+            Vec::from_array(&env, [Err(BridgeError::CommitmentAlreadyProcessed)])
+        );
+    }
+
+    #[test]
+    fn test_events_decode_into_structs() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap(),
+            Symbol::new(&env, "funds_locked")
+        );
+        assert_eq!(
+            events::FundsLockedEvent::try_from_val(&env, &data).unwrap(),
+            events::FundsLockedEvent {
+                commitment_hash: commitment,
+                sender,
+                token: token.address,
+                amount: 2_000,
+                destination_chain: 1,
+            }
+        );
+
+        client.update_config(&admin, &Some(5_000), &None, &None, &None);
+        let (_, _, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            events::ConfigUpdatedEvent::try_from_val(&env, &data).unwrap(),
+            events::ConfigUpdatedEvent {
+                admin,
+                min_lock_amount: 5_000,
+                max_lock_amount: 0,
+                relayer_fee: 10,
+                timeout_period: 604_800,
+            }
+        );
+    }
+
+    #[cfg(not(feature = "groth16"))]
+    #[test]
+    fn test_user_locked_tracks_locks_and_refunds() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let relayer = Address::generate(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let first = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let second = client.lock_funds(&sender, &3_000, &2u64, &1u32, &None);
+        assert_eq!(client.get_user_locked(&sender), 2_000 + 3_000);
+        assert_eq!(client.get_user_locked(&Address::generate(&env)), 0);
+
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&first);
+        assert_eq!(client.get_user_locked(&sender), 3_000);
+
+        // Fully unwound: the entry is removed, not left at zero
+        client.verify_and_unlock(
+            &relayer,
+            &Bytes::from_array(&env, &[1u8; 64]),
+            &second,
+            &BytesN::from_array(&env, &[2u8; 32]),
+            &BytesN::from_array(&env, &[3u8; 32]),
+        );
+        assert_eq!(client.get_user_locked(&sender), 0);
+        env.as_contract(&client.address, || {
+            assert!(!env
+                .storage()
+                .persistent()
+                .has(&DataKey::UserLocked(sender.clone())));
+        });
+    }
+
+    #[test]
+    fn test_two_step_admin_transfer() {
+        let env = Env::default();
+        let (client, admin, _token, _token_admin) = setup(&env);
+
+        let new_admin = Address::generate(&env);
+        client.propose_admin(&admin, &new_admin);
+        assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+        // Nothing changes until the nominee accepts
+        client.set_paused(&admin, &true);
+        client.accept_admin(&new_admin);
+        assert_eq!(client.get_pending_admin(), None);
+
+        let (_, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get_unchecked(0)).unwrap(),
+            Symbol::new(&env, "admin_transferred")
+        );
+        assert_eq!(Address::try_from_val(&env, &data).unwrap(), new_admin);
+
+        assert_eq!(
+            client.try_set_paused(&admin, &false),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+        client.set_paused(&new_admin, &false);
+    }
+
+    #[test]
+    fn test_accept_admin_requires_nomination() {
+        let env = Env::default();
+        let (client, admin, _token, _token_admin) = setup(&env);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_accept_admin(&stranger),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+
+        client.propose_admin(&admin, &Address::generate(&env));
+        assert_eq!(
+            client.try_accept_admin(&stranger),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+        assert_eq!(
+            client.try_propose_admin(&stranger, &stranger),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+    }
+
+    #[test]
+    fn test_lock_and_refund_two_tokens() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let other_id = env.register_stellar_asset_contract(admin.clone());
+        let other = token::Client::new(&env, &other_id);
+        let other_admin = token::StellarAssetClient::new(&env, &other_id);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &5_000);
+        other_admin.mint(&sender, &5_000);
+
+        // Only the primary token until the admin lists another
+        assert!(!client.is_supported_token(&other_id));
+        assert!(client
+            .try_lock_funds(&sender, &3_000, &2u64, &1u32, &Some(other_id.clone()))
+            .is_err());
+        assert_eq!(other.balance(&sender), 5_000);
+        client.set_supported_token(&admin, &other_id, &true);
+        assert!(client.is_supported_token(&other_id));
+
+        // Naming the primary token explicitly is the same as passing None
+        let primary =
+            client.lock_funds(&sender, &2_000, &1u64, &1u32, &Some(token.address.clone()));
+        let secondary = client.lock_funds(&sender, &3_000, &2u64, &1u32, &Some(other_id.clone()));
+        assert_eq!(client.get_commitment(&primary).token, token.address);
+        assert_eq!(client.get_commitment(&secondary).token, other_id);
+        assert_eq!(client.get_total_locked(), 2_000);
+        assert_eq!(client.get_total_locked_by_token(&token.address), 2_000);
+        assert_eq!(client.get_total_locked_by_token(&other_id), 3_000);
+
+        // Each refund pays out in the asset that was locked
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        client.refund(&primary);
+        client.refund(&secondary);
+        assert_eq!(token.balance(&sender), 5_000);
+        assert_eq!(other.balance(&sender), 5_000);
+        assert_eq!(client.get_total_locked_by_token(&token.address), 0);
+        assert_eq!(client.get_total_locked_by_token(&other_id), 0);
+        assert!(client.solvency_check());
+
+        // Delisting stops new locks only
+        client.set_supported_token(&admin, &other_id, &false);
+        assert!(client
+            .try_lock_funds(&sender, &3_000, &3u64, &1u32, &Some(other_id.clone()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_lock_requires_allowed_destination_chain() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        assert!(client.is_chain_allowed(&POLKADOT_CHAIN_ID));
+        assert!(!client.is_chain_allowed(&7u32));
+
+        assert!(client.try_lock_funds(&sender, &2_000, &1u64, &7u32, &None).is_err());
+        assert_eq!(token.balance(&sender), 10_000);
+
+        client.add_allowed_chain(&admin, &7u32);
+        assert!(client.is_chain_allowed(&7u32));
+        client.lock_funds(&sender, &2_000, &1u64, &7u32, &None);
+
+        client.remove_allowed_chain(&admin, &7u32);
+        assert!(client.try_lock_funds(&sender, &2_000, &2u64, &7u32, &None).is_err());
+        assert_eq!(
+            client.try_add_allowed_chain(&Address::generate(&env), &7u32),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+    }
+
+    #[test]
+    fn test_merkle_zeros_are_empty_subtree_roots() {
+        let mut zero = [0u8; 32];
+        for expected in MERKLE_ZEROS {
+            assert_eq!(zero, expected);
+            zero = bridge_poseidon::hash(&[zero, zero]);
+        }
+    }
+
+    /// Merkle root over `leaves` recomputed from the full bottom level
+    fn naive_merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let mut level = leaves.to_vec();
+        let mut zero = [0u8; 32];
+        for _ in 0..MERKLE_TREE_DEPTH {
+            if level.len() % 2 == 1 {
+                level.push(zero);
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| bridge_poseidon::hash(&[pair[0], pair[1]]))
+                .collect();
+            zero = bridge_poseidon::hash(&[zero, zero]);
+        }
+        level.first().copied().unwrap_or(zero)
+    }
+
+    #[test]
+    fn test_merkle_tree_tracks_locks() {
+        let env = Env::default();
+        let (client, _admin, _token, token_admin) = setup(&env);
+        assert_eq!(
+            client.get_merkle_root(),
+            BytesN::from_array(&env, &naive_merkle_root(&[]))
+        );
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        let mut leaves = alloc::vec::Vec::new();
+        let mut roots = alloc::vec::Vec::new();
+        for nonce in 0..3u64 {
+            let commitment = client.lock_funds(&sender, &2_000, &nonce, &1u32, &None);
+            assert_eq!(client.get_leaf_index(&commitment), nonce as u32);
+            leaves.push(commitment.to_array());
+
+            let root = client.get_merkle_root();
+            assert_eq!(root, BytesN::from_array(&env, &naive_merkle_root(&leaves)));
+            assert!(!roots.contains(&root));
+            roots.push(root);
+        }
+
+        // A refund does not remove the leaf
+        env.ledger().with_mut(|l| l.timestamp += DEFAULT_TIMEOUT_PERIOD);
+        client.refund(&BytesN::from_array(&env, &leaves[0]));
+        assert_eq!(client.get_merkle_root(), roots[2]);
+    }
+
+    // poseidon(0x0707..07, 1000, 42) as printed by an independent BigInt
+    // implementation (scripts/generate_poseidon_constants.js) and asserted by
+    // the circuit's test_commitment_matches_contracts
+    const POSEIDON_VECTOR: [u8; 32] = [
+        0x13, 0x02, 0x08, 0xf8, 0x51, 0x18, 0x3a, 0x27,
+        0x26, 0x8a, 0x56, 0x47, 0x8b, 0x6e, 0x89, 0x16,
+        0x3e, 0xf6, 0xe4, 0xd0, 0x70, 0xea, 0x04, 0x91,
+        0xa1, 0xd1, 0x8f, 0x6f, 0x86, 0x08, 0x26, 0x0e,
+    ];
+
+    #[test]
+    fn test_poseidon_commitment_cross_chain_vector() {
+        let env = Env::default();
+        let sender_hash = BytesN::from_array(&env, &[7u8; 32]);
+        assert_eq!(
+            StellarBridgeComplete::poseidon_commitment(&env, &sender_hash, 1_000, 42),
+            BytesN::from_array(&env, &POSEIDON_VECTOR)
+        );
+
+        let (client, _admin, _token, token_admin) = setup(&env);
+        let sender = Address::generate(&env);
+        let sender_hash = StellarBridgeComplete::hash_address(&env, &sender);
+        assert_eq!(
+            client.compute_poseidon_commitment(&sender, &2_000, &42u64),
+            StellarBridgeComplete::poseidon_commitment(&env, &sender_hash, 2_000, 42)
+        );
+        assert!(client
+            .try_compute_poseidon_commitment(&sender, &0, &42u64)
+            .is_err());
+
+        // A lock records exactly the commitment the circuit constrains
+        token_admin.mint(&sender, &5_000);
+        assert_eq!(
+            client.lock_funds(&sender, &2_000, &42u64, &1u32, &None),
+            client.compute_poseidon_commitment(&sender, &2_000, &42u64)
+        );
+    }
+
+    #[test]
+    fn test_lock_amount_bounds() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        token_admin.mint(&sender, &100_000);
+        assert_eq!(client.get_max_lock_amount(), 0);
+
+        // Below the minimum
+        assert!(client.try_lock_funds(&sender, &999, &1u64, &1u32, &None).is_err());
+
+        // No maximum by default
+        client.lock_funds(&sender, &50_000, &1u64, &1u32, &None);
+
+        client.update_config(&admin, &None, &Some(10_000), &None, &None);
+        assert_eq!(client.get_max_lock_amount(), 10_000);
+        assert!(client.try_lock_funds(&sender, &10_001, &2u64, &1u32, &None).is_err());
+        assert_eq!(token.balance(&sender), 50_000);
+
+        client.lock_funds(&sender, &10_000, &2u64, &1u32, &None);
+        assert_eq!(token.balance(&sender), 40_000);
+
+        // A maximum that would reject every lock is refused
+        assert!(client.try_update_config(&admin, &None, &Some(-1), &None, &None).is_err());
+        assert!(client.try_update_config(&admin, &None, &Some(999), &None, &None).is_err());
+        assert!(client.try_update_config(&admin, &Some(10_001), &None, &None, &None).is_err());
+        assert_eq!(client.get_max_lock_amount(), 10_000);
+        client.update_config(&admin, &Some(10_001), &Some(0), &None, &None);
+        assert_eq!(client.get_max_lock_amount(), 0);
+
+        let contract_id = env.register_contract(None, StellarBridgeComplete);
+        let uninitialized = StellarBridgeCompleteClient::new(&env, &contract_id);
+        assert!(uninitialized
+            .try_initialize(&admin, &token.address, &1_000, &999, &10, &None)
+            .is_err());
+    }
+
+    // Lock 2_000, enable a 1 hour window with a 500 bond and have a
+    // whitelisted relayer propose an unlock of it
+    fn propose_challenged_unlock(
+        env: &Env,
+    ) -> (
+        StellarBridgeCompleteClient<'_>,
+        token::Client<'_>,
+        BytesN<32>,
+        Address,
+        Address,
+    ) {
+        let (client, admin, token, token_admin) = setup(env);
+
+        let sender = Address::generate(env);
+        let relayer = Address::generate(env);
+        let challenger = Address::generate(env);
+        token_admin.mint(&sender, &10_000);
+        token_admin.mint(&relayer, &1_000);
+        token_admin.mint(&challenger, &1_000);
+
+        client.set_challenge_config(&admin, &3_600, &500);
+        client.set_relayer(&admin, &relayer, &true);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+
+        let nullifier = BytesN::from_array(env, &[2u8; 32]);
+        let recipient_hash = BytesN::from_array(env, &[3u8; 32]);
+        client.propose_unlock(&relayer, &commitment, &nullifier, &recipient_hash);
+        assert!(client.is_nullifier_used(&commitment, &nullifier));
+        client.challenge_unlock(&challenger, &commitment);
+        assert_eq!(token.balance(&relayer), 500);
+        assert_eq!(token.balance(&challenger), 500);
+
+        // Bonds are not sweepable, and nothing settles while challenged
+        assert_eq!(client.get_free_balance(), 0);
+        env.ledger().with_mut(|l| l.timestamp += 3_600);
+        assert!(client.try_finalize_unlock(&commitment).is_err());
+
+        (client, token, commitment, relayer, challenger)
+    }
+
+    #[test]
+    fn test_upheld_challenge_cancels_unlock() {
+        let env = Env::default();
+        let (client, token, commitment, relayer, challenger) = propose_challenged_unlock(&env);
+
+        client.resolve_challenge(&commitment, &true);
+
+        // Challenger gets their bond back plus the slashed relayer bond
+        assert_eq!(token.balance(&challenger), 1_500);
+        assert_eq!(token.balance(&relayer), 500);
+        assert_eq!(client.get_pending_unlock(&commitment), None);
+        assert_eq!(client.get_commitment_status(&commitment), Some(CommitmentStatus::Locked));
+        assert!(!client.is_nullifier_used(&commitment, &BytesN::from_array(&env, &[2u8; 32])));
+        assert_eq!(client.get_total_locked(), 2_000);
+        assert_eq!(client.get_fee_vault(), 0);
+        assert!(client.try_resolve_challenge(&commitment, &true).is_err());
+    }
+
+    #[test]
+    fn test_rejected_challenge_forfeits_bond() {
+        let env = Env::default();
+        let (client, token, commitment, relayer, challenger) = propose_challenged_unlock(&env);
+
+        client.resolve_challenge(&commitment, &false);
+
+        // The relayer collects the forfeited bond and the unlock goes through
+        assert_eq!(token.balance(&relayer), 1_500);
+        assert_eq!(token.balance(&challenger), 500);
+        assert_eq!(client.get_commitment_status(&commitment), Some(CommitmentStatus::Claimed));
+        assert!(client.is_nullifier_used(&commitment, &BytesN::from_array(&env, &[2u8; 32])));
+        assert_eq!(client.get_fee_vault(), 10);
+        assert_eq!(client.get_free_balance(), 0);
+        assert!(client.try_refund(&commitment).is_err());
+    }
+
+    #[test]
+    fn test_unchallenged_unlock_finalizes_after_window() {
+        let env = Env::default();
+        let (client, admin, token, token_admin) = setup(&env);
+
+        let sender = Address::generate(&env);
+        let relayer = Address::generate(&env);
+        token_admin.mint(&sender, &10_000);
+        token_admin.mint(&relayer, &1_000);
+        let commitment = client.lock_funds(&sender, &2_000, &1u64, &1u32, &None);
+        let nullifier = BytesN::from_array(&env, &[2u8; 32]);
+        let recipient_hash = BytesN::from_array(&env, &[3u8; 32]);
+
+        // Off until a window is configured, and only for whitelisted relayers
+        assert!(client
+            .try_propose_unlock(&relayer, &commitment, &nullifier, &recipient_hash)
+            .is_err());
+        client.set_challenge_config(&admin, &3_600, &500);
+        assert_eq!(
+            client.try_propose_unlock(&relayer, &commitment, &nullifier, &recipient_hash),
+            Err(Ok(BridgeError::Unauthorized.into()))
+        );
+        client.set_relayer(&admin, &relayer, &true);
+        client.propose_unlock(&relayer, &commitment, &nullifier, &recipient_hash);
+
+        // The sender cannot refund out from under a pending unlock
+        env.ledger().with_mut(|l| l.timestamp += 604_800);
+        assert!(client.try_refund(&commitment).is_err());
+
+        client.finalize_unlock(&commitment);
+        assert_eq!(token.balance(&relayer), 1_000);
+        assert_eq!(client.get_commitment_status(&commitment), Some(CommitmentStatus::Claimed));
+        assert_eq!(client.get_fee_vault(), 10);
     }
 }
@@ -15,13 +15,14 @@ if ! nc -z 127.0.0.1 9944 2>/dev/null; then
 fi
 
 cd "$(dirname "$0")/../contracts/polkadot"
+POSEIDON_DIR="$(cd ../poseidon && pwd)"
 
 # Create a temporary Cargo project for the complete bridge
 TEMP_DIR=$(mktemp -d)
 cp polkadot_bridge_complete.rs "$TEMP_DIR/lib.rs"
 
 # Create Cargo.toml for the complete contract
-cat > "$TEMP_DIR/Cargo.toml" << 'EOF'
+cat > "$TEMP_DIR/Cargo.toml" << EOF
 [package]
 name = "polkadot_bridge_complete"
 version = "0.1.0"
@@ -30,6 +31,7 @@ authors = ["Insidr Team"]
 
 [dependencies]
 ink = { version = "5.0", default-features = false }
+bridge-poseidon = { path = "$POSEIDON_DIR" }
 
 [dev-dependencies]
 ink_e2e = "5.0"
@@ -67,7 +67,9 @@ stellar contract invoke \
     --admin "$STELLAR_PUBLIC_KEY" \
     --token_contract "$TOKEN_CONTRACT" \
     --min_lock_amount 1000000 \
-    --relayer_fee 10000
+    --max_lock_amount 0 \
+    --relayer_fee 10000 \
+    --timeout_period 604800
 
 echo "✅ Contract initialized"
 echo ""